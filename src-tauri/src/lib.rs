@@ -1,8 +1,10 @@
 #![warn(clippy::expect_used)]
 #![warn(clippy::panic)]
 
-use serde_json::Value;
-use std::collections::HashMap;
+use modules::{
+    alerts, audit, backup, excursions, flex_trades, ib_gateway, logging, market_data, portfolio, pricing,
+    reconciliation, seasonality, secrets, sync,
+};
 
 pub mod modules;
 
@@ -12,150 +14,94 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[derive(serde::Serialize)]
-struct MarketQuote {
-    price: f64,
-    change_percent: f64,
-}
-
-/// Fetches market quotes from Yahoo Finance.
-#[tauri::command]
-async fn fetch_market_quotes(symbols: Vec<String>) -> Result<HashMap<String, MarketQuote>, String> {
-    if symbols.is_empty() {
-        return Ok(HashMap::new());
-    }
-
-    let joined_symbols = symbols.join(",");
-    let url = format!(
-        "https://query1.finance.yahoo.com/v7/finance/quote?symbols={}",
-        joined_symbols
-    );
-
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .cookie_store(true)
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let _ = client.get("https://fc.yahoo.com").send().await;
-
-    let crumb_resp = client
-        .get("https://query1.finance.yahoo.com/v1/test/getcrumb")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let crumb = if crumb_resp.status().is_success() {
-        crumb_resp.text().await.map_err(|e| e.to_string())?
-    } else {
-        "".to_string()
-    };
-
-    let resp = client
-        .get(&url)
-        .query(&[
-            ("symbols", joined_symbols.as_str()),
-            ("crumb", crumb.as_str()),
-        ])
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !resp.status().is_success() {
-        return Err(format!(
-            "Yahoo API Error: {}. Crumb used: {}",
-            resp.status(),
-            crumb
-        ));
-    }
-
-    let json: Value = resp.json().await.map_err(|e| e.to_string())?;
-
-    let mut map = HashMap::new();
-
-    if let Some(results) = json["quoteResponse"]["result"].as_array() {
-        for item in results {
-            if let Some(symbol) = item["symbol"].as_str() {
-                let price = item["regularMarketPrice"]
-                    .as_f64()
-                    .or_else(|| item["postMarketPrice"].as_f64());
-
-                let change_percent = item["regularMarketChangePercent"]
-                    .as_f64()
-                    .map_or(0.0, |v| v);
-
-                if let Some(p) = price {
-                    map.insert(
-                        symbol.to_string(),
-                        MarketQuote {
-                            price: p,
-                            change_percent,
-                        },
-                    );
-                }
-            }
-        }
-    }
-
-    Ok(map)
-}
-
 /// Runs the Tauri application.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_sql::Builder::default().build())
+        .plugin(tauri_plugin_notification::init())
+        .manage(modules::alerts::AlertMonitor::default())
+        .manage(modules::market_data::QuoteService::default())
+        .setup(|app| {
+            if let Err(e) = modules::logging::init(app.handle()) {
+                eprintln!("[Logging] {}", e);
+            }
+            // Schéma à jour avant que le frontend n'ouvre trading.db : la fenêtre (create: false
+            // dans tauri.conf.json) n'est créée qu'après les migrations
+            let report = modules::migrations::run_at_startup(app.handle()).map_err(|e| {
+                tracing::error!(target: modules::logging::APP, error = %e, "Migrations failed");
+                e
+            })?;
+            if !report.applied.is_empty() {
+                tracing::info!(
+                    target: modules::logging::APP,
+                    from = report.from_version,
+                    to = report.to_version,
+                    applied = %report.applied.join(", "),
+                    "Schema migrated"
+                );
+            }
+            for window in &app.config().app.windows {
+                tauri::WebviewWindowBuilder::from_config(app.handle(), window)?.build()?;
+            }
+            if let Some(path) = report.backup {
+                let operation = modules::migrations::BACKUP_OPERATION;
+                modules::backup::spawn_copies(app.handle().clone(), path, operation);
+            }
+            modules::alerts::spawn(app.handle().clone());
+            modules::backup::spawn(app.handle().clone());
+            modules::sync::spawn(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
-            fetch_market_quotes,
-            fetch_ib_trades,
-            fetch_flex_trades,
-            parse_flex_trades_csv,
-            fetch_positions,
-            create_backup
+            market_data::commands::fetch_market_quotes,
+            ib_gateway::commands::fetch_ib_trades,
+            flex_trades::commands::fetch_flex_trades,
+            flex_trades::commands::parse_flex_trades_csv,
+            flex_trades::commands::import_flex_trades,
+            audit::commands::get_audit_log,
+            audit::commands::undo_audit_entry,
+            portfolio::commands::fetch_positions,
+            backup::commands::create_backup,
+            backup::commands::list_backups,
+            backup::commands::restore_backup,
+            backup::commands::apply_backup_retention,
+            backup::commands::backup_before,
+            backup::commands::get_backup_settings,
+            backup::commands::set_backup_settings,
+            backup::commands::get_backup_log,
+            seasonality::commands::get_seasonality,
+            pricing::commands::price_option,
+            pricing::commands::implied_volatility,
+            pricing::commands::analyze_option_strategy,
+            portfolio::commands::get_portfolio_risk,
+            portfolio::commands::get_scenario_grid,
+            alerts::commands::scan_assignment_risk,
+            alerts::commands::get_alert_config,
+            alerts::commands::set_alert_config,
+            reconciliation::commands::reconcile_positions,
+            sync::commands::get_sync_settings,
+            sync::commands::set_sync_settings,
+            sync::commands::get_sync_status,
+            sync::commands::run_ib_sync,
+            sync::commands::backfill_flex_history,
+            sync::commands::sync_all_flex,
+            secrets::commands::save_secret,
+            secrets::commands::test_secret,
+            secrets::commands::delete_secret,
+            secrets::commands::list_secrets,
+            secrets::commands::unlock_secret_vault,
+            secrets::commands::lock_secret_vault,
+            secrets::commands::get_secret_vault_status,
+            logging::commands::get_recent_logs,
+            market_data::commands::get_market_data_settings,
+            market_data::commands::set_market_data_settings,
+            excursions::commands::compute_trade_excursions,
+            excursions::commands::get_trade_excursions
         ])
         .run(tauri::generate_context!())
         .map_err(|e| eprintln!("error while running tauri application: {}", e))
         .ok();
 }
-
-/// Commande Tauri: Récupère les trades depuis IB Gateway (ancien - REST)
-#[tauri::command]
-async fn fetch_ib_trades(account_id: String) -> Result<Vec<modules::ib_gateway::IBTrade>, String> {
-    modules::ib_gateway::fetch_ib_trades(account_id).await
-}
-
-/// Commande Tauri: Récupère les positions ouvertes actuelles (NOUVEAU - Socket TCP)
-#[tauri::command]
-async fn fetch_positions() -> Result<Vec<modules::tws_socket::Position>, String> {
-    let config = modules::tws_socket::TWSConfig::default();
-    let client = modules::tws_socket::TWSSyncClient::new(config);
-    client.get_positions().await
-}
-
-/// Commande Tauri: Parse un CSV Flex Query fourni en string (import fichier local)
-#[tauri::command]
-async fn parse_flex_trades_csv(
-    csv_content: String,
-) -> Result<Vec<modules::tws_socket::FlexTrade>, String> {
-    let config = modules::tws_socket::TWSConfig::default();
-    let client = modules::tws_socket::TWSSyncClient::new(config);
-    client.parse_csv_public(csv_content).await
-}
-
-/// Commande Tauri: Récupère l'historique complet via Flex Query (NOUVEAU - Socket TCP + Flex)
-#[tauri::command]
-async fn fetch_flex_trades(
-    flex_token: String,
-    query_id: i32,
-) -> Result<Vec<modules::tws_socket::FlexTrade>, String> {
-    let config = modules::tws_socket::TWSConfig::default();
-    let client = modules::tws_socket::TWSSyncClient::new(config);
-    client.get_flex_trades(&flex_token, query_id).await
-}
-
-#[tauri::command]
-async fn create_backup(app_handle: tauri::AppHandle) -> Result<String, String> {
-    modules::backup::create_backup(app_handle).await
-}
@@ -0,0 +1,73 @@
+// Traçabilité des alertes dans audit_logs (visible dans AuditTrail)
+
+use super::AssignmentAlert;
+use rusqlite::{params, Connection};
+
+const AUDIT_TABLE: &str = "open_positions";
+
+/// Écrit l'alerte dans audit_logs sauf si la même alerte a déjà été tracée aujourd'hui (UTC,
+/// comme CURRENT_TIMESTAMP) ; renvoie true si elle est nouvelle et doit être notifiée
+pub fn record_alert(conn: &Connection, alert: &AssignmentAlert) -> Result<bool, String> {
+    let action = alert.kind.audit_action();
+    let already: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM audit_logs
+             WHERE table_name = ?1 AND record_id = ?2 AND action = ?3 AND date(timestamp) = date('now')",
+            params![AUDIT_TABLE, alert.position_id, action],
+            |r| r.get(0),
+        )
+        .map_err(|e| format!("audit_logs query error: {}", e))?;
+    if already > 0 {
+        return Ok(false);
+    }
+    let payload = serde_json::to_string(alert).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO audit_logs (table_name, record_id, action, new_value) VALUES (?1, ?2, ?3, ?4)",
+        params![AUDIT_TABLE, alert.position_id, action, payload],
+    )
+    .map_err(|e| format!("audit_logs insert error: {}", e))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AlertKind;
+    use super::*;
+    use crate::modules::pricing::OptionKind;
+
+    #[test]
+    fn test_record_alert_once_per_day() {
+        let conn = Connection::open_in_memory().expect("db");
+        conn.execute_batch(
+            "CREATE TABLE audit_logs (id INTEGER PRIMARY KEY AUTOINCREMENT, table_name TEXT, record_id TEXT,
+                action TEXT, old_value TEXT, new_value TEXT, timestamp TEXT DEFAULT CURRENT_TIMESTAMP);",
+        )
+        .expect("schema");
+        let mut alert = AssignmentAlert {
+            kind: AlertKind::ExpiryInTheMoney,
+            position_id: "42".to_string(),
+            account: "Rocket Academy".to_string(),
+            underlying: "KO".to_string(),
+            option_kind: OptionKind::Put,
+            strike: 62.0,
+            expiry: "2025-06-20".to_string(),
+            days_to_expiry: 4,
+            quantity: -1.0,
+            spot: 60.0,
+            distance_pct: -3.2,
+            ex_dividend_date: None,
+            dividend: None,
+            extrinsic: None,
+            message: "KO 62P".to_string(),
+        };
+        assert!(record_alert(&conn, &alert).expect("first"));
+        assert!(!record_alert(&conn, &alert).expect("duplicate"));
+        alert.kind = AlertKind::DividendAssignment;
+        assert!(record_alert(&conn, &alert).expect("other kind"));
+
+        let action: String = conn
+            .query_row("SELECT action FROM audit_logs WHERE id = 1", [], |r| r.get(0))
+            .expect("row");
+        assert_eq!(action, "ALERT_EXPIRY_ITM");
+    }
+}
@@ -0,0 +1,21 @@
+// Commandes Tauri du moniteur de risque d'assignation
+
+use super::{AlertConfig, AlertMonitor, AssignmentAlert};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Scan immédiat du risque d'assignation (échéances proches, ex-dividendes)
+#[tauri::command]
+pub async fn scan_assignment_risk(app_handle: tauri::AppHandle) -> Result<Vec<AssignmentAlert>, AppError> {
+    super::scan_now(&app_handle).await
+}
+
+#[tauri::command]
+pub fn get_alert_config(monitor: tauri::State<'_, AlertMonitor>) -> Result<AlertConfig, AppError> {
+    monitor.config().map_err(AppError::Internal)
+}
+
+/// Commande Tauri: Seuils du moniteur, enregistrés dans settings.json (pris en compte au prochain scan)
+#[tauri::command]
+pub fn set_alert_config(app_handle: tauri::AppHandle, config: AlertConfig) -> Result<(), AppError> {
+    super::save_config(&app_handle, config).map_err(AppError::Internal)
+}
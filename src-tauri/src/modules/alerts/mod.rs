@@ -0,0 +1,254 @@
+// Moniteur d'échéance et de risque d'assignation des options vendues
+// Scan quotidien + intraday : ITM / proche du strike avant l'échéance, détachement de dividende
+// rendant l'exercice anticipé d'un call vendu probable
+
+mod audit;
+pub mod commands;
+mod monitor;
+
+pub use audit::record_alert;
+pub use monitor::{save_config, scan_now, spawn, AlertMonitor, ALERT_EVENT};
+
+use crate::modules::portfolio::{RiskPosition, DEFAULT_VOLATILITY};
+use crate::modules::pricing::{self, ExerciseStyle, OptionKind, OptionParams, DAYS_PER_YEAR};
+use crate::modules::tws_socket;
+use crate::modules::yahoo::DividendInfo;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Section "alerts" de settings.json
+pub const SETTINGS_KEY: &str = "alerts";
+
+/// Seuils du moniteur (modifiables depuis les réglages)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertConfig {
+    /// Fenêtre d'alerte avant l'échéance, en jours
+    #[serde(default = "default_expiry_days")]
+    pub expiry_days: i64,
+    /// Distance au strike (en %) sous laquelle une option OTM est signalée
+    #[serde(default = "default_strike_buffer_pct")]
+    pub strike_buffer_pct: f64,
+    /// Fenêtre de surveillance des dates ex-dividende, en jours
+    #[serde(default = "default_dividend_days")]
+    pub dividend_days: i64,
+    #[serde(default = "default_scan_interval_minutes")]
+    pub scan_interval_minutes: u64,
+    #[serde(default)]
+    pub volatilities: HashMap<String, f64>,
+    /// Surveille aussi les positions TWS
+    #[serde(default = "crate::modules::tws_socket::default_include_tws")]
+    pub include_tws: bool,
+}
+
+fn default_expiry_days() -> i64 {
+    5
+}
+
+fn default_strike_buffer_pct() -> f64 {
+    3.0
+}
+
+fn default_dividend_days() -> i64 {
+    7
+}
+
+fn default_scan_interval_minutes() -> u64 {
+    30
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            expiry_days: default_expiry_days(),
+            strike_buffer_pct: default_strike_buffer_pct(),
+            dividend_days: default_dividend_days(),
+            scan_interval_minutes: default_scan_interval_minutes(),
+            volatilities: HashMap::new(),
+            include_tws: tws_socket::default_include_tws(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    ExpiryInTheMoney,
+    ExpiryNearStrike,
+    DividendAssignment,
+}
+
+impl AlertKind {
+    /// Action écrite dans audit_logs (même registre que CLOSE / DELETE côté frontend)
+    pub fn audit_action(self) -> &'static str {
+        match self {
+            AlertKind::ExpiryInTheMoney => "ALERT_EXPIRY_ITM",
+            AlertKind::ExpiryNearStrike => "ALERT_EXPIRY_NEAR_STRIKE",
+            AlertKind::DividendAssignment => "ALERT_DIVIDEND_ASSIGNMENT",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssignmentAlert {
+    pub kind: AlertKind,
+    pub position_id: String,
+    pub account: String,
+    pub underlying: String,
+    pub option_kind: OptionKind,
+    pub strike: f64,
+    pub expiry: String,
+    pub days_to_expiry: i64,
+    pub quantity: f64,
+    pub spot: f64,
+    pub distance_pct: f64, // coussin OTM en % du strike, négatif = dans la monnaie
+    pub ex_dividend_date: Option<String>,
+    pub dividend: Option<f64>,
+    pub extrinsic: Option<f64>, // valeur temps restante du call après détachement
+    pub message: String,
+}
+
+/// Passe en revue les options vendues ; une position peut lever une alerte d'échéance
+/// et une alerte dividende
+pub fn scan(
+    positions: &[RiskPosition],
+    spots: &HashMap<String, f64>,
+    dividends: &HashMap<String, DividendInfo>,
+    config: &AlertConfig,
+    today: NaiveDate,
+) -> Vec<AssignmentAlert> {
+    let mut alerts = Vec::new();
+    for p in positions.iter().filter(|p| p.quantity < 0.0) {
+        let (Some(kind), Some(expiry), Some(spot)) = (p.kind, p.expiry, spots.get(&p.underlying).copied()) else {
+            continue;
+        };
+        let days = (expiry - today).num_days();
+        if days < 0 || p.strike <= 0.0 {
+            continue;
+        }
+        let distance_pct = match kind {
+            OptionKind::Put => (spot - p.strike) / p.strike * 100.0,
+            OptionKind::Call => (p.strike - spot) / p.strike * 100.0,
+        };
+        let base = |alert_kind, message: String| AssignmentAlert {
+            kind: alert_kind,
+            position_id: p.id.clone(),
+            account: p.account.clone(),
+            underlying: p.underlying.clone(),
+            option_kind: kind,
+            strike: p.strike,
+            expiry: expiry.format("%Y-%m-%d").to_string(),
+            days_to_expiry: days,
+            quantity: p.quantity,
+            spot,
+            distance_pct,
+            ex_dividend_date: None,
+            dividend: None,
+            extrinsic: None,
+            message,
+        };
+        let label = format!("{} {}{}", p.underlying, p.strike, if kind == OptionKind::Put { "P" } else { "C" });
+
+        if days <= config.expiry_days {
+            if distance_pct < 0.0 {
+                let msg = format!("{} expire dans {} j — dans la monnaie (spot {:.2})", label, days, spot);
+                alerts.push(base(AlertKind::ExpiryInTheMoney, msg));
+            } else if distance_pct <= config.strike_buffer_pct {
+                let msg = format!("{} expire dans {} j — à {:.1}% du strike", label, days, distance_pct);
+                alerts.push(base(AlertKind::ExpiryNearStrike, msg));
+            }
+        }
+
+        if kind != OptionKind::Call || distance_pct > config.strike_buffer_pct {
+            continue;
+        }
+        let Some(div) = dividends.get(&p.underlying) else { continue };
+        let until_ex = (div.ex_date - today).num_days();
+        if until_ex < 0 || until_ex > config.dividend_days || div.ex_date > expiry {
+            continue;
+        }
+        // Exercice anticipé rationnel si la valeur temps restante est inférieure au dividende
+        let params = OptionParams {
+            spot,
+            strike: p.strike,
+            time_years: (expiry - div.ex_date).num_days() as f64 / DAYS_PER_YEAR,
+            rate: pricing::DEFAULT_RISK_FREE_RATE,
+            dividend_yield: 0.0,
+            volatility: config.volatilities.get(&p.underlying).copied().unwrap_or(DEFAULT_VOLATILITY),
+        };
+        let extrinsic = pricing::price(OptionKind::Call, ExerciseStyle::European, &params) - (spot - p.strike).max(0.0);
+        if extrinsic < div.amount {
+            let msg = format!(
+                "{} : ex-dividende le {} ({:.2} $) > valeur temps {:.2} $ — assignation anticipée probable",
+                label,
+                div.ex_date.format("%d/%m"),
+                div.amount,
+                extrinsic
+            );
+            let mut alert = base(AlertKind::DividendAssignment, msg);
+            alert.ex_dividend_date = Some(div.ex_date.format("%Y-%m-%d").to_string());
+            alert.dividend = Some(div.amount);
+            alert.extrinsic = Some(extrinsic);
+            alerts.push(alert);
+        }
+    }
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn short(id: &str, kind: OptionKind, strike: f64, expiry: (i32, u32, u32)) -> RiskPosition {
+        RiskPosition {
+            id: id.to_string(),
+            source: "manual".to_string(),
+            account: "Rocket Academy".to_string(),
+            strategy: Some("wheel".to_string()),
+            underlying: "KO".to_string(),
+            kind: Some(kind),
+            strike,
+            expiry: NaiveDate::from_ymd_opt(expiry.0, expiry.1, expiry.2),
+            quantity: -1.0,
+            multiplier: 100.0,
+            entry_price: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_expiry_alerts() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 16).expect("date");
+        let positions = vec![
+            short("itm", OptionKind::Put, 62.0, (2025, 6, 20)),
+            short("near", OptionKind::Put, 59.0, (2025, 6, 20)),
+            short("far", OptionKind::Put, 50.0, (2025, 6, 20)),
+            short("later", OptionKind::Put, 62.0, (2025, 7, 18)),
+        ];
+        let spots = HashMap::from([("KO".to_string(), 60.0)]);
+        let alerts = scan(&positions, &spots, &HashMap::new(), &AlertConfig::default(), today);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].kind, AlertKind::ExpiryInTheMoney);
+        assert_eq!(alerts[0].days_to_expiry, 4);
+        assert_eq!(alerts[1].position_id, "near");
+        assert_eq!(alerts[1].kind, AlertKind::ExpiryNearStrike);
+    }
+
+    #[test]
+    fn test_dividend_assignment_on_deep_itm_call() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 9).expect("date");
+        let div = DividendInfo { ex_date: NaiveDate::from_ymd_opt(2025, 6, 12).expect("date"), amount: 0.51 };
+        let dividends = HashMap::from([("KO".to_string(), div)]);
+        let positions = vec![
+            short("deep", OptionKind::Call, 55.0, (2025, 6, 20)),
+            short("otm", OptionKind::Call, 70.0, (2025, 6, 20)),
+        ];
+        let spots = HashMap::from([("KO".to_string(), 62.0)]);
+        let alerts = scan(&positions, &spots, &dividends, &AlertConfig::default(), today);
+        let dividend: Vec<_> = alerts.iter().filter(|a| a.kind == AlertKind::DividendAssignment).collect();
+        assert_eq!(dividend.len(), 1);
+        assert_eq!(dividend[0].position_id, "deep");
+        assert!(dividend[0].extrinsic.unwrap_or(1.0) < 0.51);
+        // Hors fenêtre d'échéance (11 j) : pas d'alerte ITM
+        assert!(alerts.iter().all(|a| a.kind != AlertKind::ExpiryInTheMoney));
+    }
+}
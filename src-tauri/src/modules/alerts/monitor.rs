@@ -0,0 +1,124 @@
+// Tâche de fond : scan au démarrage puis toutes les `scan_interval_minutes`
+// Dividendes rafraîchis une fois par jour, chaque nouvelle alerte → audit_logs + notification + event
+
+use super::{record_alert, scan, AlertConfig, AssignmentAlert, SETTINGS_KEY};
+use crate::modules::error::AppError;
+use crate::modules::logging::ALERTS;
+use crate::modules::pricing::OptionKind;
+use crate::modules::yahoo::{self, DividendInfo};
+use crate::modules::{db, portfolio, settings};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+/// Event écouté par le frontend (toast / badge sur les positions)
+pub const ALERT_EVENT: &str = "assignment-alert";
+
+/// État partagé (tauri::State) : réglages + cache des dividendes du jour
+#[derive(Default)]
+pub struct AlertMonitor {
+    config: Mutex<AlertConfig>,
+    dividends: Mutex<Option<DividendCache>>,
+}
+
+struct DividendCache {
+    day: NaiveDate,
+    symbols: Vec<String>,
+    data: HashMap<String, DividendInfo>,
+}
+
+impl AlertMonitor {
+    pub fn config(&self) -> Result<AlertConfig, String> {
+        self.config.lock().map(|c| c.clone()).map_err(|e| e.to_string())
+    }
+
+    pub fn set_config(&self, config: AlertConfig) -> Result<(), String> {
+        *self.config.lock().map_err(|e| e.to_string())? = config;
+        Ok(())
+    }
+}
+
+/// Enregistre les seuils dans settings.json puis les applique au prochain scan
+pub fn save_config<R: Runtime>(app: &AppHandle<R>, config: AlertConfig) -> Result<(), String> {
+    settings::save_section(app, SETTINGS_KEY, &config)?;
+    app.state::<AlertMonitor>().set_config(config)
+}
+
+/// Charge les seuils enregistrés puis lance la boucle de surveillance (appelé depuis setup)
+pub fn spawn<R: Runtime>(app: AppHandle<R>) {
+    let loaded = settings::load_section(&app, SETTINGS_KEY).and_then(|c| app.state::<AlertMonitor>().set_config(c));
+    if let Err(e) = loaded {
+        tracing::warn!(target: ALERTS, error = %e, "Alert settings not loaded, using defaults");
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = scan_now(&app).await {
+                tracing::error!(target: ALERTS, error = %e, "Assignment risk scan failed");
+            }
+            let minutes = app
+                .state::<AlertMonitor>()
+                .config()
+                .map(|c| c.scan_interval_minutes)
+                .unwrap_or(30)
+                .max(1);
+            tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+        }
+    });
+}
+
+/// Scan immédiat ; renvoie toutes les alertes actives, seules les nouvelles sont notifiées
+pub async fn scan_now<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<AssignmentAlert>, AppError> {
+    let monitor = app.state::<AlertMonitor>();
+    let config = monitor.config().map_err(AppError::Internal)?;
+    let today = chrono::Local::now().date_naive();
+
+    let positions = portfolio::load_open_positions(app, config.include_tws).await?;
+    if !positions.iter().any(|p| p.kind.is_some() && p.quantity < 0.0) {
+        return Ok(Vec::new());
+    }
+    let spots = portfolio::fetch_spots(app, &positions).await?;
+    let dividends = dividends_for_today(&monitor, &positions, today).await?;
+    let alerts = scan(&positions, &spots, &dividends, &config, today);
+
+    let conn = db::open_connection(app).map_err(AppError::Database)?;
+    for alert in &alerts {
+        if !record_alert(&conn, alert).map_err(AppError::Database)? {
+            continue;
+        }
+        if let Err(e) = app.notification().builder().title("Risque d'assignation").body(&alert.message).show() {
+            tracing::warn!(target: ALERTS, error = %e, "Notification not shown");
+        }
+        if let Err(e) = app.emit(ALERT_EVENT, alert) {
+            tracing::warn!(target: ALERTS, error = %e, "Alert event not emitted");
+        }
+    }
+    Ok(alerts)
+}
+
+/// Les dates ex-dividende bougent peu : une requête Yahoo par jour pour les calls vendus
+/// (refaite si un nouveau sous-jacent apparaît dans la journée)
+async fn dividends_for_today(
+    monitor: &AlertMonitor,
+    positions: &[portfolio::RiskPosition],
+    today: NaiveDate,
+) -> Result<HashMap<String, DividendInfo>, AppError> {
+    let mut symbols: Vec<String> = positions
+        .iter()
+        .filter(|p| p.kind == Some(OptionKind::Call) && p.quantity < 0.0)
+        .map(|p| p.underlying.clone())
+        .collect();
+    symbols.sort();
+    symbols.dedup();
+    if let Some(cache) = monitor.dividends.lock().map_err(|e| AppError::Internal(e.to_string()))?.as_ref() {
+        if cache.day == today && symbols.iter().all(|s| cache.symbols.contains(s)) {
+            return Ok(cache.data.clone());
+        }
+    }
+    let data = yahoo::fetch_dividends(&symbols).await?;
+    let cache = DividendCache { day: today, symbols, data: data.clone() };
+    *monitor.dividends.lock().map_err(|e| AppError::Internal(e.to_string()))? = Some(cache);
+    Ok(data)
+}
@@ -0,0 +1,16 @@
+// Commandes Tauri du journal d'audit
+
+use super::AuditEntry;
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Dernières entrées du journal d'audit (plus récente en premier)
+#[tauri::command]
+pub fn get_audit_log(app_handle: tauri::AppHandle, limit: Option<usize>) -> Result<Vec<AuditEntry>, AppError> {
+    super::recent_entries(&app_handle, limit.unwrap_or(50)).map_err(AppError::Database)
+}
+
+/// Commande Tauri: Annule une modification tracée (refusé si la ligne a changé depuis)
+#[tauri::command]
+pub fn undo_audit_entry(app_handle: tauri::AppHandle, id: i64) -> Result<AuditEntry, AppError> {
+    super::undo_audit_entry(&app_handle, id).map_err(AppError::Database)
+}
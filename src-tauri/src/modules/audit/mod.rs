@@ -0,0 +1,143 @@
+// Journal d'audit des trades (trades, legs, flex_trades, open_positions)
+// Écrit par triggers SQLite : ancienne / nouvelle ligne en JSON, acteur et commande Rust
+// à l'origine de la modification ; chaque entrée peut être annulée individuellement
+
+pub mod commands;
+mod triggers;
+mod undo;
+
+pub use triggers::{install_triggers, AUDITED_TABLES};
+pub use undo::undo_entry;
+
+use crate::modules::db;
+use rusqlite::{params, Connection, Transaction};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+/// Origine d'une modification ; `User` pour les écritures directes de l'UI
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Actor {
+    User,
+    Sync,
+    Import,
+}
+
+impl Actor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Actor::User => "user",
+            Actor::Sync => "sync",
+            Actor::Import => "import",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub table_name: String,
+    pub record_id: String,
+    pub action: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub actor: Option<String>,
+    pub command: Option<String>,
+    pub timestamp: String,
+}
+
+/// Exécute `f` dans `tx` en attribuant ses écritures à `actor` / `command`.
+/// Le contexte est retiré avant le commit : les autres connexions ne le voient jamais
+pub fn with_context<T>(
+    tx: &Transaction,
+    actor: Actor,
+    command: &str,
+    f: impl FnOnce(&Transaction) -> Result<T, String>,
+) -> Result<T, String> {
+    tx.execute(
+        "INSERT OR REPLACE INTO audit_context (id, actor, command) VALUES (1, ?1, ?2)",
+        params![actor.as_str(), command],
+    )
+    .map_err(|e| format!("audit_context error: {}", e))?;
+    let result = f(tx);
+    tx.execute("DELETE FROM audit_context", []).map_err(|e| format!("audit_context error: {}", e))?;
+    result
+}
+
+pub fn recent_entries<R: Runtime>(app_handle: &AppHandle<R>, limit: usize) -> Result<Vec<AuditEntry>, String> {
+    list(&db::open_connection(app_handle)?, limit)
+}
+
+pub fn undo_audit_entry<R: Runtime>(app_handle: &AppHandle<R>, id: i64) -> Result<AuditEntry, String> {
+    undo_entry(&mut db::open_connection(app_handle)?, id)
+}
+
+pub fn list(conn: &Connection, limit: usize) -> Result<Vec<AuditEntry>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM audit_logs ORDER BY id DESC LIMIT ?1", ENTRY_COLUMNS))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([limit as i64], read_entry).map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+const ENTRY_COLUMNS: &str = "id, table_name, record_id, action, old_value, new_value, actor, command, timestamp";
+
+fn read_entry(r: &rusqlite::Row) -> rusqlite::Result<AuditEntry> {
+    Ok(AuditEntry {
+        id: r.get(0)?,
+        table_name: r.get::<_, Option<String>>(1)?.unwrap_or_default(),
+        record_id: r.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        action: r.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        old_value: r.get(4)?,
+        new_value: r.get(5)?,
+        actor: r.get(6)?,
+        command: r.get(7)?,
+        timestamp: r.get::<_, Option<String>>(8)?.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::migrations::{migrate, MIGRATIONS};
+
+    pub(super) fn setup() -> Connection {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("schema");
+        conn
+    }
+
+    #[test]
+    fn test_triggers_record_actor_command_and_action() {
+        let mut conn = setup();
+        conn.execute("INSERT INTO flex_trades (trade_id, symbol, strategy) VALUES ('T1', 'SPY', 'Rockets')", [])
+            .expect("ui insert");
+        let tx = conn.transaction().expect("tx");
+        with_context(&tx, Actor::Sync, "import_flex_trades", |tx| {
+            tx.execute("UPDATE flex_trades SET commission = -1 WHERE trade_id = 'T1'", []).map_err(|e| e.to_string())
+        })
+        .expect("sync update");
+        tx.commit().expect("commit");
+        conn.execute("UPDATE flex_trades SET strategy = 'Wheel' WHERE trade_id = 'T1'", []).expect("override");
+        conn.execute("UPDATE flex_trades SET is_deleted = 1 WHERE trade_id = 'T1'", []).expect("soft delete");
+        conn.execute("UPDATE flex_trades SET notes = notes WHERE trade_id = 'T1'", []).expect("no-op");
+
+        let entries = list(&conn, 10).expect("list");
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> =
+            entries.iter().map(|e| (e.action.as_str(), e.actor.as_deref(), e.command.as_deref())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("SOFT_DELETE", Some("user"), None),
+                ("STRATEGY_OVERRIDE", Some("user"), None),
+                ("UPDATE", Some("sync"), Some("import_flex_trades")),
+                ("INSERT", Some("user"), None),
+            ]
+        );
+        let new: serde_json::Value =
+            serde_json::from_str(entries[2].new_value.as_deref().expect("json")).expect("parse");
+        assert_eq!(new["commission"], serde_json::json!(-1.0));
+        let context: i64 = conn.query_row("SELECT COUNT(*) FROM audit_context", [], |r| r.get(0)).expect("ctx");
+        assert_eq!(context, 0);
+    }
+}
@@ -0,0 +1,83 @@
+// Triggers d'audit sur les tables de trades : chaque INSERT / UPDATE / DELETE écrit la ligne
+// complète (JSON) avant / après dans audit_logs, quelle que soit l'origine de l'écriture
+// À réinstaller (install_triggers) par toute migration qui ajoute une colonne à ces tables
+
+use rusqlite::Transaction;
+
+/// Tables auditées, toutes identifiées par leur colonne `id`
+pub const AUDITED_TABLES: [&str; 4] = ["trades", "legs", "flex_trades", "open_positions"];
+
+/// Acteur / commande posés par with_context ; écriture directe depuis l'UI sinon
+const ACTOR: &str = "COALESCE((SELECT actor FROM audit_context WHERE id = 1), 'user')";
+const COMMAND: &str = "(SELECT command FROM audit_context WHERE id = 1)";
+
+pub fn install_triggers(tx: &Transaction) -> Result<(), String> {
+    for table in AUDITED_TABLES {
+        let columns = columns(tx, table)?;
+        if columns.is_empty() {
+            continue;
+        }
+        tx.execute_batch(&triggers_sql(table, &columns))
+            .map_err(|e| format!("Audit triggers on {}: {}", table, e))?;
+    }
+    Ok(())
+}
+
+pub(super) fn columns(tx: &Transaction, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = tx.prepare("SELECT name FROM pragma_table_info(?1)").map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([table], |r| r.get(0)).map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+/// `json_object('col', <prefix>."col", ...)` — même forme pour les triggers et pour l'annulation
+pub(super) fn row_json(prefix: &str, columns: &[String]) -> String {
+    let pairs: Vec<String> = columns
+        .iter()
+        .map(|c| format!("'{}', {}\"{}\"", c.replace('\'', "''"), prefix, c.replace('"', "\"\"")))
+        .collect();
+    format!("json_object({})", pairs.join(", "))
+}
+
+fn triggers_sql(table: &str, columns: &[String]) -> String {
+    let old = row_json("OLD.", columns);
+    let new = row_json("NEW.", columns);
+    let has = |c: &str| columns.iter().any(|col| col == c);
+    // Annulation logique / changement de stratégie distingués d'une mise à jour ordinaire
+    let mut cases = Vec::new();
+    if has("is_deleted") {
+        cases.push(
+            "WHEN OLD.is_deleted IS NOT NEW.is_deleted THEN \
+             CASE WHEN COALESCE(NEW.is_deleted, 0) = 0 THEN 'RESTORE' ELSE 'SOFT_DELETE' END"
+                .to_string(),
+        );
+    }
+    if has("strategy") {
+        cases.push("WHEN OLD.strategy IS NOT NEW.strategy THEN 'STRATEGY_OVERRIDE'".to_string());
+    }
+    let update_action = if cases.is_empty() {
+        "'UPDATE'".to_string()
+    } else {
+        format!("CASE {} ELSE 'UPDATE' END", cases.join(" "))
+    };
+    let insert_log = |action: &str, record: &str, old_value: &str, new_value: &str| {
+        format!(
+            "INSERT INTO audit_logs (table_name, record_id, action, old_value, new_value, actor, command)
+             VALUES ('{}', CAST({}.id AS TEXT), {}, {}, {}, {}, {});",
+            table, record, action, old_value, new_value, ACTOR, COMMAND
+        )
+    };
+    format!(
+        "DROP TRIGGER IF EXISTS audit_{t}_insert;
+         DROP TRIGGER IF EXISTS audit_{t}_update;
+         DROP TRIGGER IF EXISTS audit_{t}_delete;
+         CREATE TRIGGER audit_{t}_insert AFTER INSERT ON {t} BEGIN {ins} END;
+         CREATE TRIGGER audit_{t}_update AFTER UPDATE ON {t} WHEN {old} IS NOT {new} BEGIN {upd} END;
+         CREATE TRIGGER audit_{t}_delete AFTER DELETE ON {t} BEGIN {del} END;",
+        t = table,
+        old = old,
+        new = new,
+        ins = insert_log("'INSERT'", "NEW", "NULL", &new),
+        upd = insert_log(&update_action, "NEW", &old, &new),
+        del = insert_log("'DELETE'", "OLD", &old, "NULL"),
+    )
+}
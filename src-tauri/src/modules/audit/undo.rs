@@ -0,0 +1,169 @@
+// Annulation d'une entrée d'audit : la ligne revient à son état d'avant la modification
+// Refusée si la ligne a changé depuis (annuler d'abord les modifications plus récentes) ;
+// l'annulation est elle-même auditée (actor user, command undo_audit_entry)
+
+use super::triggers::{columns, row_json, AUDITED_TABLES};
+use super::{read_entry, with_context, Actor, AuditEntry, ENTRY_COLUMNS};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection, OptionalExtension, Transaction};
+use serde_json::{Map, Value};
+
+const UNDO_COMMAND: &str = "undo_audit_entry";
+
+/// Annule l'entrée `id` et renvoie l'entrée annulée
+pub fn undo_entry(conn: &mut Connection, id: i64) -> Result<AuditEntry, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let entry = tx
+        .query_row(&format!("SELECT {} FROM audit_logs WHERE id = ?1", ENTRY_COLUMNS), [id], read_entry)
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Audit entry {} not found", id))?;
+    // Entrées antérieures aux triggers (écrites à la main par l'UI, alertes) : état incomplet
+    if !AUDITED_TABLES.contains(&entry.table_name.as_str()) || entry.actor.is_none() {
+        return Err(format!("Audit entry {} ({}) cannot be undone", id, entry.action));
+    }
+
+    with_context(&tx, Actor::User, UNDO_COMMAND, |tx| revert(tx, &entry))?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(entry)
+}
+
+fn revert(tx: &Transaction, entry: &AuditEntry) -> Result<(), String> {
+    let table = entry.table_name.as_str();
+    let table_columns = columns(tx, table)?;
+    let current = current_row(tx, table, &table_columns, &entry.record_id)?;
+    let old = parse(entry.old_value.as_deref())?;
+    let new = parse(entry.new_value.as_deref())?;
+    let changed_since = |expected: &Option<Map<String, Value>>| match (expected, &current) {
+        (Some(expected), Some(current)) => expected.iter().any(|(k, v)| current.get(k).is_some_and(|c| c != v)),
+        (None, None) => false,
+        _ => true,
+    };
+    if changed_since(&new) {
+        return Err(format!(
+            "{} #{} changed after audit entry {}; undo the newer changes first",
+            table, entry.record_id, entry.id
+        ));
+    }
+
+    match (old, entry.action.as_str()) {
+        // Annulation d'un INSERT : suppression
+        (None, "INSERT") => {
+            tx.execute(&format!("DELETE FROM \"{}\" WHERE CAST(id AS TEXT) = ?1", table), [&entry.record_id])
+                .map_err(|e| e.to_string())?;
+        }
+        // Annulation d'un DELETE : réinsertion à l'identique (même id)
+        (Some(old), "DELETE") => {
+            let (names, values) = known_columns(&old, &table_columns);
+            let placeholders = vec!["?"; names.len()].join(", ");
+            let sql = format!("INSERT INTO \"{}\" ({}) VALUES ({})", table, names.join(", "), placeholders);
+            tx.execute(&sql, params_from_iter(values)).map_err(|e| e.to_string())?;
+        }
+        // UPDATE / SOFT_DELETE / RESTORE / STRATEGY_OVERRIDE : anciennes valeurs remises
+        (Some(old), _) if new.is_some() => {
+            let (names, mut values) = known_columns(&old, &table_columns);
+            let assignments: Vec<String> = names.iter().map(|n| format!("{} = ?", n)).collect();
+            values.push(SqlValue::Text(entry.record_id.clone()));
+            let sql = format!("UPDATE \"{}\" SET {} WHERE CAST(id AS TEXT) = ?", table, assignments.join(", "));
+            tx.execute(&sql, params_from_iter(values)).map_err(|e| e.to_string())?;
+        }
+        _ => return Err(format!("Audit entry {} ({}) cannot be undone", entry.id, entry.action)),
+    }
+    Ok(())
+}
+
+fn current_row(
+    tx: &Transaction,
+    table: &str,
+    table_columns: &[String],
+    record_id: &str,
+) -> Result<Option<Map<String, Value>>, String> {
+    let sql = format!("SELECT {} FROM \"{}\" WHERE CAST(id AS TEXT) = ?1", row_json("", table_columns), table);
+    let json: Option<String> = tx.query_row(&sql, [record_id], |r| r.get(0)).optional().map_err(|e| e.to_string())?;
+    parse(json.as_deref())
+}
+
+fn parse(json: Option<&str>) -> Result<Option<Map<String, Value>>, String> {
+    match json.map(serde_json::from_str::<Value>) {
+        None => Ok(None),
+        Some(Ok(Value::Object(map))) => Ok(Some(map)),
+        Some(_) => Err("Audit entry does not contain a row snapshot".to_string()),
+    }
+}
+
+/// Colonnes du snapshot encore présentes dans la table (noms issus de pragma_table_info)
+fn known_columns(row: &Map<String, Value>, table_columns: &[String]) -> (Vec<String>, Vec<SqlValue>) {
+    table_columns
+        .iter()
+        .filter_map(|c| row.get(c).map(|v| (format!("\"{}\"", c.replace('"', "\"\"")), to_sql(v))))
+        .unzip()
+}
+
+fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => n.as_i64().map(SqlValue::Integer).unwrap_or_else(|| SqlValue::Real(n.as_f64().unwrap_or(0.0))),
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::list;
+    use super::super::tests::setup;
+    use super::*;
+
+    fn strategy(conn: &Connection) -> Option<String> {
+        conn.query_row("SELECT strategy FROM flex_trades WHERE trade_id = 'T1'", [], |r| r.get(0)).optional().expect("row")
+    }
+
+    #[test]
+    fn test_undo_update_then_insert() {
+        let mut conn = setup();
+        conn.execute("INSERT INTO flex_trades (trade_id, symbol, strategy) VALUES ('T1', 'SPY', 'Rockets')", [])
+            .expect("insert");
+        conn.execute("UPDATE flex_trades SET strategy = 'Wheel' WHERE trade_id = 'T1'", []).expect("override");
+        let entries = list(&conn, 10).expect("list");
+        let (override_id, insert_id) = (entries[0].id, entries[1].id);
+
+        // L'INSERT ne peut pas être annulé tant que la modification suivante existe
+        assert!(undo_entry(&mut conn, insert_id).expect_err("newer change").contains("newer"));
+        undo_entry(&mut conn, override_id).expect("undo override");
+        assert_eq!(strategy(&conn).as_deref(), Some("Rockets"));
+
+        let undo = &list(&conn, 1).expect("list")[0];
+        assert_eq!((undo.action.as_str(), undo.command.as_deref()), ("STRATEGY_OVERRIDE", Some(UNDO_COMMAND)));
+        undo_entry(&mut conn, insert_id).expect("undo insert");
+        assert_eq!(strategy(&conn), None);
+    }
+
+    #[test]
+    fn test_undo_delete_restores_same_row() {
+        let mut conn = setup();
+        conn.execute(
+            "INSERT INTO open_positions (id, strategy, symbol, side, quantity, price, open_date)
+             VALUES ('p1', 'Wheel', 'KO', 'SELL', -1, 1.2, '2025-01-02')",
+            [],
+        )
+        .expect("insert");
+        conn.execute("DELETE FROM open_positions WHERE id = 'p1'", []).expect("delete");
+        let delete_id = list(&conn, 1).expect("list")[0].id;
+        undo_entry(&mut conn, delete_id).expect("undo delete");
+        let price: f64 = conn.query_row("SELECT price FROM open_positions WHERE id = 'p1'", [], |r| r.get(0)).expect("row");
+        assert_eq!(price, 1.2);
+    }
+
+    #[test]
+    fn test_legacy_entries_are_refused() {
+        let mut conn = setup();
+        conn.execute(
+            "INSERT INTO audit_logs (table_name, record_id, action, old_value) VALUES ('trades', '1', 'DELETE', '{}')",
+            [],
+        )
+        .expect("legacy");
+        let id = conn.last_insert_rowid();
+        assert!(undo_entry(&mut conn, id).is_err());
+    }
+}
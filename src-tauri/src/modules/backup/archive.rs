@@ -0,0 +1,261 @@
+// Archive de sauvegarde .tvbak : zip (deflate) contenant trading.db, manifest.json et les
+// fichiers de configuration ; chiffrée en option par une clé dérivée de la phrase de passe
+// Format chiffré : MAGIC | sel (16) | nonce (24) | XChaCha20-Poly1305(zip), en-tête authentifié
+
+use super::snapshot::{copy_verified, sha256_file, write_manifest, BackupManifest};
+use crate::modules::settings::SETTINGS_FILE;
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const ARCHIVE_EXTENSION: &str = "tvbak";
+
+/// Fichiers de app_config_dir embarqués s'ils existent. Les profils de connexion y figurent : query
+/// Flex de chaque profil (section "sync") et index des secrets ; les valeurs secrètes restent dans
+/// le trousseau / coffre et ne sont pas sauvegardées
+pub const BUNDLED_FILES: [&str; 1] = [SETTINGS_FILE];
+
+const ENCRYPTED_MAGIC: &[u8; 8] = b"TVBKENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const DB_ENTRY: &str = "trading.db";
+const MANIFEST_ENTRY: &str = "manifest.json";
+/// Champs secrets encore présents dans des réglages anciens (migration vers le magasin de secrets
+/// en attente) : jamais copiés dans une archive
+const SECRET_SETTINGS_FIELDS: [&str; 2] = ["secret_key", "flex_token"];
+
+/// Contenu déchiffré et vérifié d'une archive
+#[derive(Debug)]
+pub struct ArchiveContents {
+    pub manifest: BackupManifest,
+    pub db: Vec<u8>,
+    pub extras: Vec<(String, Vec<u8>)>,
+}
+
+/// Crée `dest` depuis la base active et écrit le manifeste externe à côté
+pub fn create(db_path: &Path, dest: &Path, config_dir: &Path, passphrase: Option<&str>) -> Result<BackupManifest, String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    let staging = dest.with_extension("db.tmp");
+    let copied = copy_verified(db_path, &staging)
+        .and_then(|m| fs::read(&staging).map(|db| (m, db)).map_err(|e| e.to_string()));
+    let _ = fs::remove_file(&staging);
+    let (mut manifest, db) = copied?;
+
+    let extras: Vec<(String, Vec<u8>)> = BUNDLED_FILES
+        .iter()
+        .filter_map(|name| fs::read(config_dir.join(name)).ok().map(|bytes| (name.to_string(), bytes)))
+        .map(|(name, bytes)| {
+            let bytes = if name == SETTINGS_FILE { without_secrets(bytes)? } else { bytes };
+            Ok((name, bytes))
+        })
+        .collect::<Result<_, String>>()?;
+    manifest.file_name = dest.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    manifest.encrypted = passphrase.is_some();
+    manifest.bundled_files = extras.iter().map(|(name, _)| name.clone()).collect();
+
+    let zip = build_zip(&db, &manifest, &extras)?;
+    let bytes = match passphrase {
+        Some(p) => encrypt(&zip, p)?,
+        None => zip,
+    };
+    let result = fs::write(dest, bytes)
+        .map_err(|e| e.to_string())
+        .and_then(|_| sha256_file(dest))
+        .and_then(|sha| {
+            manifest.archive_sha256 = Some(sha);
+            write_manifest(dest, &manifest)
+        });
+    if let Err(e) = result {
+        let _ = fs::remove_file(dest);
+        return Err(e);
+    }
+    Ok(manifest)
+}
+
+/// Lit une archive ; la base extraite doit correspondre à l'empreinte du manifeste interne
+pub fn read(path: &Path, passphrase: Option<&str>) -> Result<ArchiveContents, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let zip = if bytes.starts_with(ENCRYPTED_MAGIC) {
+        let passphrase = passphrase.filter(|p| !p.is_empty()).ok_or("Passphrase required for encrypted backup")?;
+        decrypt(&bytes, passphrase)?
+    } else {
+        bytes
+    };
+
+    let mut archive = ZipArchive::new(Cursor::new(zip)).map_err(|e| format!("Invalid backup archive: {}", e))?;
+    let manifest: BackupManifest = serde_json::from_slice(&read_entry(&mut archive, MANIFEST_ENTRY)?)
+        .map_err(|e| format!("Invalid manifest: {}", e))?;
+    let db = read_entry(&mut archive, DB_ENTRY)?;
+    if format!("{:x}", Sha256::digest(&db)) != manifest.sha256 {
+        return Err(format!("Backup checksum mismatch: {}", manifest.file_name));
+    }
+    let extras = manifest
+        .bundled_files
+        .iter()
+        .filter(|name| BUNDLED_FILES.contains(&name.as_str()))
+        .map(|name| read_entry(&mut archive, name).map(|bytes| (name.clone(), bytes)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ArchiveContents { manifest, db, extras })
+}
+
+pub fn is_encrypted(path: &Path) -> bool {
+    let mut header = [0u8; 8];
+    fs::File::open(path).and_then(|mut f| f.read_exact(&mut header)).is_ok() && &header == ENCRYPTED_MAGIC
+}
+
+/// settings.json sans champ secret ; réécrit seulement si un champ a été retiré
+fn without_secrets(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    fn strip(value: &mut Value) -> bool {
+        let mut removed = false;
+        match value {
+            Value::Object(map) => {
+                let before = map.len();
+                map.retain(|key, _| !SECRET_SETTINGS_FIELDS.contains(&key.as_str()));
+                removed = map.len() != before;
+                for child in map.values_mut() {
+                    removed |= strip(child);
+                }
+            }
+            Value::Array(items) => {
+                for child in items {
+                    removed |= strip(child);
+                }
+            }
+            _ => {}
+        }
+        removed
+    }
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else { return Ok(bytes) };
+    if strip(&mut value) {
+        serde_json::to_vec_pretty(&value).map_err(|e| format!("settings.json serialization error: {}", e))
+    } else {
+        Ok(bytes)
+    }
+}
+
+fn build_zip(db: &[u8], manifest: &BackupManifest, extras: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let manifest_json = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    let entries = [(MANIFEST_ENTRY, manifest_json.as_slice()), (DB_ENTRY, db)]
+        .into_iter()
+        .chain(extras.iter().map(|(name, bytes)| (name.as_str(), bytes.as_slice())));
+    for (name, bytes) in entries {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(bytes).map_err(|e| e.to_string())?;
+    }
+    Ok(zip.finish().map_err(|e| e.to_string())?.into_inner())
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Result<Vec<u8>, String> {
+    let mut file = archive.by_name(name).map_err(|_| format!("Missing {} in backup archive", name))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation error: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, &salt)?.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut out = Vec::with_capacity(ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN + plaintext.len() + 16);
+    out.extend_from_slice(ENCRYPTED_MAGIC);
+    out.extend_from_slice(&salt);
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: plaintext, aad: &out })
+        .map_err(|_| "Backup encryption failed".to_string())?;
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn decrypt(bytes: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let header_len = ENCRYPTED_MAGIC.len() + SALT_LEN;
+    if bytes.len() < header_len + NONCE_LEN {
+        return Err("Truncated encrypted backup".to_string());
+    }
+    let (header, rest) = bytes.split_at(header_len);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, &header[ENCRYPTED_MAGIC.len()..])?.into());
+    cipher
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+        .map_err(|_| "Wrong passphrase or corrupted backup".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup() -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db_path = dir.path().join("trading.db");
+        let conn = Connection::open(&db_path).expect("db");
+        conn.execute_batch(
+            "CREATE TABLE trades (id INTEGER PRIMARY KEY, symbol TEXT);
+             INSERT INTO trades (symbol) VALUES ('SPY');",
+        )
+        .expect("schema");
+        fs::write(dir.path().join("settings.json"), br#"{"theme":"dark"}"#).expect("settings");
+        (dir, db_path)
+    }
+
+    #[test]
+    fn test_encrypted_archive_round_trip() {
+        let (dir, db_path) = setup();
+        let dest = dir.path().join("trading_backup_1.tvbak");
+        let manifest = create(&db_path, &dest, dir.path(), Some("correct horse")).expect("create");
+        assert!(manifest.encrypted && is_encrypted(&dest));
+        assert_eq!(manifest.bundled_files, vec!["settings.json".to_string()]);
+        assert!(!dest.with_extension("db.tmp").exists());
+
+        assert!(read(&dest, None).is_err());
+        assert!(read(&dest, Some("wrong")).expect_err("wrong").contains("passphrase"));
+        let contents = read(&dest, Some("correct horse")).expect("read");
+        assert_eq!(contents.manifest.row_counts.get("trades"), Some(&1));
+        assert_eq!(contents.extras[0].1, br#"{"theme":"dark"}"#.to_vec());
+    }
+
+    #[test]
+    fn test_plain_archive_is_compressed_zip() {
+        let (dir, db_path) = setup();
+        let dest = dir.path().join("trading_backup_2.tvbak");
+        let manifest = create(&db_path, &dest, dir.path(), None).expect("create");
+        assert!(!manifest.encrypted && !is_encrypted(&dest));
+        assert!(fs::metadata(&dest).expect("meta").len() < manifest.size_bytes);
+        assert!(read(&dest, None).is_ok());
+    }
+
+    #[test]
+    fn test_legacy_secrets_are_not_bundled() {
+        let (dir, db_path) = setup();
+        fs::write(
+            dir.path().join("settings.json"),
+            br#"{"sync":{"flex_token":"FT","query_id":1},"backup":{"destinations":[{"type":"s3","secret_key":"SK"}]}}"#,
+        )
+        .expect("settings");
+        let dest = dir.path().join("trading_backup_3.tvbak");
+        create(&db_path, &dest, dir.path(), None).expect("create");
+        let settings = String::from_utf8(read(&dest, None).expect("read").extras[0].1.clone()).expect("utf8");
+        assert!(!settings.contains("FT") && !settings.contains("SK"));
+        assert!(settings.contains("\"query_id\": 1"));
+    }
+}
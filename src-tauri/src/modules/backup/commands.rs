@@ -0,0 +1,59 @@
+// Commandes Tauri des sauvegardes : création, restauration, rétention, planification et journal
+
+use super::{BackupEntry, BackupLogEntry, BackupSchedule, RestoreReport, RetentionPolicy, RetentionReport};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Archive compressée de la base + profils + réglages (chiffrée si passphrase)
+#[tauri::command]
+pub async fn create_backup(app_handle: tauri::AppHandle, passphrase: Option<String>) -> Result<String, AppError> {
+    super::create_backup(app_handle, passphrase).await.map_err(AppError::Backup)
+}
+
+/// Commande Tauri: Sauvegardes disponibles (plus récente en premier) avec leur manifeste
+#[tauri::command]
+pub fn list_backups(app_handle: tauri::AppHandle) -> Result<Vec<BackupEntry>, AppError> {
+    super::list_backups(&app_handle).map_err(AppError::Backup)
+}
+
+/// Commande Tauri: Applique la rétention GFS (politique par défaut si absente) et liste les suppressions
+#[tauri::command]
+pub fn apply_backup_retention(
+    app_handle: tauri::AppHandle,
+    policy: Option<RetentionPolicy>,
+) -> Result<RetentionReport, AppError> {
+    super::apply_retention(&app_handle, &policy.unwrap_or_default()).map_err(AppError::Backup)
+}
+
+/// Commande Tauri: Restaure une sauvegarde (copie de sécurité préalable, rollback si échec)
+#[tauri::command]
+pub async fn restore_backup(
+    app_handle: tauri::AppHandle,
+    id: String,
+    passphrase: Option<String>,
+) -> Result<RestoreReport, AppError> {
+    super::restore_backup(app_handle, id, passphrase).await.map_err(AppError::Backup)
+}
+
+/// Commande Tauri: Sauvegarde obligatoire avant une opération destructive (l'annuler si erreur)
+#[tauri::command]
+pub async fn backup_before(app_handle: tauri::AppHandle, operation: String) -> Result<String, AppError> {
+    super::backup_before(&app_handle, &operation).await.map_err(AppError::Backup)
+}
+
+/// Commande Tauri: Planification (démarrage, quotidienne) et destinations de copie
+/// Les clés S3 ne sont jamais renvoyées : seulement `secret_key_configured`
+#[tauri::command]
+pub fn get_backup_settings(app_handle: tauri::AppHandle) -> Result<BackupSchedule, AppError> {
+    super::load_schedule(&app_handle).map_err(AppError::Backup)
+}
+
+#[tauri::command]
+pub fn set_backup_settings(app_handle: tauri::AppHandle, settings: BackupSchedule) -> Result<(), AppError> {
+    super::save_schedule(&app_handle, &settings).map_err(AppError::Validation)
+}
+
+/// Commande Tauri: Journal des sauvegardes et copies (plus récent en premier)
+#[tauri::command]
+pub fn get_backup_log(app_handle: tauri::AppHandle, limit: Option<usize>) -> Result<Vec<BackupLogEntry>, AppError> {
+    super::backup_log(&app_handle, limit.unwrap_or(100)).map_err(AppError::Backup)
+}
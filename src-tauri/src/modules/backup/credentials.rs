@@ -0,0 +1,94 @@
+// Clés secrètes des destinations S3 : dans le magasin de secrets (profil = nom de la destination),
+// jamais dans settings.json ni renvoyées à la webview ; celles encore en clair dans les réglages
+// (anciennes versions) y sont déplacées au chargement
+
+use super::mirror::Destination;
+use super::schedule::{BackupSchedule, SETTINGS_KEY};
+use crate::modules::logging::BACKUP;
+use crate::modules::secrets::{self, SecretKind};
+use crate::modules::settings;
+use tauri::{AppHandle, Runtime};
+
+fn s3_names(destinations: &[Destination]) -> impl Iterator<Item = &str> {
+    destinations.iter().filter(|d| matches!(d, Destination::S3 { .. })).map(Destination::name)
+}
+
+/// Enregistre les clés présentes et les retire des réglages ; vrai si une clé a été déplacée
+fn store_secrets<R: Runtime>(app: &AppHandle<R>, destinations: &mut [Destination]) -> Result<bool, String> {
+    let mut stored = false;
+    for destination in destinations.iter_mut() {
+        if let Destination::S3 { name, secret_key, secret_key_configured, .. } = destination {
+            if !secret_key.is_empty() {
+                secrets::save(app, name, SecretKind::S3SecretKey, secret_key)?;
+                secret_key.clear();
+                *secret_key_configured = true;
+                stored = true;
+            }
+        }
+    }
+    Ok(stored)
+}
+
+/// Au chargement : settings.json réécrit sans les clés une fois celles-ci enregistrées
+pub(super) fn migrate_legacy_secrets<R: Runtime>(app: &AppHandle<R>, schedule: &mut BackupSchedule) {
+    match store_secrets(app, &mut schedule.destinations) {
+        Ok(true) => match settings::save_section(app, SETTINGS_KEY, schedule) {
+            Ok(()) => tracing::info!(target: BACKUP, "S3 secret keys moved from settings.json to the secret store"),
+            Err(e) => tracing::warn!(target: BACKUP, error = %e, "Backup settings not rewritten"),
+        },
+        Ok(false) => {}
+        // Clé gardée en clair (pas de trousseau, coffre verrouillé) : nouvel essai au prochain chargement
+        Err(e) => tracing::warn!(target: BACKUP, error = %e, "S3 secret key migration postponed"),
+    }
+}
+
+/// Indique les destinations dont la clé est enregistrée (seule information renvoyée au frontend)
+pub(super) fn mark_configured<R: Runtime>(app: &AppHandle<R>, destinations: &mut [Destination]) {
+    for destination in destinations.iter_mut() {
+        if let Destination::S3 { name, secret_key, secret_key_configured, .. } = destination {
+            *secret_key_configured =
+                !secret_key.is_empty() || secrets::contains(app, name, SecretKind::S3SecretKey);
+        }
+    }
+}
+
+/// Enregistre les nouvelles clés avant d'écrire les réglages (échec : rien n'est écrit) et
+/// supprime celles des destinations S3 retirées ou renommées
+pub(super) fn save_with_secrets<R: Runtime>(app: &AppHandle<R>, schedule: &BackupSchedule) -> Result<(), String> {
+    let previous: BackupSchedule = settings::load_section(app, SETTINGS_KEY)?;
+    let mut schedule = schedule.clone();
+    // Clé restée en clair (migration en attente) et non ressaisie : elle suit la destination
+    for destination in schedule.destinations.iter_mut() {
+        if let Destination::S3 { name, secret_key, .. } = destination {
+            let legacy = previous.destinations.iter().find_map(|d| match d {
+                Destination::S3 { name: n, secret_key: k, .. } if n == name && !k.is_empty() => Some(k),
+                _ => None,
+            });
+            if let (true, Some(legacy)) = (secret_key.is_empty(), legacy) {
+                secret_key.clone_from(legacy);
+            }
+        }
+    }
+    store_secrets(app, &mut schedule.destinations)?;
+    settings::save_section(app, SETTINGS_KEY, &schedule)?;
+
+    let current: Vec<&str> = s3_names(&schedule.destinations).collect();
+    for name in s3_names(&previous.destinations).filter(|n| !current.contains(n)) {
+        if let Err(e) = secrets::delete(app, name, SecretKind::S3SecretKey) {
+            tracing::warn!(target: BACKUP, destination = %name, error = %e, "S3 secret key not deleted");
+        }
+    }
+    Ok(())
+}
+
+/// Copie de la destination avec sa clé, le temps de l'envoi
+pub(super) fn resolve<R: Runtime>(app: &AppHandle<R>, destination: &Destination) -> Result<Destination, String> {
+    let mut resolved = destination.clone();
+    if let Destination::S3 { name, secret_key, .. } = &mut resolved {
+        if secret_key.is_empty() {
+            *secret_key = secrets::get(app, name, SecretKind::S3SecretKey)?
+                .ok_or_else(|| format!("No S3 secret key saved for destination '{}'", name))?;
+        }
+    }
+    Ok(resolved)
+}
@@ -0,0 +1,135 @@
+// Sauvegardes de trading.db : archive compressée (chiffrée en option) d'une copie cohérente
+// (API backup SQLite) + manifeste de contrôle, liste et restauration avec copie de sécurité,
+// sauvegardes planifiées et copies hors site
+
+mod archive;
+pub mod commands;
+mod credentials;
+mod mirror;
+mod restore;
+mod retention;
+mod schedule;
+mod snapshot;
+mod swap;
+
+pub use archive::ARCHIVE_EXTENSION;
+pub use mirror::Destination;
+pub use restore::{BackupEntry, RestoreReport};
+pub use retention::{RetentionPolicy, RetentionReport};
+pub use schedule::{load_schedule, save_schedule, spawn, BackupLogEntry, BackupSchedule, BackupTrigger};
+pub use snapshot::{inspect, manifest_path, BackupManifest};
+
+use crate::modules::{db, sync};
+use crate::modules::migrations::MIGRATIONS;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Runtime};
+use chrono::Local;
+
+/// Event demandant au frontend de recharger ses handles SQL après une restauration
+pub const RESTORED_EVENT: &str = "database-restored";
+
+/// Dossier des sauvegardes, à côté de trading.db (app_config_dir, cf. db::db_path)
+pub fn backup_dir<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf, String> {
+    let db_path = db::db_path(app_handle)?;
+    let parent = db_path.parent().ok_or("Invalid database path")?;
+    Ok(parent.join("backups"))
+}
+
+/// Sauvegarde manuelle (copiée vers les destinations configurées) ; chiffrée si une phrase
+/// de passe est fournie
+pub async fn create_backup<R: Runtime>(app_handle: AppHandle<R>, passphrase: Option<String>) -> Result<String, String> {
+    schedule::run_backup(&app_handle, BackupTrigger::Manual, passphrase).await
+}
+
+/// À appeler avant une opération destructive : une erreur doit annuler l'opération
+pub async fn backup_before<R: Runtime>(app_handle: &AppHandle<R>, operation: &str) -> Result<String, String> {
+    schedule::run_backup(app_handle, BackupTrigger::Before(operation.to_string()), None).await
+}
+
+/// Comme `backup_before`, sans les copies hors site (démarrage : un envoi S3 lent ne doit pas
+/// retarder la fenêtre) ; les copies sont lancées ensuite par `spawn_copies`
+pub async fn backup_local_before<R: Runtime>(app_handle: &AppHandle<R>, operation: &str) -> Result<String, String> {
+    schedule::write_local(app_handle, &BackupTrigger::Before(operation.to_string()), None).await
+}
+
+/// Copies hors site d'une sauvegarde déjà écrite, en tâche de fond
+pub fn spawn_copies<R: Runtime>(app_handle: AppHandle<R>, path: String, operation: &str) {
+    let trigger = BackupTrigger::Before(operation.to_string());
+    tauri::async_runtime::spawn(async move { schedule::push_copies(&app_handle, &trigger, &path).await });
+}
+
+pub fn backup_log<R: Runtime>(app_handle: &AppHandle<R>, limit: usize) -> Result<Vec<BackupLogEntry>, String> {
+    schedule::read_log(&backup_dir(app_handle)?, limit)
+}
+
+/// Crée trading_backup_<horodatage>.tvbak dans le dossier principal, sans copie hors site
+async fn write_backup<R: Runtime>(app_handle: &AppHandle<R>, passphrase: Option<String>) -> Result<String, String> {
+    let db_path = db::db_path(app_handle)?;
+    
+    if !db_path.exists() {
+        return Err("Database file not found".to_string());
+    }
+
+    let backup_dir = backup_dir(app_handle)?;
+    if !backup_dir.exists() {
+        fs::create_dir_all(&backup_dir).map_err(|e| e.to_string())?;
+    }
+
+    // Nom horodaté à la seconde : deux sauvegardes rapprochées (migration puis démarrage)
+    // ne doivent pas s'écraser
+    let mut backup_path = timestamped_path(&backup_dir);
+    while backup_path.exists() {
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        backup_path = timestamped_path(&backup_dir);
+    }
+    let config_dir = db_path.parent().ok_or("Invalid database path")?;
+
+    // Pas de fs::copy : le plugin SQL peut écrire pendant la sauvegarde (WAL)
+    archive::create(&db_path, &backup_path, config_dir, passphrase.as_deref())?;
+    
+    // Rétention GFS : un échec de nettoyage ne doit pas faire échouer la sauvegarde
+    let _ = retention::apply(&backup_dir, &RetentionPolicy::default());
+
+    Ok(backup_path.to_string_lossy().into_owned())
+}
+
+fn timestamped_path(dir: &Path) -> PathBuf {
+    let stamp = Local::now().format("%Y%m%d_%H%M%S");
+    dir.join(format!("{}{}.{}", retention::BACKUP_PREFIX, stamp, ARCHIVE_EXTENSION))
+}
+
+pub fn list_backups<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Vec<BackupEntry>, String> {
+    restore::list(&backup_dir(app_handle)?)
+}
+
+/// Restauration sans sauvegarde ni sync Flex concurrente ; Argon2 et l'API backup SQLite
+/// tournent hors des workers async
+pub async fn restore_backup<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    passphrase: Option<String>,
+) -> Result<RestoreReport, String> {
+    let db_path = db::db_path(&app_handle)?;
+    if !db_path.exists() {
+        return Err("Database file not found".to_string());
+    }
+    let dir = backup_dir(&app_handle)?;
+    // Ordre fixe (sync puis sauvegarde) : pas d'interblocage si un autre chemin prend les deux
+    let _sync = sync::SYNC_LOCK.lock().await;
+    let _backup = schedule::BACKUP_LOCK.lock().await;
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        restore::restore(&db_path, &dir, &id, passphrase.as_deref(), MIGRATIONS)
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))??;
+    app_handle.emit(RESTORED_EVENT, &report).map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+pub fn apply_retention<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    policy: &RetentionPolicy,
+) -> Result<RetentionReport, String> {
+    retention::apply(&backup_dir(app_handle)?, policy)
+}
@@ -0,0 +1,295 @@
+// Liste et restauration des sauvegardes
+// La restauration passe par l'API backup de SQLite en sens inverse : les pages sont écrites dans
+// une seule transaction sur la base active, les connexions du plugin SQL voient l'ancien ou le
+// nouveau contenu, jamais un mélange
+
+use super::archive::{self, ARCHIVE_EXTENSION};
+use super::snapshot::{inspect, manifest_path, sha256_file, BackupManifest};
+use super::swap::{stage_files, swap};
+use crate::modules::migrations::{self, Migration};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Préfixe des copies de sécurité prises juste avant une restauration
+pub const PRE_RESTORE_PREFIX: &str = "pre_restore_";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupEntry {
+    pub id: String, // nom du fichier dans backups/
+    pub size_bytes: u64,
+    pub modified: String,
+    pub is_pre_restore: bool,
+    pub is_archive: bool,
+    pub encrypted: bool, // phrase de passe requise pour restaurer
+    pub manifest: Option<BackupManifest>, // None : sauvegarde antérieure aux manifestes
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub restored: String,
+    pub safety_snapshot: String,
+    pub manifest: BackupManifest,
+    pub restored_files: Vec<String>, // profils / réglages remis en place
+}
+
+/// Sauvegardes de `dir`, de la plus récente à la plus ancienne
+pub fn list(dir: &Path) -> Result<Vec<BackupEntry>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries: Vec<BackupEntry> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_archive(p) || p.extension().is_some_and(|ext| ext == "db"))
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            let modified: DateTime<Local> = metadata.modified().ok()?.into();
+            let id = path.file_name()?.to_string_lossy().into_owned();
+            Some(BackupEntry {
+                is_pre_restore: id.starts_with(PRE_RESTORE_PREFIX),
+                is_archive: is_archive(&path),
+                encrypted: archive::is_encrypted(&path),
+                manifest: read_manifest(&path),
+                id,
+                size_bytes: metadata.len(),
+                modified: modified.to_rfc3339(),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(entries)
+}
+
+/// Valide la sauvegarde `id` (base .db ou archive .tvbak), prend une copie de sécurité de la
+/// base active puis la remplace et la migre (`migrations`) ; si la base restaurée échoue au
+/// contrôle d'intégrité ou à une migration, la copie de sécurité est remise en place
+pub fn restore(
+    db_path: &Path,
+    dir: &Path,
+    id: &str,
+    passphrase: Option<&str>,
+    migrations: &[Migration],
+) -> Result<RestoreReport, String> {
+    let backup_path = resolve(dir, id)?;
+    let config_dir = db_path.parent().ok_or("Invalid database path")?;
+    let stamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let staging = dir.join(format!(".restore_{}.db.tmp", stamp));
+
+    let result = prepare(&backup_path, &staging, passphrase).and_then(|Prepared { source, manifest, extras }| {
+        let latest = migrations::latest_version(migrations);
+        if manifest.schema_version > latest {
+            return Err(format!(
+                "Backup schema version {} is newer than this application (version {}); update the application",
+                manifest.schema_version, latest
+            ));
+        }
+        let safety_name = format!("{}{}.{}", PRE_RESTORE_PREFIX, stamp, ARCHIVE_EXTENSION);
+        let safety_path = dir.join(&safety_name);
+        archive::create(db_path, &safety_path, config_dir, passphrase)?;
+
+        // Fichiers de config préparés avant la bascule : seul un rename reste après
+        let pending = stage_files(config_dir, &extras)?;
+        swap(db_path, &source, &safety_path, passphrase, &staging.with_extension("rollback"), migrations)?;
+        let restored_files = pending
+            .into_iter()
+            .map(|(tmp, name)| fs::rename(&tmp, config_dir.join(&name)).map(|_| name).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RestoreReport { restored: id.to_string(), safety_snapshot: safety_name, manifest, restored_files })
+    });
+    let _ = fs::remove_file(&staging);
+    result
+}
+
+/// Sauvegarde validée, prête à être injectée
+struct Prepared {
+    source: PathBuf, // base SQLite à restaurer
+    manifest: BackupManifest,
+    extras: Vec<(String, Vec<u8>)>,
+}
+
+/// Base à restaurer : le fichier lui-même (ancien format) ou la base extraite de l'archive
+fn prepare(backup_path: &Path, staging: &Path, passphrase: Option<&str>) -> Result<Prepared, String> {
+    if !is_archive(backup_path) {
+        let manifest = validate(backup_path)?;
+        return Ok(Prepared { source: backup_path.to_path_buf(), manifest, extras: Vec::new() });
+    }
+    // Le manifeste externe permet de détecter une archive altérée sans phrase de passe
+    if let Some(expected) = read_manifest(backup_path).and_then(|m| m.archive_sha256) {
+        if sha256_file(backup_path)? != expected {
+            return Err(format!("Backup checksum mismatch: {}", backup_path.display()));
+        }
+    }
+    let contents = archive::read(backup_path, passphrase)?;
+    fs::write(staging, &contents.db).map_err(|e| e.to_string())?;
+    inspect(staging)?;
+    Ok(Prepared { source: staging.to_path_buf(), manifest: contents.manifest, extras: contents.extras })
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION)
+}
+
+fn read_manifest(path: &Path) -> Option<BackupManifest> {
+    fs::read_to_string(manifest_path(path)).ok().and_then(|json| serde_json::from_str(&json).ok())
+}
+
+/// L'id vient du webview : simple nom de fichier, jamais un chemin
+fn resolve(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let is_plain_name = !id.is_empty()
+        && !id.contains(['/', '\\'])
+        && id != "."
+        && id != ".."
+        && (id.ends_with(".db") || id.ends_with(&format!(".{}", ARCHIVE_EXTENSION)));
+    if !is_plain_name {
+        return Err(format!("Invalid backup id: {}", id));
+    }
+    let path = dir.join(id);
+    if !path.is_file() {
+        return Err(format!("Backup not found: {}", id));
+    }
+    Ok(path)
+}
+
+/// Intégrité SQLite + empreinte identique au manifeste quand il existe
+fn validate(path: &Path) -> Result<BackupManifest, String> {
+    let current = inspect(path)?;
+    if let Ok(json) = fs::read_to_string(manifest_path(path)) {
+        let recorded: BackupManifest =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid manifest: {}", e))?;
+        if recorded.sha256 != current.sha256 {
+            return Err(format!("Backup checksum mismatch: {}", current.file_name));
+        }
+    }
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::snapshot::{copy_verified, write_manifest};
+    use super::*;
+    use crate::modules::sync::SyncSettings;
+    use rusqlite::Connection;
+
+    /// Sauvegarde au format .db d'avant les archives
+    fn legacy_backup(db_path: &Path, dest: &Path) {
+        let manifest = copy_verified(db_path, dest).expect("backup");
+        write_manifest(dest, &manifest).expect("manifest");
+    }
+
+    fn setup() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db_path = dir.path().join("trading.db");
+        let backups = dir.path().join("backups");
+        fs::create_dir_all(&backups).expect("backups dir");
+        let conn = Connection::open(&db_path).expect("db");
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE trades (id INTEGER PRIMARY KEY, symbol TEXT);
+             INSERT INTO trades (symbol) VALUES ('SPY');",
+        )
+        .expect("schema");
+        (dir, db_path, backups)
+    }
+
+    fn count(db_path: &Path) -> i64 {
+        let conn = Connection::open(db_path).expect("db");
+        conn.query_row("SELECT COUNT(*) FROM trades", [], |r| r.get(0)).expect("count")
+    }
+
+    #[test]
+    fn test_restore_with_safety_snapshot() {
+        let (_dir, db_path, backups) = setup();
+        legacy_backup(&db_path, &backups.join("trading_backup_1.db"));
+
+        // Connexion ouverte pendant la restauration, comme le plugin SQL
+        let open = Connection::open(&db_path).expect("db");
+        open.execute("INSERT INTO trades (symbol) VALUES ('KO')", []).expect("insert");
+        assert_eq!(count(&db_path), 2);
+
+        let report = restore(&db_path, &backups, "trading_backup_1.db", None, &[]).expect("restore");
+        assert_eq!(count(&db_path), 1);
+        let n: i64 = open.query_row("SELECT COUNT(*) FROM trades", [], |r| r.get(0)).expect("count");
+        assert_eq!(n, 1);
+
+        let listed = list(&backups).expect("list");
+        assert_eq!(listed.len(), 2);
+        let safety = listed.iter().find(|b| b.id == report.safety_snapshot).expect("safety");
+        assert!(safety.is_pre_restore);
+        assert_eq!(safety.manifest.as_ref().and_then(|m| m.row_counts.get("trades")), Some(&2));
+    }
+
+    #[test]
+    fn test_rejects_tampered_or_foreign_files() {
+        let (_dir, db_path, backups) = setup();
+        let backup = backups.join("trading_backup_1.db");
+        legacy_backup(&db_path, &backup);
+        Connection::open(&backup).expect("db").execute("DELETE FROM trades", []).expect("tamper");
+
+        let err = restore(&db_path, &backups, "trading_backup_1.db", None, &[]).expect_err("checksum");
+        assert!(err.contains("checksum"));
+        assert!(restore(&db_path, &backups, "../trading.db", None, &[]).is_err());
+        assert!(restore(&db_path, &backups, "missing.db", None, &[]).is_err());
+        assert_eq!(count(&db_path), 1);
+    }
+
+    #[test]
+    fn test_restore_encrypted_archive_with_settings() {
+        let (dir, db_path, backups) = setup();
+        fs::write(dir.path().join("settings.json"), b"{\"v\":1}").expect("settings");
+        archive::create(&db_path, &backups.join("trading_backup_1.tvbak"), dir.path(), Some("secret")).expect("archive");
+
+        Connection::open(&db_path).expect("db").execute("DELETE FROM trades", []).expect("delete");
+        fs::write(dir.path().join("settings.json"), b"{\"v\":2}").expect("settings");
+
+        assert!(restore(&db_path, &backups, "trading_backup_1.tvbak", None, &[]).is_err());
+        assert_eq!(count(&db_path), 0);
+        let report =
+            restore(&db_path, &backups, "trading_backup_1.tvbak", Some("secret"), &[]).expect("restore");
+        assert_eq!(count(&db_path), 1);
+        assert_eq!(report.restored_files, vec!["settings.json".to_string()]);
+        assert_eq!(fs::read(dir.path().join("settings.json")).expect("read"), b"{\"v\":1}".to_vec());
+
+        let listed = list(&backups).expect("list");
+        assert!(listed.iter().all(|b| b.is_archive && b.encrypted));
+        assert!(!listed.iter().any(|b| b.id.ends_with(".tmp")));
+    }
+
+    #[test]
+    fn test_restore_migrates_older_schema_and_rejects_newer() {
+        let (_dir, db_path, backups) = setup();
+        let mut conn = Connection::open(&db_path).expect("db");
+        let latest = migrations::latest_version(migrations::MIGRATIONS);
+        migrations::migrate(&mut conn, &migrations::MIGRATIONS[..5]).expect("old schema");
+        legacy_backup(&db_path, &backups.join("trading_backup_1.db"));
+        migrations::migrate(&mut conn, migrations::MIGRATIONS).expect("latest");
+
+        restore(&db_path, &backups, "trading_backup_1.db", None, migrations::MIGRATIONS).expect("restore");
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0)).expect("version");
+        assert_eq!(version, latest);
+
+        conn.pragma_update(None, "user_version", latest + 1).expect("newer");
+        legacy_backup(&db_path, &backups.join("trading_backup_2.db"));
+        let err = restore(&db_path, &backups, "trading_backup_2.db", None, migrations::MIGRATIONS).expect_err("newer");
+        assert!(err.contains("newer"));
+    }
+
+    #[test]
+    fn test_restore_brings_back_connection_profiles() {
+        let (dir, db_path, backups) = setup();
+        let settings_path = dir.path().join("settings.json");
+        fs::write(&settings_path, br#"{"sync":{"query_id":42,"jobs":[{"profile":"kasper","query_id":7}]}}"#)
+            .expect("settings");
+        archive::create(&db_path, &backups.join("trading_backup_1.tvbak"), dir.path(), None).expect("archive");
+        fs::write(&settings_path, br#"{"sync":{"query_id":0}}"#).expect("settings");
+
+        restore(&db_path, &backups, "trading_backup_1.tvbak", None, &[]).expect("restore");
+        let restored: serde_json::Value =
+            serde_json::from_slice(&fs::read(&settings_path).expect("read")).expect("json");
+        let sync: SyncSettings = serde_json::from_value(restored["sync"].clone()).expect("sync");
+        let profiles: Vec<(String, i32)> = sync.all_jobs().into_iter().map(|j| (j.profile, j.query_id)).collect();
+        assert_eq!(profiles, vec![("default".to_string(), 42), ("kasper".to_string(), 7)]);
+    }
+}
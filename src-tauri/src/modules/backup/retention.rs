@@ -0,0 +1,255 @@
+// Rétention grand-père / père / fils des sauvegardes
+// Seuls les fichiers trading_backup_YYYYMMDD_HHMMSS.db / .tvbak sont concernés : copies de sécurité
+// pre_restore_* (sauf `pre_restore_keep` renseigné), fichiers inconnus et sauvegardes au nom
+// illisible ne sont jamais supprimés
+
+use super::archive::ARCHIVE_EXTENSION;
+use super::restore::PRE_RESTORE_PREFIX;
+use super::snapshot::manifest_path;
+use chrono::{Datelike, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+pub const BACKUP_PREFIX: &str = "trading_backup_";
+const BACKUP_TIMESTAMP: &str = "%Y%m%d_%H%M%S";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// N sauvegardes les plus récentes, quelle que soit leur date
+    #[serde(default = "default_keep_last")]
+    pub keep_last: usize,
+    /// Dernière sauvegarde de chacun des N derniers jours ayant une sauvegarde
+    #[serde(default = "default_daily")]
+    pub daily: usize,
+    /// Idem par semaine ISO
+    #[serde(default = "default_weekly")]
+    pub weekly: usize,
+    /// Idem par mois
+    #[serde(default = "default_monthly")]
+    pub monthly: usize,
+    /// Plancher absolu : jamais moins de N sauvegardes conservées
+    #[serde(default = "default_min_keep")]
+    pub min_keep: usize,
+    /// Copies de sécurité pre_restore_* conservées, les plus récentes (au moins min_keep) ;
+    /// None : toutes conservées
+    #[serde(default)]
+    pub pre_restore_keep: Option<usize>,
+}
+
+fn default_keep_last() -> usize {
+    5
+}
+
+fn default_daily() -> usize {
+    7
+}
+
+fn default_weekly() -> usize {
+    4
+}
+
+fn default_monthly() -> usize {
+    12
+}
+
+fn default_min_keep() -> usize {
+    3
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: default_keep_last(),
+            daily: default_daily(),
+            weekly: default_weekly(),
+            monthly: default_monthly(),
+            min_keep: default_min_keep(),
+            pre_restore_keep: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionReport {
+    pub kept: Vec<String>,
+    pub deleted: Vec<String>,
+    pub errors: Vec<String>, // suppression impossible : le fichier reste
+}
+
+/// Horodatage d'une sauvegarde d'après son nom (indépendant du mtime, modifié par les copies)
+pub fn backup_timestamp(file_name: &str) -> Option<NaiveDateTime> {
+    prefixed_timestamp(file_name, BACKUP_PREFIX)
+}
+
+fn prefixed_timestamp(file_name: &str, prefix: &str) -> Option<NaiveDateTime> {
+    let rest = file_name.strip_prefix(prefix)?;
+    let stamp = rest
+        .strip_suffix(".db")
+        .or_else(|| rest.strip_suffix(ARCHIVE_EXTENSION)?.strip_suffix('.'))?;
+    NaiveDateTime::parse_from_str(stamp, BACKUP_TIMESTAMP).ok()
+}
+
+/// Applique la politique au dossier et supprime sauvegardes + manifestes hors rétention
+pub fn apply(dir: &Path, policy: &RetentionPolicy) -> Result<RetentionReport, String> {
+    if !dir.exists() {
+        return Ok(RetentionReport::default());
+    }
+    let names: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    let backups: Vec<(String, NaiveDateTime)> =
+        names.iter().filter_map(|name| backup_timestamp(name).map(|ts| (name.clone(), ts))).collect();
+    let mut pre_restore: Vec<(String, NaiveDateTime)> = match policy.pre_restore_keep {
+        Some(_) => names
+            .iter()
+            .filter_map(|name| prefixed_timestamp(name, PRE_RESTORE_PREFIX).map(|ts| (name.clone(), ts)))
+            .collect(),
+        None => Vec::new(),
+    };
+    pre_restore.sort_by_key(|b| std::cmp::Reverse(b.1));
+
+    let mut keep = select_kept(&backups, policy);
+    let pre_restore_keep = policy.pre_restore_keep.unwrap_or_default().max(policy.min_keep);
+    keep.extend(pre_restore.iter().take(pre_restore_keep).map(|(name, _)| name.clone()));
+    let mut report = RetentionReport::default();
+    for (name, _) in backups.into_iter().chain(pre_restore) {
+        if keep.contains(&name) {
+            report.kept.push(name);
+            continue;
+        }
+        let path = dir.join(&name);
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                let _ = fs::remove_file(manifest_path(&path));
+                report.deleted.push(name);
+            }
+            Err(e) => report.errors.push(format!("{}: {}", name, e)),
+        }
+    }
+    report.kept.sort();
+    report.deleted.sort();
+    Ok(report)
+}
+
+/// Noms à conserver : union des paliers, complétée jusqu'au plancher min_keep
+fn select_kept(backups: &[(String, NaiveDateTime)], policy: &RetentionPolicy) -> HashSet<String> {
+    let mut sorted: Vec<&(String, NaiveDateTime)> = backups.iter().collect();
+    sorted.sort_by_key(|b| std::cmp::Reverse(b.1));
+
+    let mut keep: HashSet<String> = sorted.iter().take(policy.keep_last).map(|(n, _)| n.clone()).collect();
+    keep_per_period(&sorted, policy.daily, |ts| ts.date().num_days_from_ce(), &mut keep);
+    keep_per_period(&sorted, policy.weekly, |ts| {
+        let week = ts.date().iso_week();
+        week.year() * 100 + week.week() as i32
+    }, &mut keep);
+    keep_per_period(&sorted, policy.monthly, |ts| ts.year() * 100 + ts.month() as i32, &mut keep);
+
+    for (name, _) in &sorted {
+        if keep.len() >= policy.min_keep {
+            break;
+        }
+        keep.insert(name.clone());
+    }
+    keep
+}
+
+/// Garde la plus récente sauvegarde de chacune des `count` dernières périodes
+fn keep_per_period(
+    sorted: &[&(String, NaiveDateTime)],
+    count: usize,
+    period: impl Fn(&NaiveDateTime) -> i32,
+    keep: &mut HashSet<String>,
+) {
+    let mut seen = HashSet::new();
+    for (name, ts) in sorted {
+        if seen.len() >= count {
+            break;
+        }
+        if seen.insert(period(ts)) {
+            keep.insert(name.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(stamp: &str) -> String {
+        format!("{}{}.db", BACKUP_PREFIX, stamp)
+    }
+
+    #[test]
+    fn test_gfs_tiers() {
+        let policy =
+            RetentionPolicy { keep_last: 1, daily: 2, weekly: 2, monthly: 2, min_keep: 0, pre_restore_keep: None };
+        let stamps = [
+            "20250610_180000", "20250610_090000", // même jour
+            "20250609_120000",
+            "20250604_120000", // semaine précédente
+            "20250520_120000", "20250505_120000", // mai
+            "20250415_120000", // avril
+            "20250115_120000", // trop ancien
+        ];
+        let backups: Vec<_> = stamps.iter().map(|s| (name(s), backup_timestamp(&name(s)).expect("ts"))).collect();
+        let keep = select_kept(&backups, &policy);
+
+        assert!(keep.contains(&name("20250610_180000")));
+        assert!(!keep.contains(&name("20250610_090000")));
+        assert!(keep.contains(&name("20250609_120000")));
+        assert!(keep.contains(&name("20250604_120000")));
+        assert!(keep.contains(&name("20250520_120000")));
+        assert!(!keep.contains(&name("20250505_120000")));
+        assert!(!keep.contains(&name("20250415_120000")));
+        assert!(!keep.contains(&name("20250115_120000")));
+    }
+
+    #[test]
+    fn test_apply_only_touches_backups_and_honours_minimum() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        for stamp in ["20240101_100000", "20240102_100000", "20240103_100000"] {
+            fs::write(dir.path().join(name(stamp)), b"db").expect("write");
+        }
+        fs::write(dir.path().join(format!("{}20240104_100000.{}", BACKUP_PREFIX, ARCHIVE_EXTENSION)), b"zip")
+            .expect("write");
+        fs::write(dir.path().join(format!("{}20240101_100000.manifest.json", BACKUP_PREFIX)), b"{}").expect("manifest");
+        fs::write(dir.path().join("pre_restore_20230101_000000.db"), b"db").expect("write");
+        fs::write(dir.path().join("notes.txt"), b"x").expect("write");
+
+        let policy =
+            RetentionPolicy { keep_last: 0, daily: 0, weekly: 0, monthly: 0, min_keep: 2, pre_restore_keep: None };
+        let report = apply(dir.path(), &policy).expect("apply");
+        assert_eq!(
+            report.kept,
+            vec![name("20240103_100000"), format!("{}20240104_100000.{}", BACKUP_PREFIX, ARCHIVE_EXTENSION)]
+        );
+        assert_eq!(report.deleted.len(), 2);
+        assert!(!dir.path().join(format!("{}20240101_100000.manifest.json", BACKUP_PREFIX)).exists());
+        assert!(dir.path().join("pre_restore_20230101_000000.db").exists());
+        assert!(dir.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_pre_restore_snapshots_are_pruned_only_on_request() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let snapshots: Vec<String> = (1..=5)
+            .map(|day| format!("{}202401{:02}_100000.{}", PRE_RESTORE_PREFIX, day, ARCHIVE_EXTENSION))
+            .collect();
+        for snapshot in &snapshots {
+            fs::write(dir.path().join(snapshot), b"zip").expect("write");
+        }
+
+        let report = apply(dir.path(), &RetentionPolicy::default()).expect("apply");
+        assert!(report.deleted.is_empty() && report.kept.is_empty());
+
+        let policy = RetentionPolicy { pre_restore_keep: Some(1), min_keep: 2, ..RetentionPolicy::default() };
+        let report = apply(dir.path(), &policy).expect("apply");
+        assert_eq!(report.deleted, snapshots[..3].to_vec());
+        assert_eq!(report.kept, snapshots[3..].to_vec());
+        assert!(!dir.path().join(&snapshots[0]).exists());
+    }
+}
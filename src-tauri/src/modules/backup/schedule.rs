@@ -0,0 +1,280 @@
+// Sauvegardes automatiques : au démarrage, quotidienne, avant une opération destructive
+// Chaque sauvegarde est recopiée vers les destinations configurées ; succès et échecs sont
+// ajoutés au journal backups/backup_log.jsonl (une ligne JSON par copie)
+
+use super::credentials;
+use super::mirror::{self, Destination};
+use super::retention::backup_timestamp;
+use crate::modules::logging::BACKUP;
+use crate::modules::settings;
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use tokio::sync::Mutex;
+
+/// Section "backup" de settings.json
+pub const SETTINGS_KEY: &str = "backup";
+const LOG_FILE: &str = "backup_log.jsonl";
+const DAILY_INTERVAL_HOURS: i64 = 24;
+const CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Sérialise les sauvegardes : deux déclenchements dans la même seconde écriraient le même fichier ;
+/// tenu aussi pendant une restauration
+pub(super) static BACKUP_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSchedule {
+    #[serde(default = "default_true")]
+    pub on_start: bool,
+    /// Sauvegarde si la plus récente a plus de 24 h (vérifié toutes les heures, rattrape les jours manqués)
+    #[serde(default = "default_true")]
+    pub daily: bool,
+    #[serde(default)]
+    pub destinations: Vec<Destination>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for BackupSchedule {
+    fn default() -> Self {
+        Self { on_start: true, daily: true, destinations: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackupTrigger {
+    Startup,
+    Daily,
+    Manual,
+    /// Avant une opération destructive (suppression d'import, migration de schéma...)
+    Before(String),
+}
+
+impl fmt::Display for BackupTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupTrigger::Startup => write!(f, "startup"),
+            BackupTrigger::Daily => write!(f, "daily"),
+            BackupTrigger::Manual => write!(f, "manual"),
+            BackupTrigger::Before(operation) => write!(f, "before:{}", operation),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupLogEntry {
+    pub timestamp: String,
+    pub trigger: String,
+    pub backup: Option<String>,
+    /// "local" pour la sauvegarde elle-même, sinon le nom de la destination
+    pub destination: String,
+    pub location: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Réglages sans clé S3 (voir credentials.rs), chaque destination S3 indiquant si la sienne est enregistrée
+pub fn load_schedule<R: Runtime>(app_handle: &AppHandle<R>) -> Result<BackupSchedule, String> {
+    let mut schedule: BackupSchedule = settings::load_section(app_handle, SETTINGS_KEY)?;
+    credentials::migrate_legacy_secrets(app_handle, &mut schedule);
+    credentials::mark_configured(app_handle, &mut schedule.destinations);
+    Ok(schedule)
+}
+
+/// Les clés S3 saisies partent dans le magasin de secrets, pas dans settings.json
+pub fn save_schedule<R: Runtime>(app_handle: &AppHandle<R>, schedule: &BackupSchedule) -> Result<(), String> {
+    credentials::save_with_secrets(app_handle, schedule)
+}
+
+/// Sauvegarde + copies ; seul l'échec de la sauvegarde locale est une erreur
+pub async fn run_backup<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    trigger: BackupTrigger,
+    passphrase: Option<String>,
+) -> Result<String, String> {
+    let path = write_local(app_handle, &trigger, passphrase).await?;
+    push_copies(app_handle, &trigger, &path).await;
+    Ok(path)
+}
+
+/// Sauvegarde dans le dossier principal seulement (aucun appel réseau)
+pub(super) async fn write_local<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    trigger: &BackupTrigger,
+    passphrase: Option<String>,
+) -> Result<String, String> {
+    let _guard = BACKUP_LOCK.lock().await;
+    let dir = super::backup_dir(app_handle)?;
+    let result = super::write_backup(app_handle, passphrase).await;
+    log(&dir, entry(trigger, backup_name(&result), "local", &result));
+    result
+}
+
+/// Copie vers chaque destination ; les échecs sont journalisés, jamais remontés
+pub(super) async fn push_copies<R: Runtime>(app_handle: &AppHandle<R>, trigger: &BackupTrigger, path: &str) {
+    let _guard = BACKUP_LOCK.lock().await;
+    let dir = match super::backup_dir(app_handle) {
+        Ok(dir) => dir,
+        Err(e) => {
+            tracing::error!(target: BACKUP, error = %e, "Backup copies skipped");
+            return;
+        }
+    };
+    let backup_name = backup_name(&Ok(path.to_string()));
+
+    // Réglages illisibles : la sauvegarde locale reste valable, les copies sont sautées
+    let destinations = match load_schedule(app_handle) {
+        Ok(schedule) => schedule.destinations,
+        Err(e) => {
+            log(&dir, entry(trigger, backup_name.clone(), "settings", &Err(e)));
+            Vec::new()
+        }
+    };
+    for destination in &destinations {
+        let pushed = match credentials::resolve(app_handle, destination) {
+            Ok(resolved) => mirror::push(&resolved, Path::new(path)).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &pushed {
+            tracing::error!(target: BACKUP, destination = %destination.name(), error = %e, "Backup copy failed");
+        }
+        log(&dir, entry(trigger, backup_name.clone(), destination.name(), &pushed));
+    }
+}
+
+fn backup_name(result: &Result<String, String>) -> Option<String> {
+    result.as_ref().ok().and_then(|p| Path::new(p).file_name()).map(|n| n.to_string_lossy().into_owned())
+}
+
+/// Lance la boucle planifiée (appelé depuis setup)
+pub fn spawn<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        match load_schedule(&app) {
+            Ok(schedule) if schedule.on_start => scheduled(&app, BackupTrigger::Startup).await,
+            Ok(_) => {}
+            Err(e) => tracing::error!(target: BACKUP, error = %e, "Backup settings error"),
+        }
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let due = load_schedule(&app).map(|s| s.daily).unwrap_or(true)
+                && super::backup_dir(&app).map(|dir| daily_due(&dir, Local::now().naive_local())).unwrap_or(false);
+            if due {
+                scheduled(&app, BackupTrigger::Daily).await;
+            }
+        }
+    });
+}
+
+async fn scheduled<R: Runtime>(app: &AppHandle<R>, trigger: BackupTrigger) {
+    // Premier lancement : pas encore de base à sauvegarder
+    let exists = crate::modules::db::db_path(app).map(|p| p.exists()).unwrap_or(false);
+    if !exists {
+        return;
+    }
+    match run_backup(app, trigger.clone(), None).await {
+        Ok(path) => tracing::info!(target: BACKUP, trigger = %trigger, path = %path, "Backup created"),
+        Err(e) => tracing::error!(target: BACKUP, trigger = %trigger, error = %e, "Backup failed"),
+    }
+}
+
+/// Vrai si aucune sauvegarde trading_backup_* n'a moins de 24 h
+fn daily_due(dir: &Path, now: NaiveDateTime) -> bool {
+    let latest = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| backup_timestamp(&e.file_name().to_string_lossy()))
+                .max()
+        })
+        .unwrap_or(None);
+    latest.is_none_or(|ts| (now - ts).num_hours() >= DAILY_INTERVAL_HOURS)
+}
+
+fn entry(trigger: &BackupTrigger, backup: Option<String>, destination: &str, result: &Result<String, String>) -> BackupLogEntry {
+    BackupLogEntry {
+        timestamp: Local::now().to_rfc3339(),
+        trigger: trigger.to_string(),
+        backup,
+        destination: destination.to_string(),
+        location: result.as_ref().ok().cloned(),
+        success: result.is_ok(),
+        error: result.as_ref().err().cloned(),
+    }
+}
+
+fn log_path(dir: &Path) -> PathBuf {
+    dir.join(LOG_FILE)
+}
+
+fn log(dir: &Path, entry: BackupLogEntry) {
+    let written = fs::create_dir_all(dir)
+        .and_then(|_| OpenOptions::new().create(true).append(true).open(log_path(dir)))
+        .and_then(|mut file| {
+            let line = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+            writeln!(file, "{}", line)
+        });
+    if let Err(e) = written {
+        tracing::warn!(target: BACKUP, error = %e, "Backup log not written");
+    }
+}
+
+/// `limit` dernières entrées du journal, la plus récente en premier
+pub fn read_log(dir: &Path, limit: usize) -> Result<Vec<BackupLogEntry>, String> {
+    let path = log_path(dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(text.lines().rev().filter_map(|l| serde_json::from_str(l).ok()).take(limit).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_due_uses_newest_backup_name() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let now = NaiveDateTime::parse_from_str("20250610_120000", "%Y%m%d_%H%M%S").expect("now");
+        assert!(daily_due(dir.path(), now));
+
+        fs::write(dir.path().join("trading_backup_20250609_080000.tvbak"), b"x").expect("write");
+        fs::write(dir.path().join("pre_restore_20250610_110000.tvbak"), b"x").expect("write");
+        assert!(daily_due(dir.path(), now));
+
+        fs::write(dir.path().join("trading_backup_20250609_130000.tvbak"), b"x").expect("write");
+        assert!(!daily_due(dir.path(), now));
+    }
+
+    #[test]
+    fn test_log_round_trip_newest_first() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let before = BackupTrigger::Before("clear_flex_trades".into());
+        log(dir.path(), entry(&BackupTrigger::Startup, Some("a.tvbak".into()), "local", &Ok("a".into())));
+        log(dir.path(), entry(&before, Some("b.tvbak".into()), "nas", &Err("unmounted".into())));
+
+        let entries = read_log(dir.path(), 10).expect("log");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].trigger, "before:clear_flex_trades");
+        assert!(!entries[0].success && entries[0].error.as_deref() == Some("unmounted"));
+        assert_eq!(entries[1].trigger, "startup");
+        assert_eq!(read_log(dir.path(), 1).expect("log").len(), 1);
+    }
+
+    #[test]
+    fn test_schedule_defaults_when_section_is_partial() {
+        let schedule: BackupSchedule = serde_json::from_str(
+            r#"{"destinations":[{"type":"directory","name":"nas","path":"/mnt/nas"}]}"#,
+        )
+        .expect("json");
+        assert!(schedule.on_start && schedule.daily);
+        assert_eq!(schedule.destinations[0].name(), "nas");
+    }
+}
@@ -0,0 +1,157 @@
+// Copie cohérente de trading.db via l'API de sauvegarde en ligne de SQLite
+// (lit les pages au travers de SQLite : le contenu du -wal est inclus, aucune page à moitié écrite)
+
+use crate::modules::db;
+use chrono::Local;
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Métadonnées écrites à côté de chaque sauvegarde (trading_backup_X.manifest.json)
+/// et dans l'archive ; size_bytes / sha256 décrivent toujours la base elle-même
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub file_name: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub schema_version: i64,
+    pub row_counts: BTreeMap<String, i64>,
+    #[serde(default)]
+    pub encrypted: bool,
+    #[serde(default)]
+    pub bundled_files: Vec<String>, // profils de connexion, réglages
+    #[serde(default)]
+    pub archive_sha256: Option<String>, // empreinte du fichier .tvbak (manifeste externe uniquement)
+}
+
+pub fn manifest_path(backup: &Path) -> PathBuf {
+    backup.with_extension("manifest.json")
+}
+
+/// Copie `source` vers `dest` et vérifie l'intégrité de la copie ; en cas d'échec la copie
+/// partielle est supprimée
+pub fn copy_verified(source: &Path, dest: &Path) -> Result<BackupManifest, String> {
+    let result = db::open_path(source)
+        .and_then(|conn| {
+            conn.backup(DatabaseName::Main, dest, None)
+                .map_err(|e| format!("SQLite backup error: {}", e))
+        })
+        .and_then(|_| inspect(dest));
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+/// Contrôle une base (PRAGMA integrity_check) et calcule son manifeste
+pub fn inspect(path: &Path) -> Result<BackupManifest, String> {
+    let (schema_version, row_counts) = {
+        let conn = db::open_path(path)?;
+        check_integrity(&conn)?;
+        (schema_version(&conn)?, row_counts(&conn)?)
+    };
+    let size_bytes = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    Ok(BackupManifest {
+        file_name: path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        created_at: Local::now().to_rfc3339(),
+        size_bytes,
+        sha256: sha256_file(path)?,
+        schema_version,
+        row_counts,
+        encrypted: false,
+        bundled_files: Vec::new(),
+        archive_sha256: None,
+    })
+}
+
+pub fn write_manifest(backup: &Path, manifest: &BackupManifest) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    fs::write(manifest_path(backup), json).map_err(|e| e.to_string())
+}
+
+pub(super) fn check_integrity(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check").map_err(|e| e.to_string())?;
+    let messages = stmt
+        .query_map([], |r| r.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    if messages.len() == 1 && messages[0] == "ok" {
+        Ok(())
+    } else {
+        Err(format!("Integrity check failed: {}", messages.join("; ")))
+    }
+}
+
+/// Version du schéma (PRAGMA user_version)
+fn schema_version(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("PRAGMA user_version", [], |r| r.get(0)).map_err(|e| e.to_string())
+}
+
+fn row_counts(conn: &Connection) -> Result<BTreeMap<String, i64>, String> {
+    let tables: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |r| r.get(0)).map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?
+    };
+    tables
+        .into_iter()
+        .map(|t| {
+            let sql = format!("SELECT COUNT(*) FROM \"{}\"", t.replace('"', "\"\""));
+            let count: i64 = conn.query_row(&sql, [], |r| r.get(0)).map_err(|e| e.to_string())?;
+            Ok((t, count))
+        })
+        .collect()
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_of_wal_database() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let source = dir.path().join("trading.db");
+        let conn = Connection::open(&source).expect("db");
+        // Écritures restées dans le -wal : une copie de fichier les perdrait
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL; PRAGMA user_version = 3;
+             CREATE TABLE trades (id INTEGER PRIMARY KEY, symbol TEXT);
+             INSERT INTO trades (symbol) VALUES ('SPY'), ('KO');",
+        )
+        .expect("schema");
+
+        let dest = dir.path().join("trading_backup_test.db");
+        let manifest = copy_verified(&source, &dest).expect("snapshot");
+        write_manifest(&dest, &manifest).expect("manifest");
+        assert_eq!(manifest.row_counts.get("trades"), Some(&2));
+        assert_eq!(manifest.schema_version, 3);
+        assert_eq!(manifest.sha256.len(), 64);
+        assert_eq!(manifest.size_bytes, fs::metadata(&dest).expect("meta").len());
+
+        let written: BackupManifest =
+            serde_json::from_str(&fs::read_to_string(manifest_path(&dest)).expect("manifest")).expect("json");
+        assert_eq!(written.sha256, manifest.sha256);
+    }
+
+    #[test]
+    fn test_inspect_rejects_corrupt_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("broken.db");
+        fs::write(&path, b"not a sqlite database at all").expect("write");
+        assert!(inspect(&path).is_err());
+    }
+}
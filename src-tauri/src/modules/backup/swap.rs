@@ -0,0 +1,51 @@
+// Bascule de la base active vers la sauvegarde validée (API backup SQLite en sens inverse), mise
+// au schéma courant, puis fichiers de configuration remis en place par un simple rename
+
+use super::archive;
+use super::snapshot::check_integrity;
+use crate::modules::db;
+use crate::modules::migrations::{self, Migration};
+use rusqlite::DatabaseName;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Remplace le contenu de la base active puis applique les migrations en attente (sauvegarde
+/// pré-migration d'un schéma plus ancien) ; en cas d'échec, réinjecte la copie de sécurité
+pub(super) fn swap(
+    db_path: &Path,
+    source: &Path,
+    safety_path: &Path,
+    passphrase: Option<&str>,
+    rollback_tmp: &Path,
+    migrations: &[Migration],
+) -> Result<(), String> {
+    let mut live = db::open_path(db_path)?;
+    let restored = live
+        .restore(DatabaseName::Main, source, None::<fn(rusqlite::backup::Progress)>)
+        .map_err(|e| format!("SQLite restore error: {}", e))
+        .and_then(|_| check_integrity(&live))
+        .and_then(|_| migrations::migrate(&mut live, migrations).map(|_| ()));
+    let Err(e) = restored else { return Ok(()) };
+
+    let rollback = archive::read(safety_path, passphrase)
+        .and_then(|c| fs::write(rollback_tmp, c.db).map_err(|e| e.to_string()))
+        .and_then(|_| {
+            live.restore(DatabaseName::Main, rollback_tmp, None::<fn(rusqlite::backup::Progress)>)
+                .map_err(|e| e.to_string())
+        });
+    let _ = fs::remove_file(rollback_tmp);
+    match rollback {
+        Ok(()) => Err(format!("Restore failed, database rolled back: {}", e)),
+        Err(r) => Err(format!("{} — rollback failed: {} (safety copy: {})", e, r, safety_path.display())),
+    }
+}
+
+pub(super) fn stage_files(config_dir: &Path, files: &[(String, Vec<u8>)]) -> Result<Vec<(PathBuf, String)>, String> {
+    files
+        .iter()
+        .map(|(name, bytes)| {
+            let tmp = config_dir.join(format!("{}.restore", name));
+            fs::write(&tmp, bytes).map(|_| (tmp, name.clone())).map_err(|e| e.to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,88 @@
+// Normalisation des dates/heures des trades (formats Flex XML, CSV et saisie manuelle)
+// + heure de New York (clôture US, relevés Flex) sans base de fuseaux horaires
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc, Weekday};
+
+/// Parse la date d'un trade : "20240115" (Flex XML), "2024-01-15" (CSV) ou "15/01/2024"
+pub fn parse_trade_date(raw: &str) -> Option<NaiveDate> {
+    let s = raw.trim();
+    // "2024-01-15T14:30:22" ou "2024-01-15 14:30:22" → on ne garde que la date
+    let s = s.split(['T', ' ', ';']).next().unwrap_or(s);
+    ["%Y%m%d", "%Y-%m-%d", "%d/%m/%Y", "%Y/%m/%d"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+}
+
+/// Extrait l'heure d'exécution : "143022" (Flex XML), "14:30:22" ou "14:30" (CSV)
+pub fn parse_trade_hour(raw: &str) -> Option<u32> {
+    let s = raw.trim();
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let hour = if s.contains(':') {
+        digits.parse::<u32>().ok()?
+    } else if digits.len() == 6 || digits.len() == 4 {
+        digits[..2].parse::<u32>().ok()?
+    } else {
+        return None;
+    };
+    if hour < 24 { Some(hour) } else { None }
+}
+
+/// Heure d'été US : du 2e dimanche de mars au 1er dimanche de novembre
+/// (bascule à 2 h locale ignorée : sans effet sur les heures de marché)
+fn new_york_is_dst(date: NaiveDate) -> bool {
+    let start = NaiveDate::from_weekday_of_month_opt(date.year(), 3, Weekday::Sun, 2);
+    let end = NaiveDate::from_weekday_of_month_opt(date.year(), 11, Weekday::Sun, 1);
+    matches!((start, end), (Some(start), Some(end)) if date >= start && date < end)
+}
+
+fn new_york_offset_hours(date: NaiveDate) -> i64 {
+    if new_york_is_dst(date) { -4 } else { -5 }
+}
+
+/// Heure locale de New York → UTC
+pub fn new_york_to_utc(local: NaiveDateTime) -> DateTime<Utc> {
+    (local - Duration::hours(new_york_offset_hours(local.date()))).and_utc()
+}
+
+/// UTC → heure locale de New York
+pub fn utc_to_new_york(instant: DateTime<Utc>) -> NaiveDateTime {
+    let naive = instant.naive_utc();
+    // Date locale approchée en heure d'hiver, suffisante pour choisir le décalage
+    let date = (naive - Duration::hours(5)).date();
+    naive + Duration::hours(new_york_offset_hours(date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trade_date_formats() {
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 15);
+        assert_eq!(parse_trade_date("20240115"), expected);
+        assert_eq!(parse_trade_date("2024-01-15"), expected);
+        assert_eq!(parse_trade_date("15/01/2024"), expected);
+        assert_eq!(parse_trade_date("2024-01-15 14:30:22"), expected);
+        assert_eq!(parse_trade_date(""), None);
+    }
+
+    #[test]
+    fn test_parse_trade_hour_formats() {
+        assert_eq!(parse_trade_hour("143022"), Some(14));
+        assert_eq!(parse_trade_hour("09:45:00"), Some(9));
+        assert_eq!(parse_trade_hour("9:45"), Some(9));
+        assert_eq!(parse_trade_hour(""), None);
+        assert_eq!(parse_trade_hour("99:00"), None);
+    }
+
+    #[test]
+    fn test_new_york_dst_boundaries() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").expect("datetime");
+        // 2025 : heure d'été du 9 mars au 2 novembre
+        assert_eq!(new_york_to_utc(at("2025-03-07 22:30")).to_rfc3339(), "2025-03-08T03:30:00+00:00");
+        assert_eq!(new_york_to_utc(at("2025-03-10 22:30")).to_rfc3339(), "2025-03-11T02:30:00+00:00");
+        assert_eq!(new_york_to_utc(at("2025-11-03 22:30")).to_rfc3339(), "2025-11-04T03:30:00+00:00");
+        let utc = new_york_to_utc(at("2025-07-01 22:30"));
+        assert_eq!(utc_to_new_york(utc), at("2025-07-01 22:30"));
+    }
+}
@@ -0,0 +1,36 @@
+// Accès SQLite côté Rust à la base partagée avec tauri-plugin-sql
+// Le frontend reste propriétaire des écritures courantes, Rust lit/agrège en direct
+
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Nom du fichier chargé par le frontend via `Database.load('sqlite:trading.db')`
+pub const DB_FILENAME: &str = "trading.db";
+
+/// Délai d'attente si le plugin SQL tient un verrou d'écriture
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Chemin de trading.db — tauri-plugin-sql résout les URLs relatives dans app_config_dir,
+/// on pointe donc au même endroit pour lire exactement la base utilisée par l'UI
+pub fn db_path<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(DB_FILENAME))
+}
+
+/// Ouvre une connexion vers trading.db, tolérante aux écritures concurrentes du plugin SQL
+pub fn open_connection<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Connection, String> {
+    let path = db_path(app_handle)?;
+    if !path.exists() {
+        return Err("Database file not found".to_string());
+    }
+    open_path(&path)
+}
+
+/// Ouvre un fichier SQLite quelconque (base active, sauvegarde) avec le même busy_timeout
+pub fn open_path(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| format!("SQLite open error: {}", e))?;
+    conn.busy_timeout(BUSY_TIMEOUT).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
@@ -0,0 +1,178 @@
+// Erreur typée renvoyée par les commandes Tauri, sérialisée en {code, message, details, retryable}
+// Le frontend traduit à partir de `code` (message anglais gardé pour les logs) et propose
+// "Réessayer" quand `retryable` est vrai
+// Les modules encore en Result<_, String> sont rattachés à une variante par la commande appelante
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Codes Flex Web Service temporaires (relevé en cours de génération, serveur chargé,
+/// trop de requêtes) : la même requête peut réussir plus tard
+const RETRYABLE_FLEX_CODES: [u32; 11] = [1001, 1003, 1004, 1005, 1006, 1007, 1008, 1009, 1018, 1019, 1021];
+/// Relevé encore en génération : relancé automatiquement par get_flex_executions
+pub const FLEX_STATEMENT_IN_PROGRESS: u32 = 1019;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// Requête HTTP impossible ou réponse HTTP en erreur (Yahoo, IBKR, S3)
+    Network(String),
+    /// Token Flex / identifiants absents ou refusés, coffre verrouillé
+    Auth(String),
+    /// Erreur applicative du Flex Web Service (<ErrorCode> de la réponse IBKR)
+    FlexService { code: u32, message: String },
+    /// Contenu illisible ; ligne / colonne quand le format les fournit
+    Parse { line: Option<usize>, column: Option<usize>, source_format: String, message: String },
+    Database(String),
+    Backup(String),
+    TwsConnection(String),
+    /// Paramètre refusé (règle de sync, seuil, chemin)
+    Validation(String),
+    /// Erreur non classée d'un module encore en Result<_, String>
+    Internal(String),
+}
+
+impl AppError {
+    /// Erreur Flex Web Service à partir du corps XML (<ErrorCode>, <ErrorMessage>)
+    pub fn from_flex_response(body: &str) -> Option<Self> {
+        let code = xml_text(body, "ErrorCode")?.parse().ok()?;
+        let message = xml_text(body, "ErrorMessage").unwrap_or("Flex Web Service error").to_string();
+        Some(Self::FlexService { code, message })
+    }
+
+    /// Erreur serde_json avec la position dans le document
+    pub fn json(source_format: &str, e: &serde_json::Error) -> Self {
+        Self::Parse {
+            line: Some(e.line()),
+            column: Some(e.column()),
+            source_format: source_format.to_string(),
+            message: e.to_string(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Network(_) => "network",
+            Self::Auth(_) => "auth",
+            Self::FlexService { .. } => "flex_service",
+            Self::Parse { .. } => "parse",
+            Self::Database(_) => "database",
+            Self::Backup(_) => "backup",
+            Self::TwsConnection(_) => "tws_connection",
+            Self::Validation(_) => "validation",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::Network(_) | Self::TwsConnection(_) => true,
+            Self::FlexService { code, .. } => RETRYABLE_FLEX_CODES.contains(code),
+            _ => false,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::FlexService { code, message } => format!("Flex error {}: {}", code, message),
+            Self::Parse { line: Some(line), column, source_format, message } => {
+                format!("{} parse error at line {}, column {}: {}", source_format, line, column.unwrap_or(0), message)
+            }
+            Self::Parse { source_format, message, .. } => format!("{} parse error: {}", source_format, message),
+            Self::Network(m)
+            | Self::Auth(m)
+            | Self::Database(m)
+            | Self::Backup(m)
+            | Self::TwsConnection(m)
+            | Self::Validation(m)
+            | Self::Internal(m) => m.clone(),
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            Self::FlexService { code, .. } => json!({ "flex_code": code }),
+            Self::Parse { line, column, source_format, .. } => {
+                json!({ "line": line, "column": column, "source_format": source_format })
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message())?;
+        state.serialize_field("details", &self.details())?;
+        state.serialize_field("retryable", &self.retryable())?;
+        state.end()
+    }
+}
+
+/// Modules en Result<_, String> : `?` reste utilisable sur une AppError
+impl From<AppError> for String {
+    fn from(e: AppError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Message d'un module non encore typé, sans cause connue
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::Internal(message)
+    }
+}
+
+fn xml_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..end].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_message_details_and_retryable() {
+        let body = "<FlexStatementResponse><Status>Warn</Status><ErrorCode>1019</ErrorCode>\
+                    <ErrorMessage>Statement generation in progress.</ErrorMessage></FlexStatementResponse>";
+        let error = AppError::from_flex_response(body).expect("flex error");
+        assert_eq!(
+            serde_json::to_value(&error).expect("json"),
+            json!({
+                "code": "flex_service",
+                "message": "Flex error 1019: Statement generation in progress.",
+                "details": { "flex_code": 1019 },
+                "retryable": true
+            })
+        );
+        // Token expiré : relancer ne sert à rien
+        let expired = AppError::FlexService { code: 1012, message: "Token has expired.".into() };
+        assert!(!expired.retryable());
+        assert!(AppError::from_flex_response("<Status>Success</Status>").is_none());
+    }
+
+    #[test]
+    fn test_json_parse_error_keeps_position() {
+        let e = serde_json::from_str::<Value>("{\n  \"a\": ,\n}").expect_err("invalid json");
+        let error = AppError::json("json", &e);
+        let value = serde_json::to_value(&error).expect("json");
+        assert_eq!(value["code"], "parse");
+        assert_eq!(value["details"]["line"], 2);
+        assert_eq!(value["details"]["source_format"], "json");
+        assert!(!error.retryable());
+        assert_eq!(String::from(AppError::Database("locked".into())), "locked");
+    }
+}
@@ -0,0 +1,139 @@
+// Cache SQLite des barres historiques (price_bars) : une période déjà chargée n'est plus
+// redemandée aux fournisseurs. Seule la partie révolue d'une plage est marquée comme couverte
+
+use crate::modules::market_data::{Bar, BarInterval, ProviderKind};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{params, Connection};
+
+/// Format fixe : l'ordre alphabétique suit l'ordre chronologique
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format(TIME_FORMAT).to_string()
+}
+
+fn parse_time(raw: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(raw, TIME_FORMAT).ok().map(|t| t.and_utc())
+}
+
+/// Barres d'un symbole sur [from, to[
+#[derive(Debug, Clone)]
+pub struct BarRequest {
+    pub symbol: String,
+    pub interval: BarInterval,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+/// Barres demandées si une plage chargée couvre toute la période, avec son fournisseur
+pub fn cached(conn: &Connection, request: &BarRequest) -> Result<Option<(String, Vec<Bar>)>, String> {
+    let BarRequest { symbol, interval, .. } = request;
+    let (from, to) = (format_time(request.from), format_time(request.to));
+    let provider: Option<String> = conn
+        .query_row(
+            "SELECT provider FROM price_bar_ranges
+             WHERE symbol = ?1 AND bar_interval = ?2 AND range_start <= ?3 AND range_end >= ?4
+             ORDER BY fetched_at DESC LIMIT 1",
+            params![symbol, interval.as_str(), from, to],
+            |r| r.get(0),
+        )
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(format!("price_bar_ranges query error: {}", e)),
+        })?;
+    let Some(provider) = provider else { return Ok(None) };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT time, open, high, low, close, volume FROM price_bars
+             WHERE symbol = ?1 AND bar_interval = ?2 AND time >= ?3 AND time < ?4 ORDER BY time",
+        )
+        .map_err(|e| format!("price_bars query error: {}", e))?;
+    let rows = stmt
+        .query_map(params![symbol, interval.as_str(), from, to], |r| {
+            Ok((r.get::<_, String>(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?))
+        })
+        .map_err(|e| format!("price_bars query error: {}", e))?;
+    let mut bars = Vec::new();
+    for row in rows {
+        let (time, open, high, low, close, volume) = row.map_err(|e| e.to_string())?;
+        if let Some(time) = parse_time(&time) {
+            bars.push(Bar { time, open, high, low, close, volume });
+        }
+    }
+    Ok(Some((provider, bars)))
+}
+
+/// Enregistre les barres ; la plage n'est marquée couverte que jusqu'à `complete_before`
+/// (la barre en cours ou à venir sera redemandée)
+pub fn store(
+    conn: &mut Connection,
+    request: &BarRequest,
+    provider: ProviderKind,
+    bars: &[Bar],
+    complete_before: DateTime<Utc>,
+) -> Result<(), String> {
+    let BarRequest { symbol, interval, from, to } = request;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for bar in bars.iter().filter(|b| b.time < complete_before) {
+        tx.execute(
+            "INSERT OR REPLACE INTO price_bars (symbol, bar_interval, time, open, high, low, close, volume)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                symbol,
+                interval.as_str(),
+                format_time(bar.time),
+                bar.open,
+                bar.high,
+                bar.low,
+                bar.close,
+                bar.volume
+            ],
+        )
+        .map_err(|e| format!("price_bars insert error: {}", e))?;
+    }
+    let range_end = (*to).min(complete_before);
+    if range_end > *from {
+        tx.execute(
+            "INSERT INTO price_bar_ranges (symbol, bar_interval, range_start, range_end, provider)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![symbol, interval.as_str(), format_time(*from), format_time(range_end), provider.as_str()],
+        )
+        .map_err(|e| format!("price_bar_ranges insert error: {}", e))?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::migrations::{migrate, MIGRATIONS};
+    use chrono::TimeDelta;
+
+    fn day(n: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_749_513_600, 0).expect("time") + TimeDelta::days(n)
+    }
+
+    fn bar(n: i64, close: f64) -> Bar {
+        Bar { time: day(n), open: close, high: close + 1.0, low: close - 1.0, close, volume: Some(10.0) }
+    }
+
+    #[test]
+    fn test_only_completed_ranges_are_served_from_cache() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("schema");
+        let request =
+            |interval, from, to| BarRequest { symbol: "SPY".to_string(), interval, from: day(from), to: day(to) };
+        let bars = [bar(0, 100.0), bar(1, 101.0), bar(4, 102.0), bar(5, 103.0)];
+        store(&mut conn, &request(BarInterval::Day, 0, 6), ProviderKind::Yahoo, &bars, day(5)).expect("store");
+
+        // Couvert jusqu'au jour 5 exclu : le week-end (jours 2-3) ne force pas de rechargement
+        let (provider, cached_bars) =
+            cached(&conn, &request(BarInterval::Day, 0, 5)).expect("query").expect("cache hit");
+        assert_eq!(provider, "yahoo");
+        assert_eq!(cached_bars, bars[..3].to_vec());
+        assert!(cached(&conn, &request(BarInterval::Day, 0, 6)).expect("query").is_none());
+        assert!(cached(&conn, &request(BarInterval::Hour, 0, 2)).expect("query").is_none());
+    }
+}
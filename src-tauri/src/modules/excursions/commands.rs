@@ -0,0 +1,19 @@
+// Commandes Tauri des MAE / MFE des trades clôturés
+
+use super::{ExcursionOptions, ExcursionReport, TradeExcursion};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: MAE / MFE des trades clôturés (barres historiques en cache SQLite) enregistrés par trade
+#[tauri::command]
+pub async fn compute_trade_excursions(
+    app_handle: tauri::AppHandle,
+    options: Option<ExcursionOptions>,
+) -> Result<ExcursionReport, AppError> {
+    super::compute_closed_trades(&app_handle, options.unwrap_or_default()).await
+}
+
+/// Commande Tauri: Excursions enregistrées (sorties les plus récentes d'abord)
+#[tauri::command]
+pub fn get_trade_excursions(app_handle: tauri::AppHandle) -> Result<Vec<TradeExcursion>, AppError> {
+    super::load_excursions(&app_handle).map_err(AppError::Database)
+}
@@ -0,0 +1,119 @@
+// MAE / MFE d'un trade à partir des barres de la période de détention, et mouvement laissé
+// après la sortie. Les dates d'entrée / sortie sont journalières : les barres du jour d'entrée
+// comptent entièrement (mouvement antérieur à l'entrée compris)
+
+use crate::modules::market_data::Bar;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Long,
+    Short,
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Long => "long",
+            Self::Short => "short",
+        }
+    }
+}
+
+/// Écarts en prix unitaire (toujours ≥ 0 sauf `captured`), pourcentages du prix d'entrée
+/// (du prix de sortie pour left_on_table)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Excursion {
+    /// Pire mouvement contre la position pendant la détention
+    pub mae: f64,
+    pub mae_pct: f64,
+    /// Meilleur mouvement en faveur de la position pendant la détention
+    pub mfe: f64,
+    pub mfe_pct: f64,
+    /// Mouvement effectivement encaissé (sortie - entrée, signé selon le sens)
+    pub captured: f64,
+    /// captured / mfe ; None si le trade n'a jamais été gagnant
+    pub capture_ratio: Option<f64>,
+    /// Meilleur mouvement en faveur après la sortie ; None sans barre postérieure
+    pub left_on_table: Option<f64>,
+    pub left_on_table_pct: Option<f64>,
+    pub bars_held: usize,
+    pub bars_after: usize,
+}
+
+fn extremes(bars: &[Bar]) -> Option<(f64, f64)> {
+    let high = bars.iter().map(|b| b.high).reduce(f64::max)?;
+    let low = bars.iter().map(|b| b.low).reduce(f64::min)?;
+    Some((high, low))
+}
+
+/// None sans barre pendant la détention ou avec un prix d'entrée / sortie invalide
+pub fn compute(side: Side, entry: f64, exit: f64, held: &[Bar], after: &[Bar]) -> Option<Excursion> {
+    if entry <= 0.0 || exit <= 0.0 {
+        return None;
+    }
+    // Les prix exécutés font partie de la période, même si les barres les manquent
+    let (high, low) = extremes(held)?;
+    let (high, low) = (high.max(entry).max(exit), low.min(entry).min(exit));
+
+    let (mae, mfe, captured) = match side {
+        Side::Long => (entry - low, high - entry, exit - entry),
+        Side::Short => (high - entry, entry - low, entry - exit),
+    };
+    let left_on_table = extremes(after).map(|(high, low)| match side {
+        Side::Long => (high - exit).max(0.0),
+        Side::Short => (exit - low).max(0.0),
+    });
+
+    Some(Excursion {
+        mae,
+        mae_pct: mae / entry * 100.0,
+        mfe,
+        mfe_pct: mfe / entry * 100.0,
+        captured,
+        capture_ratio: (mfe > 0.0).then(|| captured / mfe),
+        left_on_table,
+        left_on_table_pct: left_on_table.map(|l| l / exit * 100.0),
+        bars_held: held.len(),
+        bars_after: after.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn bar(day: i64, high: f64, low: f64) -> Bar {
+        let time = DateTime::from_timestamp(1_749_513_600 + day * 86_400, 0).expect("time");
+        Bar { time, open: low, high, low, close: high, volume: None }
+    }
+
+    #[test]
+    fn test_long_trade_excursions_and_move_left_after_exit() {
+        let held = [bar(0, 105.0, 98.0), bar(1, 112.0, 101.0), bar(2, 110.0, 104.0)];
+        let after = [bar(3, 115.0, 107.0), bar(4, 113.0, 106.0)];
+        let e = compute(Side::Long, 100.0, 108.0, &held, &after).expect("excursion");
+        assert_eq!((e.mae, e.mfe, e.captured), (2.0, 12.0, 8.0));
+        assert_eq!((e.mae_pct, e.mfe_pct), (2.0, 12.0));
+        assert!((e.capture_ratio.expect("ratio") - 8.0 / 12.0).abs() < 1e-12);
+        assert_eq!(e.left_on_table, Some(7.0));
+        assert_eq!((e.bars_held, e.bars_after), (3, 2));
+    }
+
+    #[test]
+    fn test_short_losing_trade_and_missing_data() {
+        let held = [bar(0, 52.0, 49.5), bar(1, 55.0, 50.5)];
+        let e = compute(Side::Short, 50.0, 54.0, &held, &[]).expect("excursion");
+        assert_eq!((e.mae, e.mfe, e.captured), (5.0, 0.5, -4.0));
+        assert_eq!(e.capture_ratio, Some(-8.0));
+        assert_eq!(e.left_on_table, None);
+
+        // Trade jamais gagnant : pas de ratio ; barres absentes ou prix nul : pas de calcul
+        let flat = compute(Side::Long, 10.0, 9.0, &[bar(0, 10.0, 8.5)], &[]).expect("excursion");
+        assert_eq!(flat.capture_ratio, None);
+        assert!(compute(Side::Long, 10.0, 9.0, &[], &[]).is_none());
+        assert!(compute(Side::Long, 0.0, 9.0, &held, &[]).is_none());
+    }
+}
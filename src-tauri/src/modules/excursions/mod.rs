@@ -0,0 +1,228 @@
+// MAE / MFE des trades clôturés : barres historiques de la période de détention (et des jours
+// suivant la sortie) via les fournisseurs market_data, mises en cache dans price_bars, puis
+// résultat enregistré par trade dans trade_excursions pour les vues Statistiques
+// Les connexions SQLite sont ouvertes par étape, jamais gardées pendant un appel réseau
+
+mod bar_cache;
+pub mod commands;
+mod metrics;
+mod store;
+
+pub use metrics::{compute, Excursion, Side};
+pub use store::TradeExcursion;
+
+use crate::modules::error::AppError;
+use crate::modules::logging::MARKET_DATA;
+use crate::modules::market_data::{fetch_bars_with_fallback, BarInterval, QuoteProvider, QuoteService};
+use crate::modules::{dates, db};
+use bar_cache::BarRequest;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use store::ClosedTrade;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Jours calendaires observés après la sortie par défaut
+pub const DEFAULT_POST_EXIT_DAYS: u32 = 10;
+
+fn default_post_exit_days() -> u32 {
+    DEFAULT_POST_EXIT_DAYS
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExcursionOptions {
+    #[serde(default)]
+    pub interval: BarInterval,
+    #[serde(default = "default_post_exit_days")]
+    pub post_exit_days: u32,
+    /// Recalcule aussi les trades déjà présents dans trade_excursions
+    #[serde(default)]
+    pub recompute: bool,
+}
+
+impl Default for ExcursionOptions {
+    fn default() -> Self {
+        Self { interval: BarInterval::default(), post_exit_days: DEFAULT_POST_EXIT_DAYS, recompute: false }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedTrade {
+    pub trade_id: i64,
+    pub symbol: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExcursionReport {
+    pub computed: usize,
+    /// Trades dont les barres venaient entièrement du cache SQLite
+    pub from_cache: usize,
+    pub skipped: Vec<SkippedTrade>,
+}
+
+/// Même règle que useLivePrices : une crypto saisie sans paire est cotée en USDT
+fn bar_symbol(trade: &ClosedTrade) -> String {
+    let symbol = trade.symbol.trim().to_uppercase();
+    let crypto =
+        trade.asset_type.as_deref() == Some("crypto") || matches!(trade.broker.as_deref(), Some("Binance" | "Bybit"));
+    let has_quote = ["USDT", "BUSD", "USD", "EUR"].iter().any(|q| symbol.contains(q));
+    if crypto && symbol.len() <= 5 && !has_quote {
+        format!("{}USDT", symbol)
+    } else {
+        symbol
+    }
+}
+
+fn start_of(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Barres de la détention (jour d'entrée au jour de sortie inclus) puis des `post_exit_days` suivants,
+/// avec la fin de la détention (minuit suivant le jour de sortie)
+fn bar_request(
+    trade: &ClosedTrade,
+    options: &ExcursionOptions,
+    now: DateTime<Utc>,
+) -> Result<(BarRequest, DateTime<Utc>), String> {
+    let parsed = (dates::parse_trade_date(&trade.open_date), dates::parse_trade_date(&trade.exit_date));
+    let (Some(open), Some(exit)) = parsed else {
+        return Err(format!("Unreadable dates '{}' / '{}'", trade.open_date, trade.exit_date));
+    };
+    if exit < open {
+        return Err("Exit date before open date".to_string());
+    }
+    let exit_end = start_of(exit) + TimeDelta::days(1);
+    let to = exit_end + TimeDelta::days(i64::from(options.post_exit_days));
+    let request =
+        BarRequest { symbol: bar_symbol(trade), interval: options.interval, from: start_of(open), to: to.min(now) };
+    Ok((request, exit_end))
+}
+
+/// Excursion d'un trade ; Err(raison) pour un trade ignoré. Le booléen indique un succès de cache
+async fn excursion_for<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    providers: &[Box<dyn QuoteProvider>],
+    trade: &ClosedTrade,
+    options: &ExcursionOptions,
+    now: DateTime<Utc>,
+) -> Result<(TradeExcursion, bool), String> {
+    let (request, exit_end) = bar_request(trade, options, now)?;
+    let cached = {
+        let conn = db::open_connection(app_handle)?;
+        bar_cache::cached(&conn, &request)?
+    };
+    let from_cache = cached.is_some();
+    let (provider, bars) = match cached {
+        Some(hit) => hit,
+        None => {
+            let fetched =
+                fetch_bars_with_fallback(providers, &request.symbol, request.interval, request.from, request.to)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let Some((kind, bars)) = fetched else {
+                return Err(format!("No price history for {}", request.symbol));
+            };
+            // La barre du jour n'est pas définitive : seule la période révolue est mise en cache
+            let mut conn = db::open_connection(app_handle)?;
+            bar_cache::store(&mut conn, &request, kind, &bars, start_of(now.date_naive()))?;
+            (kind.as_str().to_string(), bars)
+        }
+    };
+
+    let (held, after): (Vec<_>, Vec<_>) = bars.into_iter().partition(|b| b.time < exit_end);
+    let excursion = compute(trade.side, trade.entry, trade.exit, &held, &after)
+        .ok_or_else(|| "No bar during the holding period or missing entry price".to_string())?;
+
+    let record = TradeExcursion {
+        trade_id: trade.id,
+        symbol: request.symbol,
+        strategy: None,
+        open_date: trade.open_date.clone(),
+        exit_date: trade.exit_date.clone(),
+        side: trade.side,
+        entry_price: trade.entry,
+        exit_price: trade.exit,
+        bar_interval: request.interval.as_str().to_string(),
+        provider,
+        post_exit_days: options.post_exit_days,
+        excursion,
+        computed_at: None,
+    };
+    Ok((record, from_cache))
+}
+
+/// Calcule et enregistre les excursions des trades clôturés (non encore calculés sauf `recompute`)
+pub async fn compute_closed_trades<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    options: ExcursionOptions,
+) -> Result<ExcursionReport, AppError> {
+    let trades = {
+        let conn = db::open_connection(app_handle).map_err(AppError::Database)?;
+        store::closed_trades(&conn, options.recompute).map_err(AppError::Database)?
+    };
+    let providers = app_handle.state::<QuoteService>().configured_providers(app_handle)?;
+    let now = Utc::now();
+
+    let mut report = ExcursionReport::default();
+    for trade in &trades {
+        match excursion_for(app_handle, &providers, trade, &options, now).await {
+            Ok((record, from_cache)) => {
+                let conn = db::open_connection(app_handle).map_err(AppError::Database)?;
+                store::save(&conn, &record).map_err(AppError::Database)?;
+                report.computed += 1;
+                report.from_cache += usize::from(from_cache);
+            }
+            Err(reason) => {
+                tracing::warn!(target: MARKET_DATA, trade_id = trade.id, symbol = %trade.symbol, reason = %reason,
+                    "Trade excursion skipped");
+                report.skipped.push(SkippedTrade { trade_id: trade.id, symbol: trade.symbol.clone(), reason });
+            }
+        }
+    }
+    tracing::info!(target: MARKET_DATA, computed = report.computed, from_cache = report.from_cache,
+        skipped = report.skipped.len(), interval = options.interval.as_str(), "Trade excursions computed");
+    Ok(report)
+}
+
+pub fn load_excursions<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Vec<TradeExcursion>, String> {
+    let conn = db::open_connection(app_handle)?;
+    store::load_all(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(symbol: &str, asset_type: Option<&str>, open: &str, exit: &str) -> ClosedTrade {
+        ClosedTrade {
+            id: 1,
+            symbol: symbol.to_string(),
+            asset_type: asset_type.map(str::to_string),
+            broker: None,
+            side: Side::Long,
+            entry: 10.0,
+            exit: 11.0,
+            open_date: open.to_string(),
+            exit_date: exit.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bar_request_covers_holding_period_and_post_exit_window() {
+        let now = start_of(NaiveDate::from_ymd_opt(2025, 7, 1).expect("date"));
+        let options = ExcursionOptions::default();
+        let day = |m, d| start_of(NaiveDate::from_ymd_opt(2025, m, d).expect("date"));
+
+        let (request, exit_end) = bar_request(&trade("wld", Some("crypto"), "2025-06-02", "2025-06-10"), &options, now)
+            .expect("request");
+        assert_eq!(request.symbol, "WLDUSDT");
+        assert_eq!((request.from, exit_end, request.to), (day(6, 2), day(6, 11), day(6, 21)));
+
+        // Sortie récente : la fenêtre s'arrête à maintenant
+        let (recent, _) =
+            bar_request(&trade("AAPL", None, "2025-06-20", "2025-06-27"), &options, now).expect("request");
+        assert_eq!((recent.symbol.as_str(), recent.to), ("AAPL", now));
+        assert!(bar_request(&trade("AAPL", None, "2025-06-20", "2025-06-10"), &options, now).is_err());
+    }
+}
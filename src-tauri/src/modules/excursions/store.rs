@@ -0,0 +1,218 @@
+// Trades clôturés éligibles (jambe action : Rockets, actions de la Wheel) et table trade_excursions
+
+use super::metrics::{Excursion, Side};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+/// Trade clôturé tel que lu dans trades / legs ; dates brutes (parsées par l'appelant)
+#[derive(Debug, Clone)]
+pub struct ClosedTrade {
+    pub id: i64,
+    pub symbol: String,
+    pub asset_type: Option<String>,
+    pub broker: Option<String>,
+    pub side: Side,
+    pub entry: f64,
+    pub exit: f64,
+    pub open_date: String,
+    pub exit_date: String,
+}
+
+/// Ligne de trade_excursions, avec les infos du trade utiles aux vues Statistiques
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeExcursion {
+    pub trade_id: i64,
+    /// Symbole des barres (paire USDT pour une crypto saisie sans paire)
+    pub symbol: String,
+    pub strategy: Option<String>,
+    pub open_date: String,
+    pub exit_date: String,
+    pub side: Side,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub bar_interval: String,
+    pub provider: String,
+    /// Fenêtre (jours calendaires) du mouvement laissé après la sortie
+    pub post_exit_days: u32,
+    #[serde(flatten)]
+    pub excursion: Excursion,
+    pub computed_at: Option<String>,
+}
+
+/// Trades clôturés avec prix et dates de sortie ; ceux déjà calculés sont exclus sauf `include_computed`
+pub fn closed_trades(conn: &Connection, include_computed: bool) -> Result<Vec<ClosedTrade>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.symbol, t.asset_type, t.broker, l.side, COALESCE(t.entry_executed, l.open_price),
+                    t.exit_price, t.open_date, t.exit_date
+             FROM trades t
+             JOIN legs l ON l.id = (SELECT MIN(id) FROM legs WHERE trade_id = t.id AND type = 'stock')
+             WHERE t.status = 'closed' AND COALESCE(t.is_deleted, 0) = 0
+               AND t.exit_price IS NOT NULL AND t.exit_date IS NOT NULL AND t.open_date IS NOT NULL
+               AND (?1 OR t.id NOT IN (SELECT trade_id FROM trade_excursions))
+             ORDER BY t.exit_date",
+        )
+        .map_err(|e| format!("closed trades query error: {}", e))?;
+    let rows = stmt
+        .query_map([include_computed], |r| {
+            let side: String = r.get(4)?;
+            Ok(ClosedTrade {
+                id: r.get(0)?,
+                symbol: r.get(1)?,
+                asset_type: r.get(2)?,
+                broker: r.get(3)?,
+                side: if side.eq_ignore_ascii_case("short") { Side::Short } else { Side::Long },
+                entry: r.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+                exit: r.get(6)?,
+                open_date: r.get(7)?,
+                exit_date: r.get(8)?,
+            })
+        })
+        .map_err(|e| format!("closed trades query error: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+pub fn save(conn: &Connection, record: &TradeExcursion) -> Result<(), String> {
+    let e = &record.excursion;
+    conn.execute(
+        "INSERT OR REPLACE INTO trade_excursions
+           (trade_id, symbol, bar_interval, provider, side, entry_price, exit_price, mae, mae_pct, mfe, mfe_pct,
+            captured, capture_ratio, left_on_table, left_on_table_pct, post_exit_days, bars_held, bars_after,
+            computed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, CURRENT_TIMESTAMP)",
+        params![
+            record.trade_id,
+            record.symbol,
+            record.bar_interval,
+            record.provider,
+            record.side.as_str(),
+            record.entry_price,
+            record.exit_price,
+            e.mae,
+            e.mae_pct,
+            e.mfe,
+            e.mfe_pct,
+            e.captured,
+            e.capture_ratio,
+            e.left_on_table,
+            e.left_on_table_pct,
+            record.post_exit_days,
+            e.bars_held as i64,
+            e.bars_after as i64,
+        ],
+    )
+    .map_err(|e| format!("trade_excursions insert error: {}", e))?;
+    Ok(())
+}
+
+/// Excursions des trades non supprimés, sorties les plus récentes d'abord
+pub fn load_all(conn: &Connection) -> Result<Vec<TradeExcursion>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT x.trade_id, x.symbol, t.strategy, t.open_date, t.exit_date, x.side, x.entry_price, x.exit_price,
+                    x.bar_interval, x.provider, x.post_exit_days, x.mae, x.mae_pct, x.mfe, x.mfe_pct, x.captured,
+                    x.capture_ratio, x.left_on_table, x.left_on_table_pct, x.bars_held, x.bars_after, x.computed_at
+             FROM trade_excursions x
+             JOIN trades t ON t.id = x.trade_id
+             WHERE COALESCE(t.is_deleted, 0) = 0
+             ORDER BY t.exit_date DESC, x.trade_id DESC",
+        )
+        .map_err(|e| format!("trade_excursions query error: {}", e))?;
+    let rows = stmt
+        .query_map([], |r| {
+            let side: String = r.get(5)?;
+            Ok(TradeExcursion {
+                trade_id: r.get(0)?,
+                symbol: r.get(1)?,
+                strategy: r.get(2)?,
+                open_date: r.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                exit_date: r.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                side: if side == "short" { Side::Short } else { Side::Long },
+                entry_price: r.get(6)?,
+                exit_price: r.get(7)?,
+                bar_interval: r.get(8)?,
+                provider: r.get(9)?,
+                post_exit_days: r.get(10)?,
+                excursion: Excursion {
+                    mae: r.get(11)?,
+                    mae_pct: r.get(12)?,
+                    mfe: r.get(13)?,
+                    mfe_pct: r.get(14)?,
+                    captured: r.get(15)?,
+                    capture_ratio: r.get(16)?,
+                    left_on_table: r.get(17)?,
+                    left_on_table_pct: r.get(18)?,
+                    bars_held: r.get::<_, i64>(19)?.max(0) as usize,
+                    bars_after: r.get::<_, i64>(20)?.max(0) as usize,
+                },
+                computed_at: r.get(21)?,
+            })
+        })
+        .map_err(|e| format!("trade_excursions query error: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::migrations::{migrate, MIGRATIONS};
+
+    fn setup() -> Connection {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("schema");
+        conn.execute_batch(
+            "INSERT INTO trades (id, date, symbol, strategy, status, open_date, exit_date, exit_price, entry_executed)
+             VALUES (1, '2025-06-02', 'NVDA', 'rockets', 'closed', '2025-06-02', '2025-06-10', 142.0, 130.0),
+                    (2, '2025-06-03', 'AMD', 'rockets', 'open', '2025-06-03', NULL, NULL, 110.0),
+                    (3, '2025-06-04', 'SPY', 'pcs', 'closed', '2025-06-04', '2025-06-20', 1.2, NULL);
+             INSERT INTO legs (trade_id, type, side, quantity, open_price) VALUES
+                    (1, 'stock', 'long', 10, 129.5), (2, 'stock', 'long', 5, 110.0), (3, 'put', 'short', 1, 2.5);",
+        )
+        .expect("trades");
+        conn
+    }
+
+    #[test]
+    fn test_closed_stock_trades_are_loaded_once_then_saved_and_listed() {
+        let conn = setup();
+        let trades = closed_trades(&conn, false).expect("trades");
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].id, trades[0].side, trades[0].entry), (1, Side::Long, 130.0));
+
+        let excursion = Excursion {
+            mae: 3.0,
+            mae_pct: 3.0 / 130.0 * 100.0,
+            mfe: 15.0,
+            mfe_pct: 15.0 / 130.0 * 100.0,
+            captured: 12.0,
+            capture_ratio: Some(0.8),
+            left_on_table: None,
+            left_on_table_pct: None,
+            bars_held: 7,
+            bars_after: 0,
+        };
+        let record = TradeExcursion {
+            trade_id: 1,
+            symbol: "NVDA".to_string(),
+            strategy: None,
+            open_date: trades[0].open_date.clone(),
+            exit_date: trades[0].exit_date.clone(),
+            side: Side::Long,
+            entry_price: 130.0,
+            exit_price: 142.0,
+            bar_interval: "1d".to_string(),
+            provider: "yahoo".to_string(),
+            post_exit_days: 10,
+            excursion: excursion.clone(),
+            computed_at: None,
+        };
+        save(&conn, &record).expect("save");
+
+        assert!(closed_trades(&conn, false).expect("trades").is_empty());
+        assert_eq!(closed_trades(&conn, true).expect("trades").len(), 1);
+        let listed = load_all(&conn).expect("excursions");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].strategy.as_deref(), Some("rockets"));
+        assert_eq!(listed[0].excursion, excursion);
+    }
+}
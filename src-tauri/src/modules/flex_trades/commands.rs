@@ -0,0 +1,49 @@
+// Commandes Tauri des trades Flex : récupération (Web Service ou CSV) et import idempotent
+
+use super::ImportReport;
+use crate::modules::audit::Actor;
+use crate::modules::error::AppError;
+use crate::modules::secrets::{self, SecretKind};
+use crate::modules::tws_socket::{FlexTrade, TWSConfig, TWSSyncClient};
+use std::collections::HashMap;
+
+/// Commande Tauri: Récupère l'historique complet via Flex Query (NOUVEAU - Socket TCP + Flex)
+/// Token lu dans le magasin de secrets (profil "default" par défaut) si `flex_token` est absent
+#[tauri::command]
+pub async fn fetch_flex_trades(
+    app_handle: tauri::AppHandle,
+    query_id: i32,
+    profile: Option<String>,
+    flex_token: Option<String>,
+) -> Result<Vec<FlexTrade>, AppError> {
+    let flex_token = match flex_token.filter(|t| !t.is_empty()) {
+        Some(token) => token,
+        None => {
+            let profile = profile.as_deref().unwrap_or(secrets::DEFAULT_PROFILE);
+            secrets::get(&app_handle, profile, SecretKind::FlexToken)?
+                .ok_or_else(|| AppError::Auth(format!("No Flex token saved for profile '{}'", profile)))?
+        }
+    };
+    let client = TWSSyncClient::new(TWSConfig::default());
+    client.get_flex_trades(&flex_token, query_id).await
+}
+
+/// Commande Tauri: Parse un CSV Flex Query fourni en string (import fichier local)
+#[tauri::command]
+pub async fn parse_flex_trades_csv(csv_content: String) -> Result<Vec<FlexTrade>, AppError> {
+    let client = TWSSyncClient::new(TWSConfig::default());
+    client.parse_csv_public(csv_content).await
+}
+
+/// Commande Tauri: Enregistre des FlexTrade (sync ou import CSV) en une transaction idempotente
+#[tauri::command]
+pub fn import_flex_trades(
+    app_handle: tauri::AppHandle,
+    trades: Vec<FlexTrade>,
+    strategy_overrides: Option<HashMap<String, String>>,
+    actor: Option<Actor>,
+) -> Result<ImportReport, AppError> {
+    let actor = actor.unwrap_or(Actor::Import);
+    super::import_flex_trades(&app_handle, &trades, &strategy_overrides.unwrap_or_default(), actor)
+        .map_err(AppError::Database)
+}
@@ -0,0 +1,53 @@
+// Comparaison d'un trade importé avec la ligne déjà en base : même trade (identité),
+// puis mêmes valeurs IBKR (inchangé) ou non (mise à jour)
+
+use crate::modules::tws_socket::FlexTrade;
+
+/// Champs qui identifient le trade : s'ils divergent, ce n'est pas le même trade.
+/// Une valeur absente en base (ancien import CSV) n'est pas une divergence
+pub(super) fn identity_diff(existing: &FlexTrade, incoming: &FlexTrade) -> Vec<String> {
+    let texts = [
+        ("account_id", &existing.account_id, &incoming.account_id),
+        ("symbol", &existing.symbol, &incoming.symbol),
+        ("date", &existing.date, &incoming.date),
+        ("side", &existing.side, &incoming.side),
+        ("put_call", &existing.put_call, &incoming.put_call),
+        ("expiry", &existing.expiry, &incoming.expiry),
+    ];
+    let mut fields: Vec<String> = texts
+        .iter()
+        .filter(|(_, a, b)| !a.is_empty() && !b.is_empty() && !a.eq_ignore_ascii_case(b))
+        .map(|(name, _, _)| name.to_string())
+        .collect();
+    if existing.strike != 0.0 && incoming.strike != 0.0 && !approx_eq(existing.strike, incoming.strike) {
+        fields.push("strike".to_string());
+    }
+    fields
+}
+
+pub(super) fn same_broker_fields(a: &FlexTrade, b: &FlexTrade) -> bool {
+    let texts = a.account_id == b.account_id
+        && a.symbol == b.symbol
+        && a.asset_class == b.asset_class
+        && a.side == b.side
+        && a.date == b.date
+        && a.time == b.time
+        && a.expiry == b.expiry
+        && a.put_call == b.put_call
+        && a.open_close == b.open_close
+        && a.exchange == b.exchange
+        && a.notes == b.notes;
+    let numbers = [
+        (a.price, b.price),
+        (a.commission, b.commission),
+        (a.realized_pnl, b.realized_pnl),
+        (a.strike, b.strike),
+        (a.proceeds, b.proceeds),
+        (a.cost_basis, b.cost_basis),
+    ];
+    texts && a.quantity == b.quantity && a.multiplier == b.multiplier && numbers.iter().all(|(x, y)| approx_eq(*x, *y))
+}
+
+fn approx_eq(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
@@ -0,0 +1,274 @@
+// Dépôt flex_trades : import idempotent des FlexTrade (sync Flex Query, import CSV)
+// Une seule transaction par import ; les choix de l'utilisateur (strategy, is_deleted)
+// ne sont jamais écrasés, seuls les champs fournis par IBKR sont mis à jour
+
+pub mod commands;
+mod compare;
+mod report;
+mod strategy;
+
+pub use report::{ImportConflict, ImportReport};
+pub use strategy::detect_strategy;
+
+use crate::modules::audit::{self, Actor};
+use crate::modules::db;
+use crate::modules::tws_socket::FlexTrade;
+use chrono::{SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::collections::HashMap;
+use tauri::{AppHandle, Runtime};
+use compare::{identity_diff, same_broker_fields};
+
+/// Ligne de sync_metadata mise à jour par chaque import
+pub const SYNC_ACCOUNT: &str = "FLEX";
+
+/// Commande à l'origine des écritures, reprise dans audit_logs
+const IMPORT_COMMAND: &str = "import_flex_trades";
+
+/// `strategy_overrides` (trade_id → stratégie) ne s'applique qu'aux nouveaux trades ;
+/// `actor` : sync (Flex Query) ou import (fichier CSV)
+pub fn import_flex_trades<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    trades: &[FlexTrade],
+    strategy_overrides: &HashMap<String, String>,
+    actor: Actor,
+) -> Result<ImportReport, String> {
+    let mut conn = db::open_connection(app_handle)?;
+    upsert(&mut conn, trades, strategy_overrides, actor)
+}
+
+/// Import transactionnel sur une connexion ouverte (trades, audit et sync_metadata ensemble)
+pub fn upsert(
+    conn: &mut Connection,
+    trades: &[FlexTrade],
+    strategy_overrides: &HashMap<String, String>,
+    actor: Actor,
+) -> Result<ImportReport, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let report = audit::with_context(&tx, actor, IMPORT_COMMAND, |tx| upsert_all(tx, trades, strategy_overrides))?;
+    tx.execute(
+        // next_sync_date (planificateur) conservé
+        "INSERT INTO sync_metadata (account_id, last_sync_date, trades_synced) VALUES (?1, ?2, ?3)
+         ON CONFLICT(account_id) DO UPDATE SET last_sync_date = excluded.last_sync_date,
+           trades_synced = excluded.trades_synced",
+        params![SYNC_ACCOUNT, Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true), report.inserted as i64],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+fn upsert_all(
+    tx: &Transaction,
+    trades: &[FlexTrade],
+    strategy_overrides: &HashMap<String, String>,
+) -> Result<ImportReport, String> {
+    let mut report = ImportReport::default();
+
+    for trade in trades {
+        if trade.open_close.eq_ignore_ascii_case("O") {
+            report.skipped_open += 1;
+            continue;
+        }
+        match stored(tx, &trade.trade_id)? {
+            None => {
+                let strategy = strategy_overrides
+                    .get(&trade.trade_id)
+                    .cloned()
+                    .unwrap_or_else(|| detect_strategy(trade).to_string());
+                insert(tx, trade, &strategy)?;
+                report.inserted += 1;
+            }
+            Some(existing) => {
+                let diverging = identity_diff(&existing, trade);
+                if !diverging.is_empty() {
+                    report.conflicts.push(ImportConflict {
+                        trade_id: trade.trade_id.clone(),
+                        symbol: trade.symbol.clone(),
+                        date: trade.date.clone(),
+                        fields: diverging,
+                    });
+                } else if same_broker_fields(&existing, trade) {
+                    report.unchanged += 1;
+                } else {
+                    // Correction IBKR (commission, P&L réalisé...) : strategy / is_deleted conservés
+                    update(tx, trade)?;
+                    report.updated += 1;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Colonnes flex_trades alimentées par IBKR, dans l'ordre des paramètres ?1..?20
+const BROKER_COLUMNS: &str = "trade_id, account_id, symbol, asset_class, side, quantity, multiplier, price, \
+    commission, realized_pnl, date, time, expiry, strike, put_call, open_close, exchange, proceeds, cost_basis, notes";
+
+fn stored(tx: &Transaction, trade_id: &str) -> Result<Option<FlexTrade>, String> {
+    // NULL (lignes insérées par l'ancien import JS) lus comme valeurs vides
+    let sql = format!("SELECT {} FROM flex_trades WHERE trade_id = ?1", BROKER_COLUMNS);
+    tx.query_row(&sql, [trade_id], |r| {
+        let text = |i: usize| r.get::<_, Option<String>>(i).map(Option::unwrap_or_default);
+        let real = |i: usize| r.get::<_, Option<f64>>(i).map(Option::unwrap_or_default);
+        let int = |i: usize| r.get::<_, Option<i32>>(i).map(Option::unwrap_or_default);
+        Ok(FlexTrade {
+            trade_id: text(0)?,
+            account_id: text(1)?,
+            symbol: text(2)?,
+            asset_class: text(3)?,
+            side: text(4)?,
+            quantity: int(5)?,
+            multiplier: int(6)?,
+            price: real(7)?,
+            commission: real(8)?,
+            realized_pnl: real(9)?,
+            date: text(10)?,
+            time: text(11)?,
+            expiry: text(12)?,
+            strike: real(13)?,
+            put_call: text(14)?,
+            open_close: text(15)?,
+            exchange: text(16)?,
+            proceeds: real(17)?,
+            cost_basis: real(18)?,
+            notes: text(19)?,
+        })
+    })
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn insert(tx: &Transaction, t: &FlexTrade, strategy: &str) -> Result<(), String> {
+    let sql = format!(
+        "INSERT INTO flex_trades ({}, strategy, synced_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, CURRENT_TIMESTAMP)",
+        BROKER_COLUMNS
+    );
+    tx.execute(&sql, params![
+        t.trade_id, t.account_id, t.symbol, t.asset_class, t.side, t.quantity, t.multiplier, t.price,
+        t.commission, t.realized_pnl, t.date, t.time, t.expiry, t.strike, t.put_call, t.open_close,
+        t.exchange, t.proceeds, t.cost_basis, t.notes, strategy
+    ])
+    .map_err(|e| format!("{}: {}", t.trade_id, e))?;
+    Ok(())
+}
+
+fn update(tx: &Transaction, t: &FlexTrade) -> Result<(), String> {
+    tx.execute(
+        "UPDATE flex_trades SET account_id = ?2, symbol = ?3, asset_class = ?4, side = ?5, quantity = ?6,
+           multiplier = ?7, price = ?8, commission = ?9, realized_pnl = ?10, date = ?11, time = ?12, expiry = ?13,
+           strike = ?14, put_call = ?15, open_close = ?16, exchange = ?17, proceeds = ?18, cost_basis = ?19,
+           notes = ?20, synced_at = CURRENT_TIMESTAMP
+         WHERE trade_id = ?1",
+        params![
+            t.trade_id, t.account_id, t.symbol, t.asset_class, t.side, t.quantity, t.multiplier, t.price,
+            t.commission, t.realized_pnl, t.date, t.time, t.expiry, t.strike, t.put_call, t.open_close,
+            t.exchange, t.proceeds, t.cost_basis, t.notes
+        ],
+    )
+    .map_err(|e| format!("{}: {}", t.trade_id, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::migrations::{migrate, MIGRATIONS};
+
+    fn trade(id: &str, commission: f64) -> FlexTrade {
+        FlexTrade {
+            account_id: "U1234567".into(),
+            trade_id: id.into(),
+            symbol: "SPY".into(),
+            asset_class: "OPT".into(),
+            side: "SELL".into(),
+            quantity: 1,
+            multiplier: 100,
+            price: 2.5,
+            commission,
+            realized_pnl: 120.0,
+            date: "2025-03-14".into(),
+            time: "10:30:00".into(),
+            expiry: "2025-03-21".into(),
+            strike: 550.0,
+            put_call: "P".into(),
+            open_close: "C".into(),
+            exchange: "CBOE".into(),
+            proceeds: 250.0,
+            cost_basis: 130.0,
+            notes: String::new(),
+        }
+    }
+
+    fn setup() -> Connection {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("schema");
+        conn
+    }
+
+    #[test]
+    fn test_reimport_is_idempotent_and_reports_each_outcome() {
+        let mut conn = setup();
+        let mut open = trade("3", -1.0);
+        open.open_close = "O".into();
+        let overrides = HashMap::from([("2".to_string(), "PCS".to_string())]);
+        let first =
+            upsert(&mut conn, &[trade("1", -1.0), trade("2", -1.0), open], &overrides, Actor::Sync).expect("first");
+        assert_eq!((first.inserted, first.skipped_open), (2, 1));
+
+        let again =
+            upsert(&mut conn, &[trade("1", -1.0), trade("2", -1.0)], &HashMap::new(), Actor::Sync).expect("again");
+        assert_eq!((again.inserted, again.updated, again.unchanged), (0, 0, 2));
+
+        let synced: i64 = conn
+            .query_row("SELECT trades_synced FROM sync_metadata WHERE account_id = 'FLEX'", [], |r| r.get(0))
+            .expect("metadata");
+        assert_eq!(synced, 0);
+    }
+
+    #[test]
+    fn test_update_keeps_user_strategy_and_deletion() {
+        let mut conn = setup();
+        upsert(&mut conn, &[trade("1", -1.0)], &HashMap::new(), Actor::Sync).expect("insert");
+        conn.execute("UPDATE flex_trades SET strategy = 'Wheel', is_deleted = 1 WHERE trade_id = '1'", [])
+            .expect("user edit");
+
+        let report = upsert(&mut conn, &[trade("1", -1.35)], &HashMap::new(), Actor::Sync).expect("update");
+        assert_eq!(report.updated, 1);
+        let (commission, strategy, deleted): (f64, String, i64) = conn
+            .query_row("SELECT commission, strategy, is_deleted FROM flex_trades WHERE trade_id = '1'", [], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+            })
+            .expect("row");
+        assert_eq!((commission, strategy.as_str(), deleted), (-1.35, "Wheel", 1));
+    }
+
+    #[test]
+    fn test_synthetic_id_collision_is_a_conflict() {
+        let mut conn = setup();
+        let id = "SYN|SPY|2025-03-14|SELL|1|25000|55000|P|2025-03-21";
+        upsert(&mut conn, &[trade(id, -1.0)], &HashMap::new(), Actor::Sync).expect("insert");
+        let mut other = trade(id, -1.0);
+        other.symbol = "QQQ".into();
+
+        let report = upsert(&mut conn, &[other], &HashMap::new(), Actor::Sync).expect("conflict");
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].fields, vec!["symbol".to_string()]);
+        let symbol: String =
+            conn.query_row("SELECT symbol FROM flex_trades WHERE trade_id = ?1", [id], |r| r.get(0)).expect("row");
+        assert_eq!(symbol, "SPY");
+    }
+
+    #[test]
+    fn test_legacy_null_columns_are_filled_not_conflicting() {
+        let mut conn = setup();
+        conn.execute(
+            "INSERT INTO flex_trades (trade_id, symbol, date, side, strategy) VALUES ('1', 'SPY', '2025-03-14', 'SELL', 'PCS')",
+            [],
+        )
+        .expect("legacy row");
+        let report = upsert(&mut conn, &[trade("1", -1.0)], &HashMap::new(), Actor::Sync).expect("upsert");
+        assert_eq!((report.updated, report.conflicts.len()), (1, 0));
+    }
+}
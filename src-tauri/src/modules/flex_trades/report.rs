@@ -0,0 +1,33 @@
+// Bilan d'un import flex_trades (renvoyé au frontend et émis par la sync planifiée)
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Trades ouverts : suivis dans open_positions, pas dans flex_trades
+    pub skipped_open: usize,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+impl ImportReport {
+    /// Cumul des imports successifs d'une même sync (une requête Flex par période)
+    pub fn merge(&mut self, other: ImportReport) {
+        self.inserted += other.inserted;
+        self.updated += other.updated;
+        self.unchanged += other.unchanged;
+        self.skipped_open += other.skipped_open;
+        self.conflicts.extend(other.conflicts);
+    }
+}
+
+/// trade_id déjà en base pour un autre trade (ex. collision d'un id synthétique SYN|…)
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportConflict {
+    pub trade_id: String,
+    pub symbol: String,
+    pub date: String,
+    pub fields: Vec<String>, // champs d'identité divergents
+}
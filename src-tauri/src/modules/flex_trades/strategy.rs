@@ -0,0 +1,80 @@
+// Stratégie attribuée à un trade importé sans choix de l'utilisateur (fallback)
+// NB : PCS nécessite une analyse de groupe — non détectable sur un seul trade
+
+use crate::modules::tws_socket::FlexTrade;
+
+pub fn detect_strategy(trade: &FlexTrade) -> &'static str {
+    if trade.asset_class == "CASH" {
+        return "Autre";
+    }
+    let occ_right = occ_right(&trade.symbol);
+    if trade.asset_class == "OPT" || occ_right.is_some() {
+        let is_call = trade.put_call == "C" || (trade.put_call != "P" && occ_right == Some('C'));
+        let is_sell = trade.side.eq_ignore_ascii_case("SELL");
+        return match (is_call, is_sell) {
+            (true, true) => "Naked Call",
+            (true, false) => "Long Call",
+            (false, true) => "Naked Put",
+            (false, false) => "Long Put",
+        };
+    }
+    // STK : assignation / exercice → Wheel, sinon Rockets
+    let notes = trade.notes.to_uppercase();
+    let assigned = notes
+        .split(|c: char| c == ';' || c == ',' || c.is_whitespace())
+        .any(|code| matches!(code, "A" | "EX" | "ASGN"));
+    if assigned {
+        "Wheel"
+    } else {
+        "Rockets"
+    }
+}
+
+/// 'C' ou 'P' si le symbole contient un motif OCC `YYMMDD[CP]<strike>`
+fn occ_right(symbol: &str) -> Option<char> {
+    let bytes = symbol.as_bytes();
+    bytes.windows(8).find_map(|w| {
+        let digits = w[..6].iter().all(u8::is_ascii_digit);
+        let right = matches!(w[6], b'C' | b'P');
+        (digits && right && w[7].is_ascii_digit()).then_some(w[6] as char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(asset_class: &str, symbol: &str, side: &str, put_call: &str, notes: &str) -> FlexTrade {
+        FlexTrade {
+            account_id: "U1".into(),
+            trade_id: "1".into(),
+            symbol: symbol.into(),
+            asset_class: asset_class.into(),
+            side: side.into(),
+            quantity: 1,
+            multiplier: 100,
+            price: 1.0,
+            commission: 0.0,
+            realized_pnl: 0.0,
+            date: "2025-01-02".into(),
+            time: String::new(),
+            expiry: String::new(),
+            strike: 0.0,
+            put_call: put_call.into(),
+            open_close: "C".into(),
+            exchange: String::new(),
+            proceeds: 0.0,
+            cost_basis: 0.0,
+            notes: notes.into(),
+        }
+    }
+
+    #[test]
+    fn test_detect_strategy_matches_frontend_rules() {
+        assert_eq!(detect_strategy(&trade("OPT", "SPY", "SELL", "P", "")), "Naked Put");
+        assert_eq!(detect_strategy(&trade("", "AAPL  250117C00200000", "BUY", "", "")), "Long Call");
+        assert_eq!(detect_strategy(&trade("STK", "KO", "BUY", "", "A;O")), "Wheel");
+        assert_eq!(detect_strategy(&trade("STK", "KO", "BUY", "", "O")), "Rockets");
+        assert_eq!(detect_strategy(&trade("CASH", "EUR.USD", "BUY", "", "")), "Autre");
+    }
+}
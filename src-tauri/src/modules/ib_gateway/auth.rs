@@ -1,33 +1,40 @@
 use base64::{engine::general_purpose, Engine as _};
+use crate::modules::secrets::{self, SecretKind};
+use tauri::{AppHandle, Runtime};
 
-/// Récupère les credentials IB Gateway depuis le système
-/// 
-/// Pour tester: 
-/// - username: "demo_user" (ou depuis env si développement)
-/// - password: "demo_pass"
-/// 
-/// Production: Tauri Secure Storage (Keychain/Credential Manager)
+/// Credentials IB Gateway, lus dans le magasin de secrets (trousseau système ou coffre chiffré)
+///
+/// Développement : IB_USERNAME / IB_PASSWORD si aucun secret n'est enregistré
 pub struct IBCredentials {
     pub username: String,
     pub password: String,
 }
 
 impl IBCredentials {
-    /// Charge les credentials (pour Phase 1: hardcodé demo, Phase 2: Tauri Keychain)
-    pub fn load() -> Result<Self, String> {
-        // TODO Phase 2: Intégrer tauri-plugin-keychain pour production
-        // Pour maintenant, on simule avec env vars pour les tests
-        
-        let username = std::env::var("IB_USERNAME")
-            .unwrap_or_else(|_| "demo".to_string());
-        let password = std::env::var("IB_PASSWORD")
-            .unwrap_or_else(|_| "demo".to_string());
-
-        if username.is_empty() || password.is_empty() {
-            return Err("IB credentials empty. Set IB_USERNAME and IB_PASSWORD env vars".to_string());
-        }
+    /// Charge les credentials du profil "default" ; erreur s'ils sont absents (plus de repli demo)
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Self, String> {
+        let username = secrets::get(app_handle, secrets::DEFAULT_PROFILE, SecretKind::IbUsername)?;
+        let password = secrets::get(app_handle, secrets::DEFAULT_PROFILE, SecretKind::IbPassword)?;
+        Self::resolve(
+            (username, password),
+            (std::env::var("IB_USERNAME").ok(), std::env::var("IB_PASSWORD").ok()),
+        )
+    }
 
-        Ok(IBCredentials { username, password })
+    /// Paire enregistrée complète, sinon paire d'environnement complète
+    fn resolve(
+        stored: (Option<String>, Option<String>),
+        env: (Option<String>, Option<String>),
+    ) -> Result<Self, String> {
+        let complete = |(username, password): (Option<String>, Option<String>)| match (username, password) {
+            (Some(username), Some(password)) if !username.is_empty() && !password.is_empty() => {
+                Some(IBCredentials { username, password })
+            }
+            _ => None,
+        };
+        complete(stored).or_else(|| complete(env)).ok_or_else(|| {
+            "IB credentials not configured: save ib_username and ib_password for the default profile".to_string()
+        })
     }
 
     /// Encode en Basic Auth (base64 de "username:password")
@@ -55,10 +62,15 @@ mod tests {
     }
 
     #[test]
-    fn test_auth_load_handles_missing_vars() {
-        // Simule: pas de env vars, donc defaults
-        // (test peut échouer si IB_USERNAME/PASSWORD sont définis, c'est ok)
-        let result = IBCredentials::load();
-        assert!(result.is_ok());
+    fn test_credentials_require_a_complete_pair() {
+        let some = |s: &str| Some(s.to_string());
+        assert!(IBCredentials::resolve((None, None), (None, None)).is_err());
+        assert!(IBCredentials::resolve((some("user"), None), (None, some("pass"))).is_err());
+
+        let stored = IBCredentials::resolve((some("user"), some("pass")), (some("env"), some("env")))
+            .expect("stored pair");
+        assert_eq!(stored.username, "user");
+        let env = IBCredentials::resolve((some("user"), some("")), (some("env"), some("secret"))).expect("env pair");
+        assert_eq!(env.username, "env");
     }
 }
@@ -17,9 +17,8 @@ pub struct IBGatewayClient {
 }
 
 impl IBGatewayClient {
-    /// Crée un nouveau client IB Gateway
-    pub fn new() -> Result<Self, String> {
-        let credentials = IBCredentials::load()?;
+    /// Crée un nouveau client IB Gateway (credentials chargés par l'appelant)
+    pub fn new(credentials: IBCredentials) -> Result<Self, String> {
         let client = Client::builder()
             .timeout(Duration::from_secs(REQUEST_TIMEOUT))
             .build()
@@ -0,0 +1,11 @@
+// Commande Tauri de l'ancien client REST IB Gateway
+
+use super::{IBCredentials, IBTrade};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Récupère les trades depuis IB Gateway (ancien - REST)
+#[tauri::command]
+pub async fn fetch_ib_trades(app_handle: tauri::AppHandle, account_id: String) -> Result<Vec<IBTrade>, AppError> {
+    let credentials = IBCredentials::load(&app_handle).map_err(AppError::Auth)?;
+    super::fetch_ib_trades(account_id, credentials).await.map_err(AppError::TwsConnection)
+}
@@ -1,5 +1,6 @@
 mod auth;
 mod client;
+pub mod commands;
 pub mod parser;
 
 pub use auth::IBCredentials;
@@ -9,21 +10,21 @@ pub use parser::{IBTrade, parse_ib_trades, deduplicate_trades};
 /// Fonction publique principale: Récupère les trades depuis IB Gateway
 /// 
 /// Flux:
-/// 1. Crée client (auth + HTTP)
+/// 1. Crée client (credentials du magasin de secrets + HTTP)
 /// 2. Fetch JSON brut depuis IB
 /// 3. Parse + déduplique
 /// 4. Retourne Vec<IBTrade>
 /// 
 /// Usage depuis Vue:
 ///   const trades = await invoke('fetch_ib_trades', { account_id: 'U123456' })
-pub async fn fetch_ib_trades(account_id: String) -> Result<Vec<IBTrade>, String> {
+pub async fn fetch_ib_trades(account_id: String, credentials: IBCredentials) -> Result<Vec<IBTrade>, String> {
     // Valide le format account_id
     if account_id.is_empty() {
         return Err("account_id is empty".to_string());
     }
 
-    // Crée le client (charge credentials)
-    let client = IBGatewayClient::new()?;
+    // Crée le client HTTP
+    let client = IBGatewayClient::new(credentials)?;
 
     // Fetch, parse, déduplique
     client.fetch_and_parse_trades(&account_id).await
@@ -35,7 +36,8 @@ mod tests {
 
     #[test]
     fn test_fetch_ib_trades_validates_account_id() {
-        let result = futures::executor::block_on(fetch_ib_trades("".to_string()));
+        let credentials = IBCredentials { username: "user".to_string(), password: "pass".to_string() };
+        let result = futures::executor::block_on(fetch_ib_trades("".to_string(), credentials));
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,16 @@
+// Commande Tauri du panneau diagnostics
+
+use super::{LogEntry, DEFAULT_LIMIT};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Dernières lignes de log (panneau diagnostics), niveau minimal et module optionnels
+#[tauri::command]
+pub fn get_recent_logs(
+    app_handle: tauri::AppHandle,
+    level: Option<String>,
+    module: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, AppError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    super::recent_logs(&app_handle, level.as_deref(), module.as_deref(), limit).map_err(AppError::Validation)
+}
@@ -0,0 +1,89 @@
+// Logs structurés (tracing) : niveaux, une cible par domaine (flex, tws, backup, market_data...),
+// span par exécution de sync avec identifiant de corrélation
+// Sortie : stderr lisible + fichiers JSON à rotation quotidienne dans app_log_dir,
+// tout passe par le masquage de redact.rs avant écriture
+
+pub mod commands;
+mod reader;
+mod writer;
+
+pub use reader::{read_recent, recent_logs, LogEntry, DEFAULT_LIMIT};
+
+use crate::modules::redact;
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+use writer::Redacting;
+
+// Cibles (filtre du panneau diagnostics, directives TRADEVISION_LOG="info,flex=debug")
+pub const APP: &str = "app";
+pub const FLEX: &str = "flex";
+pub const TWS: &str = "tws";
+pub const SYNC: &str = "sync";
+pub const BACKUP: &str = "backup";
+pub const MARKET_DATA: &str = "market_data";
+pub const ALERTS: &str = "alerts";
+pub const SECRETS: &str = "secrets";
+
+/// Directives tracing (syntaxe EnvFilter) ; défaut "info", "debug" en mode debug
+pub const FILTER_ENV: &str = "TRADEVISION_LOG";
+/// Fichiers "tradevision.AAAA-MM-JJ.json"
+pub const LOG_FILE_PREFIX: &str = "tradevision";
+pub const LOG_FILE_SUFFIX: &str = "json";
+/// Jours de logs conservés
+const MAX_LOG_FILES: usize = 14;
+
+/// Vide le tampon du writer non bloquant à la fermeture de l'application
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// Installe le subscriber global (appelé en premier dans setup)
+pub fn init<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = log_dir(app_handle)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| format!("Log file error: {}", e))?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+
+    let file_layer = fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_writer(Redacting(file_writer))
+        .with_filter(filter());
+    let stderr_layer = fmt::layer().compact().with_writer(Redacting(std::io::stderr)).with_filter(filter());
+    tracing_subscriber::registry()
+        .with(file_layer)
+        .with(stderr_layer)
+        .try_init()
+        .map_err(|e| format!("Logger already initialized: {}", e))?;
+    let _ = GUARD.set(guard);
+    tracing::info!(target: APP, dir = %dir.display(), "Logging initialized");
+    Ok(dir)
+}
+
+pub fn log_dir<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf, String> {
+    app_handle.path().app_log_dir().map_err(|e| e.to_string())
+}
+
+/// Identifiant court et triable d'une exécution (sync, reprise d'historique)
+pub fn correlation_id() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed) & 0xffff;
+    format!("{}-{:04x}", Utc::now().format("%Y%m%dT%H%M%S"), n)
+}
+
+fn filter() -> EnvFilter {
+    let default = if redact::debug_enabled() { "debug" } else { "info" };
+    EnvFilter::try_from_env(FILTER_ENV).unwrap_or_else(|_| EnvFilter::new(default))
+}
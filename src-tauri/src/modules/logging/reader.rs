@@ -0,0 +1,144 @@
+// Lecture des fichiers JSON de logs pour le panneau diagnostics : plus récents d'abord,
+// filtrés par niveau minimal et par cible
+
+use super::{log_dir, LOG_FILE_PREFIX, LOG_FILE_SUFFIX};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Runtime};
+
+pub const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 2000;
+const LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Identifiant de l'exécution de sync englobante, le cas échéant
+    pub correlation_id: Option<String>,
+    /// Champs structurés hors message
+    pub fields: Map<String, Value>,
+}
+
+pub fn recent_logs<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    level: Option<&str>,
+    module: Option<&str>,
+    limit: usize,
+) -> Result<Vec<LogEntry>, String> {
+    read_recent(&log_dir(app_handle)?, level, module, limit)
+}
+
+/// `level` : niveau minimal ("warn" garde warn + error) ; `module` : cible ou préfixe "flex::"
+pub fn read_recent(
+    dir: &Path,
+    level: Option<&str>,
+    module: Option<&str>,
+    limit: usize,
+) -> Result<Vec<LogEntry>, String> {
+    let max_rank = match level {
+        Some(level) => rank(level).ok_or_else(|| format!("Unknown log level '{}'", level))?,
+        None => LEVELS.len() - 1,
+    };
+    let limit = limit.clamp(1, MAX_LIMIT);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Log dir error: {}", e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                n.starts_with(&format!("{}.", LOG_FILE_PREFIX)) && n.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+            })
+        })
+        .collect();
+    // Date dans le nom : ordre lexicographique = ordre chronologique
+    files.sort();
+
+    let mut entries = Vec::new();
+    for file in files.iter().rev() {
+        let text = fs::read_to_string(file).map_err(|e| format!("Log read error: {}", e))?;
+        for line in text.lines().rev() {
+            let Some(entry) = parse_line(line) else { continue };
+            let in_module = module.is_none_or(|m| entry.target == m || entry.target.starts_with(&format!("{}::", m)));
+            if in_module && rank(&entry.level).is_some_and(|r| r <= max_rank) {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    return Ok(entries);
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn rank(level: &str) -> Option<usize> {
+    LEVELS.iter().position(|l| l.eq_ignore_ascii_case(level.trim()))
+}
+
+/// Ligne JSON de tracing-subscriber ; les lignes tronquées (écriture en cours) sont ignorées
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let mut fields = value.get("fields")?.as_object()?.clone();
+    let message = match fields.remove("message") {
+        Some(Value::String(message)) => message,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    let text = |key: &str| value.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+    let correlation_id = value
+        .get("spans")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .chain(value.get("span"))
+        .find_map(|span| span.get("correlation_id").and_then(Value::as_str))
+        .map(str::to_string);
+    Some(LogEntry {
+        timestamp: text("timestamp"),
+        level: text("level").to_lowercase(),
+        target: text("target"),
+        message,
+        correlation_id,
+        fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_logs_are_filtered_and_newest_first() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let line = |ts: &str, level: &str, target: &str, msg: &str| {
+            format!(
+                r#"{{"timestamp":"{}","level":"{}","fields":{{"message":"{}","count":3}},"target":"{}","spans":[{{"correlation_id":"run-1","name":"sync_run"}}]}}"#,
+                ts, level, msg, target
+            )
+        };
+        let day1 = [line("1", "INFO", "flex", "old"), line("2", "ERROR", "tws", "tws down")].join("\n");
+        let day2 = [line("3", "WARN", "flex", "retry"), line("4", "DEBUG", "flex", "noise"), "{\"truncated".into()]
+            .join("\n");
+        fs::write(dir.path().join("tradevision.2025-06-13.json"), day1).expect("write");
+        fs::write(dir.path().join("tradevision.2025-06-14.json"), day2).expect("write");
+        fs::write(dir.path().join("other.txt"), "ignored").expect("write");
+
+        let flex = read_recent(dir.path(), Some("info"), Some("flex"), 10).expect("read");
+        let messages: Vec<&str> = flex.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["retry", "old"]);
+        assert_eq!(flex[0].correlation_id.as_deref(), Some("run-1"));
+        assert_eq!(flex[0].fields.get("count"), Some(&Value::from(3)));
+
+        let errors = read_recent(dir.path(), Some("error"), None, 10).expect("read");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(read_recent(dir.path(), None, None, 2).expect("read").len(), 2);
+        assert!(read_recent(dir.path(), Some("loud"), None, 10).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+// Writer tracing qui masque chaque ligne (tokens, ReferenceCode, comptes IB) avant écriture
+
+use crate::modules::redact::redact;
+use std::io::{self, Write};
+use tracing_subscriber::fmt::MakeWriter;
+
+pub(super) struct Redacting<M>(pub M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+/// Un appel `write` par événement formaté : la ligne entière est masquée d'un coup
+pub(super) struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_all(redact(&String::from_utf8_lossy(buf)).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_are_redacted_before_reaching_the_sink() {
+        let mut sink = Vec::new();
+        let mut writer = RedactingWriter(&mut sink);
+        writer.write_all(b"{\"fields\":{\"message\":\"Import U1234567 done\"}}\n").expect("write");
+        assert_eq!(String::from_utf8(sink).expect("utf8"), "{\"fields\":{\"message\":\"Import U12***67 done\"}}\n");
+    }
+}
@@ -0,0 +1,69 @@
+// Barres OHLC historiques (journalières ou horaires) : même ordre de fournisseurs que les
+// cotations, le premier qui renvoie des barres l'emporte. TWS n'en fournit pas (historique non
+// implémenté dans la crate ibapi) et laisse la main aux suivants
+
+use super::{ProviderKind, QuoteProvider};
+use crate::modules::error::AppError;
+use crate::modules::logging::MARKET_DATA;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bar {
+    /// Début de la barre
+    pub time: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarInterval {
+    #[default]
+    Day,
+    Hour,
+}
+
+impl BarInterval {
+    /// Clé du cache SQLite et notation Binance
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "1d",
+            Self::Hour => "1h",
+        }
+    }
+}
+
+/// Barres de [from, to[ du premier fournisseur qui cote le symbole et en renvoie ;
+/// None si aucun n'a d'historique, erreur seulement si tous ceux essayés ont échoué
+pub async fn fetch_bars_with_fallback(
+    providers: &[Box<dyn QuoteProvider>],
+    symbol: &str,
+    interval: BarInterval,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Option<(ProviderKind, Vec<Bar>)>, AppError> {
+    let mut first_error = None;
+    for provider in providers.iter().filter(|p| p.supports(symbol)) {
+        match provider.bars(symbol, interval, from, to).await {
+            Ok(bars) if !bars.is_empty() => {
+                tracing::debug!(target: MARKET_DATA, provider = ?provider.kind(), symbol = %symbol,
+                    interval = interval.as_str(), bars = bars.len(), "Bars fetched");
+                return Ok(Some((provider.kind(), bars)));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(target: MARKET_DATA, provider = ?provider.kind(), symbol = %symbol, error = %e,
+                    "Bar history failed");
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,239 @@
+// Binance REST public (sans clé) : paires crypto cotées en stablecoin, "BTCUSDT" ou "BTC/USDT"
+
+use super::{Bar, BarInterval, MarketQuote, MarketState, PriceField, ProviderKind, QuoteProvider, RateLimit};
+use crate::modules::error::AppError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const TICKER_URL: &str = "https://api.binance.com/api/v3/ticker/24hr";
+const KLINES_URL: &str = "https://api.binance.com/api/v3/klines";
+const EXCHANGE: &str = "BINANCE";
+const QUOTE_ASSETS: [&str; 4] = ["USDT", "USDC", "BUSD", "FDUSD"];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Le poids d'une requête ticker/24hr plafonne à partir de 100 paires
+const MAX_BATCH: usize = 100;
+/// Bougies par requête (maximum de l'API) ; MAX_KLINE_PAGES borne une période très longue
+const KLINES_LIMIT: usize = 1000;
+const MAX_KLINE_PAGES: usize = 20;
+
+pub struct BinanceProvider {
+    client: Client,
+}
+
+impl Default for BinanceProvider {
+    fn default() -> Self {
+        Self { client: Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap_or_default() }
+    }
+}
+
+impl BinanceProvider {
+    /// Statistiques 24 h des paires ; 400 si une paire n'existe pas (toute la requête est refusée)
+    async fn tickers(&self, pairs: &[String]) -> Result<Vec<(String, MarketQuote)>, AppError> {
+        let list = serde_json::to_string(pairs).map_err(|e| AppError::Internal(e.to_string()))?;
+        let resp = self
+            .client
+            .get(TICKER_URL)
+            .query(&[("symbols", list.as_str())])
+            .send()
+            .await
+            .map_err(|e| AppError::Network(format!("Binance request failed: {}", e)))?;
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| AppError::Network(e.to_string()))?;
+        match status {
+            s if s.is_success() => parse_tickers(&text),
+            StatusCode::BAD_REQUEST => Err(AppError::Validation(format!("Binance rejected symbols: {}", text))),
+            s => Err(AppError::Network(format!("Binance API Error: {}", s))),
+        }
+    }
+
+    /// Bougies de [from, to[, page par page ; paire inconnue (400) → aucune bougie
+    async fn klines(
+        &self,
+        pair: &str,
+        interval: BarInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, AppError> {
+        let mut bars = Vec::new();
+        let mut start = from.timestamp_millis();
+        let end = to.timestamp_millis() - 1;
+        for _ in 0..MAX_KLINE_PAGES {
+            let resp = self
+                .client
+                .get(KLINES_URL)
+                .query(&[
+                    ("symbol", pair.to_string()),
+                    ("interval", interval.as_str().to_string()),
+                    ("startTime", start.to_string()),
+                    ("endTime", end.to_string()),
+                    ("limit", KLINES_LIMIT.to_string()),
+                ])
+                .send()
+                .await
+                .map_err(|e| AppError::Network(format!("Binance request failed: {}", e)))?;
+            let status = resp.status();
+            let text = resp.text().await.map_err(|e| AppError::Network(e.to_string()))?;
+            let page = match status {
+                s if s.is_success() => parse_klines(&text)?,
+                StatusCode::BAD_REQUEST => return Ok(bars),
+                s => return Err(AppError::Network(format!("Binance API Error: {}", s))),
+            };
+            let Some(last) = page.last() else { break };
+            start = last.time.timestamp_millis() + 1;
+            let full = page.len() == KLINES_LIMIT;
+            bars.extend(page);
+            if !full || start > end {
+                break;
+            }
+        }
+        Ok(bars)
+    }
+}
+
+#[async_trait]
+impl QuoteProvider for BinanceProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Binance
+    }
+
+    fn supports(&self, symbol: &str) -> bool {
+        let pair = pair(symbol);
+        QUOTE_ASSETS.iter().any(|q| pair.len() > q.len() && pair.ends_with(q))
+    }
+
+    fn max_batch(&self) -> usize {
+        MAX_BATCH
+    }
+
+    /// Largement sous la limite de poids par minute de l'API publique
+    fn rate_limit(&self) -> RateLimit {
+        RateLimit { burst: 10, per_second: 2.0 }
+    }
+
+    async fn quotes(&self, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError> {
+        let mut pairs: Vec<String> = symbols.iter().map(|s| pair(s)).collect();
+        pairs.sort();
+        pairs.dedup();
+
+        let tickers = match self.tickers(&pairs).await {
+            // Une paire inconnue fait échouer le lot : on redemande paire par paire
+            Err(AppError::Validation(_)) if pairs.len() > 1 => {
+                let mut tickers = Vec::new();
+                for pair in &pairs {
+                    if let Ok(found) = self.tickers(std::slice::from_ref(pair)).await {
+                        tickers.extend(found);
+                    }
+                }
+                tickers
+            }
+            other => other?,
+        };
+
+        let by_pair: HashMap<String, MarketQuote> = tickers.into_iter().collect();
+        Ok(symbols.iter().filter_map(|s| by_pair.get(&pair(s)).map(|q| (s.clone(), q.clone()))).collect())
+    }
+
+    async fn bars(
+        &self,
+        symbol: &str,
+        interval: BarInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, AppError> {
+        self.klines(&pair(symbol), interval, from, to).await
+    }
+}
+
+fn pair(symbol: &str) -> String {
+    symbol.replace('/', "").trim().to_uppercase()
+}
+
+/// Tableau (paramètre symbols) ou objet seul ; les prix sont des chaînes. Marché continu :
+/// toujours REGULAR, devise = actif de cotation de la paire
+fn parse_tickers(text: &str) -> Result<Vec<(String, MarketQuote)>, AppError> {
+    let value: Value = serde_json::from_str(text).map_err(|e| AppError::json("json", &e))?;
+    let items = match value {
+        Value::Array(items) => items,
+        item => vec![item],
+    };
+    let number = |item: &Value, key: &str| item[key].as_str().and_then(|v| v.parse::<f64>().ok());
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let symbol = item["symbol"].as_str()?;
+            let price = number(item, "lastPrice")?;
+            let change_percent = number(item, "priceChangePercent").unwrap_or(0.0);
+            let quote = MarketQuote {
+                price_field: PriceField::Last,
+                bid: number(item, "bidPrice"),
+                ask: number(item, "askPrice"),
+                last: Some(price),
+                previous_close: number(item, "prevClosePrice"),
+                day_high: number(item, "highPrice"),
+                day_low: number(item, "lowPrice"),
+                volume: number(item, "volume"),
+                currency: QUOTE_ASSETS.iter().find(|q| symbol.ends_with(*q)).map(|q| q.to_string()),
+                exchange: Some(EXCHANGE.to_string()),
+                market_state: Some(MarketState::Regular),
+                quote_time: item["closeTime"].as_i64().and_then(DateTime::from_timestamp_millis),
+                ..MarketQuote::new(price, change_percent)
+            };
+            Some((symbol.to_string(), quote))
+        })
+        .collect())
+}
+
+/// [openTime, "open", "high", "low", "close", "volume", closeTime, ...]
+fn parse_klines(text: &str) -> Result<Vec<Bar>, AppError> {
+    let value: Value = serde_json::from_str(text).map_err(|e| AppError::json("json", &e))?;
+    let rows = value.as_array().map(Vec::as_slice).unwrap_or_default();
+    let number = |row: &Value, i: usize| row[i].as_str().and_then(|v| v.parse::<f64>().ok());
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            Some(Bar {
+                time: DateTime::from_timestamp_millis(row[0].as_i64()?)?,
+                open: number(row, 1)?,
+                high: number(row, 2)?,
+                low: number(row, 3)?,
+                close: number(row, 4)?,
+                volume: number(row, 5),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tickers_klines_and_supported_pairs() {
+        let text = r#"[{"symbol":"BTCUSDT","lastPrice":"65012.50","priceChangePercent":"-1.25",
+                        "bidPrice":"65012.49","askPrice":"65012.51","closeTime":1749686400000},
+                       {"symbol":"ETHUSDC","lastPrice":"bad"}]"#;
+        let tickers = parse_tickers(text).expect("tickers");
+        assert_eq!(tickers.len(), 1);
+        assert_eq!(tickers[0].0, "BTCUSDT");
+        assert_eq!(tickers[0].1.price, 65012.5);
+        assert_eq!(tickers[0].1.change_percent, -1.25);
+        assert_eq!(tickers[0].1.bid, Some(65012.49));
+        assert_eq!(tickers[0].1.currency.as_deref(), Some("USDT"));
+        assert_eq!(tickers[0].1.quote_time.map(|t| t.timestamp()), Some(1749686400));
+
+        let klines = parse_klines(
+            r#"[[1749686400000,"65000.0","66500.5","64100.0","66000.0","1234.5",1749772799999,"0",10,"0","0","0"]]"#,
+        )
+        .expect("klines");
+        assert_eq!(klines.len(), 1);
+        assert_eq!((klines[0].high, klines[0].low, klines[0].volume), (66500.5, 64100.0, Some(1234.5)));
+
+        let provider = BinanceProvider::default();
+        assert!(provider.supports("btc/usdt") && provider.supports("SOLUSDC"));
+        assert!(!provider.supports("AAPL") && !provider.supports("EURUSD=X") && !provider.supports("USDT"));
+    }
+}
@@ -0,0 +1,27 @@
+// Commandes Tauri des cotations et de l'ordre des fournisseurs
+
+use super::{MarketDataSettings, MarketQuote, QuoteService};
+use crate::modules::error::AppError;
+use std::collections::HashMap;
+
+/// Fetches market quotes from the configured providers (Binance, Yahoo, TWS), in fallback order.
+/// Served from the shared quote cache when fresh; stale quotes are flagged instead of failing.
+#[tauri::command]
+pub async fn fetch_market_quotes(
+    app_handle: tauri::AppHandle,
+    quotes: tauri::State<'_, QuoteService>,
+    symbols: Vec<String>,
+) -> Result<HashMap<String, MarketQuote>, AppError> {
+    quotes.quotes(&app_handle, &symbols).await
+}
+
+/// Commande Tauri: Ordre des fournisseurs de cotations (section "market_data" de settings.json)
+#[tauri::command]
+pub fn get_market_data_settings(app_handle: tauri::AppHandle) -> Result<MarketDataSettings, AppError> {
+    super::load_settings(&app_handle).map_err(AppError::Internal)
+}
+
+#[tauri::command]
+pub fn set_market_data_settings(app_handle: tauri::AppHandle, settings: MarketDataSettings) -> Result<(), AppError> {
+    super::save_settings(&app_handle, &settings).map_err(AppError::Validation)
+}
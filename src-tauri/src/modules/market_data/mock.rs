@@ -0,0 +1,90 @@
+// Fournisseur local pour les tests : cotations fixes, symboles acceptés, panne et latence simulées
+
+use super::{MarketQuote, ProviderKind, QuoteProvider};
+use crate::modules::error::AppError;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct MockProvider {
+    kind: ProviderKind,
+    quotes: HashMap<String, MarketQuote>,
+    /// None : tous les symboles sont acceptés
+    supported: Option<Vec<String>>,
+    error: Option<AppError>,
+    batch: usize,
+    delay: Option<Duration>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl MockProvider {
+    pub fn new(kind: ProviderKind) -> Self {
+        Self {
+            kind,
+            quotes: HashMap::new(),
+            supported: None,
+            error: None,
+            batch: 100,
+            delay: None,
+            calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn with_quote(mut self, symbol: &str, price: f64) -> Self {
+        self.quotes.insert(symbol.to_string(), MarketQuote::new(price, 0.0));
+        self
+    }
+
+    pub fn only(mut self, symbols: &[&str]) -> Self {
+        self.supported = Some(symbols.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    pub fn failing(mut self, error: AppError) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    pub fn batch_of(mut self, batch: usize) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Nombre d'appels à quotes(), partagé avec le fournisseur une fois emballé
+    pub fn calls(&self) -> Arc<AtomicUsize> {
+        self.calls.clone()
+    }
+}
+
+#[async_trait]
+impl QuoteProvider for MockProvider {
+    fn kind(&self) -> ProviderKind {
+        self.kind
+    }
+
+    fn supports(&self, symbol: &str) -> bool {
+        self.supported.as_ref().is_none_or(|s| s.iter().any(|x| x == symbol))
+    }
+
+    fn max_batch(&self) -> usize {
+        self.batch
+    }
+
+    async fn quotes(&self, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+        Ok(symbols.iter().filter_map(|s| self.quotes.get(s).map(|q| (s.clone(), q.clone()))).collect())
+    }
+}
@@ -0,0 +1,236 @@
+// Cotations via des fournisseurs interchangeables (Yahoo, TWS, Binance) essayés dans l'ordre
+// configuré (section "market_data" de settings.json) : un symbole introuvable ou un fournisseur
+// en panne passe au suivant, seule une panne de tous renvoie une erreur. Les commandes passent par
+// le QuoteService (état Tauri) qui ajoute cache, regroupement des demandes et limite de débit
+
+mod bars;
+mod binance;
+pub mod commands;
+#[cfg(test)]
+mod mock;
+mod quote;
+mod service;
+mod throttle;
+mod tws;
+mod yahoo;
+
+pub use bars::{fetch_bars_with_fallback, Bar, BarInterval};
+pub use binance::BinanceProvider;
+pub use quote::{MarketQuote, MarketState, PriceField};
+pub use service::{fetch_quotes, CachePolicy, Providers, QuoteService};
+pub use throttle::{RateLimit, Throttled, TokenBucket};
+pub use tws::TwsProvider;
+pub use yahoo::YahooProvider;
+
+use crate::modules::error::AppError;
+use crate::modules::logging::MARKET_DATA;
+use crate::modules::settings;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Runtime};
+
+/// Section "market_data" de settings.json
+pub const SETTINGS_KEY: &str = "market_data";
+
+#[async_trait]
+pub trait QuoteProvider: Send + Sync {
+    fn kind(&self) -> ProviderKind;
+
+    /// Faux pour les symboles que le fournisseur ne cote pas (crypto chez Yahoo, forex chez Binance...)
+    fn supports(&self, symbol: &str) -> bool;
+
+    /// Nombre maximal de symboles par requête
+    fn max_batch(&self) -> usize {
+        20
+    }
+
+    /// Débit toléré par le fournisseur (une requête = un lot)
+    fn rate_limit(&self) -> RateLimit {
+        RateLimit::default()
+    }
+
+    /// Cotations trouvées, par symbole demandé ; un symbole inconnu est simplement absent
+    async fn quotes(&self, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError>;
+
+    /// Barres OHLC de [from, to[ ; vide si le fournisseur n'a pas d'historique pour ce symbole
+    async fn bars(
+        &self,
+        _symbol: &str,
+        _interval: BarInterval,
+        _from: DateTime<Utc>,
+        _to: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, AppError> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    Yahoo,
+    /// Bid/ask tick-by-tick de TWS / IB Gateway (abonnement aux données de marché requis)
+    Tws,
+    Binance,
+}
+
+impl ProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yahoo => "yahoo",
+            Self::Tws => "tws",
+            Self::Binance => "binance",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketDataSettings {
+    /// Ordre d'essai ; TWS n'est pas inclus par défaut (doit être lancé et connecté)
+    #[serde(default = "default_providers")]
+    pub providers: Vec<ProviderKind>,
+    /// Durée pendant laquelle une cotation est resservie sans interroger les fournisseurs
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// Âge maximal d'un dernier cours connu servi (marqué stale) quand les fournisseurs échouent
+    #[serde(default = "default_max_stale_hours")]
+    pub max_stale_hours: u64,
+}
+
+fn default_providers() -> Vec<ProviderKind> {
+    vec![ProviderKind::Binance, ProviderKind::Yahoo]
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    15
+}
+
+/// Couvre un week-end : le cours de clôture du vendredi reste affiché le lundi matin
+fn default_max_stale_hours() -> u64 {
+    72
+}
+
+impl Default for MarketDataSettings {
+    fn default() -> Self {
+        Self {
+            providers: default_providers(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+            max_stale_hours: default_max_stale_hours(),
+        }
+    }
+}
+
+impl MarketDataSettings {
+    /// Bornée à un jour de TTL et 30 jours de dernier cours connu
+    pub fn cache_policy(&self) -> CachePolicy {
+        let ttl = TimeDelta::seconds(self.cache_ttl_secs.min(86_400) as i64);
+        let max_stale = TimeDelta::hours(self.max_stale_hours.min(30 * 24) as i64);
+        CachePolicy { ttl, max_stale: max_stale.max(ttl) }
+    }
+}
+
+pub fn load_settings<R: Runtime>(app_handle: &AppHandle<R>) -> Result<MarketDataSettings, String> {
+    settings::load_section(app_handle, SETTINGS_KEY)
+}
+
+pub fn save_settings<R: Runtime>(app_handle: &AppHandle<R>, market_data: &MarketDataSettings) -> Result<(), String> {
+    if market_data.providers.is_empty() {
+        return Err("At least one market data provider is required".to_string());
+    }
+    settings::save_section(app_handle, SETTINGS_KEY, market_data)
+}
+
+/// Fournisseurs dans l'ordre demandé, chacun découpé en lots et limité en débit
+pub fn build_providers(kinds: &[ProviderKind]) -> Vec<Box<dyn QuoteProvider>> {
+    let mut providers: Vec<Box<dyn QuoteProvider>> = Vec::new();
+    for kind in kinds {
+        if providers.iter().any(|p| p.kind() == *kind) {
+            continue;
+        }
+        let provider: Box<dyn QuoteProvider> = match kind {
+            ProviderKind::Yahoo => Box::new(YahooProvider::default()),
+            ProviderKind::Tws => Box::new(TwsProvider::default()),
+            ProviderKind::Binance => Box::new(BinanceProvider::default()),
+        };
+        providers.push(Box::new(Throttled::new(provider)));
+    }
+    providers
+}
+
+/// Chaque symbole est demandé au premier fournisseur qui le cote, puis aux suivants s'il manque
+pub async fn fetch_with_fallback(
+    providers: &[Box<dyn QuoteProvider>],
+    symbols: &[String],
+) -> Result<HashMap<String, MarketQuote>, AppError> {
+    let mut missing: Vec<String> = Vec::new();
+    for symbol in symbols {
+        if !symbol.trim().is_empty() && !missing.contains(symbol) {
+            missing.push(symbol.clone());
+        }
+    }
+    let mut found = HashMap::new();
+    let mut first_error = None;
+
+    for provider in providers {
+        let wanted: Vec<String> = missing.iter().filter(|s| provider.supports(s)).cloned().collect();
+        if wanted.is_empty() {
+            continue;
+        }
+        match provider.quotes(&wanted).await {
+            Ok(quotes) => {
+                let (asked, got) = (wanted.len(), quotes.len());
+                tracing::debug!(target: MARKET_DATA, provider = ?provider.kind(), asked, found = got, "Quotes fetched");
+                missing.retain(|s| !quotes.contains_key(s));
+                found.extend(quotes);
+            }
+            Err(e) => {
+                tracing::warn!(target: MARKET_DATA, provider = ?provider.kind(), error = %e, "Provider failed");
+                first_error.get_or_insert(e);
+            }
+        }
+        if missing.is_empty() {
+            break;
+        }
+    }
+
+    match first_error {
+        Some(e) if found.is_empty() => Err(e),
+        _ => Ok(found),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockProvider;
+    use super::*;
+
+    fn fetch(providers: &[Box<dyn QuoteProvider>], symbols: &[&str]) -> Result<HashMap<String, MarketQuote>, AppError> {
+        let symbols: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+        tauri::async_runtime::block_on(fetch_with_fallback(providers, &symbols))
+    }
+
+    #[test]
+    fn test_missing_symbols_fall_through_to_the_next_provider() {
+        let binance =
+            MockProvider::new(ProviderKind::Binance).with_quote("BTCUSDT", 65000.0).only(&["BTCUSDT", "ETHUSDT"]);
+        let yahoo = MockProvider::new(ProviderKind::Yahoo).with_quote("AAPL", 190.0).with_quote("BTCUSDT", 1.0);
+        let providers: Vec<Box<dyn QuoteProvider>> = vec![Box::new(binance), Box::new(yahoo)];
+        let quotes = fetch(&providers, &["BTCUSDT", "AAPL", "ETHUSDT"]).expect("quotes");
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes["BTCUSDT"].price, 65000.0);
+        assert_eq!(quotes["AAPL"].price, 190.0);
+    }
+
+    #[test]
+    fn test_failed_provider_is_skipped_and_error_kept_when_nothing_found() {
+        let failing = || MockProvider::new(ProviderKind::Tws).failing(AppError::TwsConnection("refused".into()));
+        let yahoo = MockProvider::new(ProviderKind::Yahoo).with_quote("SPY", 540.0);
+        let providers: Vec<Box<dyn QuoteProvider>> = vec![Box::new(failing()), Box::new(yahoo)];
+        assert_eq!(fetch(&providers, &["SPY"]).expect("quotes")["SPY"].price, 540.0);
+
+        let providers: Vec<Box<dyn QuoteProvider>> = vec![Box::new(failing())];
+        assert_eq!(fetch(&providers, &["SPY"]).expect_err("error").code(), "tws_connection");
+        assert_eq!(build_providers(&[ProviderKind::Yahoo, ProviderKind::Yahoo]).len(), 1);
+    }
+}
@@ -0,0 +1,92 @@
+// Cotation normalisée, commune à tous les fournisseurs : les champs qu'un fournisseur ne donne
+// pas restent à None, et price_field indique toujours d'où vient `price`
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketQuote {
+    /// Prix retenu pour les calculs (P/L latent, spot des grecques...)
+    pub price: f64,
+    pub price_field: PriceField,
+    /// Variation du jour associée au prix retenu
+    pub change_percent: f64,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    /// Dernier échange de la séance régulière (lastPrice chez Binance)
+    pub last: Option<f64>,
+    pub previous_close: Option<f64>,
+    pub day_high: Option<f64>,
+    pub day_low: Option<f64>,
+    pub volume: Option<f64>,
+    pub currency: Option<String>,
+    pub exchange: Option<String>,
+    pub market_state: Option<MarketState>,
+    /// Horodatage de la cotation chez le fournisseur (dernier échange, dernier tick)
+    pub quote_time: Option<DateTime<Utc>>,
+    /// Date de récupération auprès du fournisseur
+    pub as_of: DateTime<Utc>,
+    /// Dernier cours connu, servi parce que les fournisseurs n'ont pas répondu
+    pub stale: bool,
+}
+
+/// Champ du fournisseur dont provient `price`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceField {
+    /// regularMarketPrice (Yahoo) : dernier cours de la séance régulière, clôture hors séance
+    Regular,
+    PreMarket,
+    PostMarket,
+    /// Dernier échange (Binance, marché continu)
+    #[default]
+    Last,
+    /// Milieu de la fourchette bid/ask (TWS)
+    Mid,
+}
+
+/// Séance au moment de la cotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MarketState {
+    Pre,
+    Regular,
+    Post,
+    Closed,
+}
+
+impl MarketState {
+    /// marketState Yahoo : PREPRE / POSTPOST (nuit) sont rattachés à la séance étendue voisine
+    pub fn from_yahoo(state: &str) -> Option<Self> {
+        match state {
+            "PRE" | "PREPRE" => Some(Self::Pre),
+            "REGULAR" => Some(Self::Regular),
+            "POST" | "POSTPOST" => Some(Self::Post),
+            "CLOSED" => Some(Self::Closed),
+            _ => None,
+        }
+    }
+}
+
+impl MarketQuote {
+    pub fn new(price: f64, change_percent: f64) -> Self {
+        Self {
+            price,
+            price_field: PriceField::default(),
+            change_percent,
+            bid: None,
+            ask: None,
+            last: None,
+            previous_close: None,
+            day_high: None,
+            day_low: None,
+            volume: None,
+            currency: None,
+            exchange: None,
+            market_state: None,
+            quote_time: None,
+            as_of: Utc::now(),
+            stale: false,
+        }
+    }
+}
@@ -0,0 +1,234 @@
+// Service de cotations partagé (état Tauri) : cache avec TTL, regroupement des demandes
+// simultanées d'un même symbole, et dernier cours connu (stale) quand les fournisseurs échouent
+
+use super::{build_providers, fetch_with_fallback, load_settings, MarketQuote, ProviderKind, QuoteProvider};
+use crate::modules::error::AppError;
+use crate::modules::logging::MARKET_DATA;
+use chrono::{TimeDelta, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::watch;
+
+pub type Providers = Arc<Vec<Box<dyn QuoteProvider>>>;
+
+/// Fraîcheur d'une cotation en cache
+#[derive(Debug, Clone, Copy)]
+pub struct CachePolicy {
+    /// En deçà, la cotation est servie sans interroger les fournisseurs
+    pub ttl: TimeDelta,
+    /// Au-delà, une cotation périmée n'est plus servie (ni gardée en cache)
+    pub max_stale: TimeDelta,
+}
+
+#[derive(Default)]
+pub struct QuoteService {
+    /// Fournisseurs construits pour l'ordre des réglages (clients HTTP, session Yahoo, seaux à jetons)
+    providers: Mutex<Option<(Vec<ProviderKind>, Providers)>>,
+    cache: Mutex<HashMap<String, MarketQuote>>,
+    /// Symboles en cours de récupération ; le canal se ferme quand le cache est à jour
+    in_flight: Mutex<HashMap<String, watch::Receiver<()>>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Libère les symboles d'une récupération, y compris si la tâche est annulée
+struct InFlight<'a> {
+    service: &'a QuoteService,
+    symbols: Vec<String>,
+    _done: watch::Sender<()>,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = lock(&self.service.in_flight);
+        for symbol in &self.symbols {
+            in_flight.remove(symbol);
+        }
+    }
+}
+
+impl QuoteService {
+    /// Cotations avec les fournisseurs et la politique de cache des réglages
+    pub async fn quotes<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        symbols: &[String],
+    ) -> Result<HashMap<String, MarketQuote>, AppError> {
+        let market_data = load_settings(app_handle).map_err(AppError::Validation)?;
+        let providers = self.providers(&market_data.providers);
+        self.quotes_with(&providers, symbols, market_data.cache_policy()).await
+    }
+
+    /// Fournisseurs des réglages, partagés avec les cotations (mêmes seaux à jetons) ;
+    /// sert à l'historique de barres
+    pub fn configured_providers<R: Runtime>(&self, app_handle: &AppHandle<R>) -> Result<Providers, AppError> {
+        let market_data = load_settings(app_handle).map_err(AppError::Validation)?;
+        Ok(self.providers(&market_data.providers))
+    }
+
+    fn providers(&self, kinds: &[ProviderKind]) -> Providers {
+        let mut current = lock(&self.providers);
+        match current.as_ref() {
+            Some((built_for, providers)) if built_for == kinds => providers.clone(),
+            _ => {
+                let providers: Providers = Arc::new(build_providers(kinds));
+                *current = Some((kinds.to_vec(), providers.clone()));
+                providers
+            }
+        }
+    }
+
+    /// Cache frais, sinon fournisseurs (ou attente d'une récupération déjà lancée), sinon dernier
+    /// cours connu marqué `stale`. L'erreur n'est renvoyée que si aucun symbole n'a de cours
+    pub async fn quotes_with(
+        &self,
+        providers: &[Box<dyn QuoteProvider>],
+        symbols: &[String],
+        policy: CachePolicy,
+    ) -> Result<HashMap<String, MarketQuote>, AppError> {
+        let mut wanted: Vec<String> = Vec::new();
+        for symbol in symbols {
+            if !symbol.trim().is_empty() && !wanted.contains(symbol) {
+                wanted.push(symbol.clone());
+            }
+        }
+
+        let mut result = HashMap::new();
+        let mut to_fetch = Vec::new();
+        let mut pending = Vec::new();
+        let sender = {
+            let cache = lock(&self.cache);
+            let mut in_flight = lock(&self.in_flight);
+            let now = Utc::now();
+            for symbol in &wanted {
+                match cache.get(symbol) {
+                    Some(quote) if now - quote.as_of < policy.ttl => {
+                        result.insert(symbol.clone(), quote.clone());
+                    }
+                    _ => match in_flight.get(symbol) {
+                        Some(done) => pending.push(done.clone()),
+                        None => to_fetch.push(symbol.clone()),
+                    },
+                }
+            }
+            let (sender, done) = watch::channel(());
+            in_flight.extend(to_fetch.iter().map(|s| (s.clone(), done.clone())));
+            sender
+        };
+
+        let mut error = None;
+        if !to_fetch.is_empty() {
+            let guard = InFlight { service: self, symbols: to_fetch.clone(), _done: sender };
+            match fetch_with_fallback(providers, &to_fetch).await {
+                Ok(quotes) => self.store(quotes, policy),
+                Err(e) => error = Some(e),
+            }
+            drop(guard);
+        }
+        for mut done in pending {
+            // Erreur = émetteur fermé : la récupération est terminée dans tous les cas
+            let _ = done.changed().await;
+        }
+
+        let cache = lock(&self.cache);
+        let now = Utc::now();
+        for symbol in &wanted {
+            let Some(quote) = cache.get(symbol).filter(|_| !result.contains_key(symbol)) else { continue };
+            let age = now - quote.as_of;
+            if age < policy.ttl {
+                result.insert(symbol.clone(), quote.clone());
+            } else if age < policy.max_stale {
+                tracing::debug!(target: MARKET_DATA, symbol = %symbol, age_secs = age.num_seconds(),
+                    "Serving stale quote");
+                result.insert(symbol.clone(), MarketQuote { stale: true, ..quote.clone() });
+            }
+        }
+
+        match error {
+            Some(e) if result.is_empty() => Err(e),
+            _ => Ok(result),
+        }
+    }
+
+    fn store(&self, quotes: HashMap<String, MarketQuote>, policy: CachePolicy) {
+        let mut cache = lock(&self.cache);
+        let now = Utc::now();
+        cache.retain(|_, q| now - q.as_of < policy.max_stale);
+        cache.extend(quotes);
+    }
+}
+
+/// Raccourci pour les modules qui n'ont que l'AppHandle
+pub async fn fetch_quotes<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    symbols: Vec<String>,
+) -> Result<HashMap<String, MarketQuote>, AppError> {
+    app_handle.state::<QuoteService>().quotes(app_handle, &symbols).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockProvider;
+    use super::*;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    const POLICY: CachePolicy = CachePolicy { ttl: TimeDelta::seconds(15), max_stale: TimeDelta::hours(72) };
+
+    fn symbols(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_fresh_quotes_are_cached_and_stale_ones_served_on_failure() {
+        let service = QuoteService::default();
+        let mock = MockProvider::new(ProviderKind::Yahoo).with_quote("SPY", 540.0);
+        let calls = mock.calls();
+        let providers: Vec<Box<dyn QuoteProvider>> = vec![Box::new(mock)];
+        let quotes = tauri::async_runtime::block_on(async {
+            service.quotes_with(&providers, &symbols(&["SPY"]), POLICY).await.expect("first");
+            service.quotes_with(&providers, &symbols(&["SPY"]), POLICY).await.expect("cached")
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!quotes["SPY"].stale);
+
+        let mut old = MarketQuote::new(530.0, 0.0);
+        old.as_of = Utc::now() - TimeDelta::hours(2);
+        service.store(HashMap::from([("QQQ".to_string(), old)]), POLICY);
+        let failing: Vec<Box<dyn QuoteProvider>> =
+            vec![Box::new(MockProvider::new(ProviderKind::Yahoo).failing(AppError::Network("down".into())))];
+        let quotes = tauri::async_runtime::block_on(service.quotes_with(&failing, &symbols(&["QQQ"]), POLICY))
+            .expect("stale quote instead of an error");
+        assert!(quotes["QQQ"].stale);
+        assert_eq!(quotes["QQQ"].price, 530.0);
+        let error = tauri::async_runtime::block_on(service.quotes_with(&failing, &symbols(&["IWM"]), POLICY));
+        assert_eq!(error.expect_err("nothing cached").code(), "network");
+    }
+
+    #[test]
+    fn test_concurrent_requests_for_the_same_symbol_are_coalesced() {
+        let service = Arc::new(QuoteService::default());
+        let mock = MockProvider::new(ProviderKind::Binance)
+            .with_quote("BTCUSDT", 65000.0)
+            .delayed(Duration::from_millis(50));
+        let calls = mock.calls();
+        let providers: Providers = Arc::new(vec![Box::new(mock)]);
+        let prices = tauri::async_runtime::block_on(async {
+            let mut tasks = tokio::task::JoinSet::new();
+            for _ in 0..3 {
+                let (service, providers) = (service.clone(), providers.clone());
+                tasks.spawn(async move { service.quotes_with(&providers, &symbols(&["BTCUSDT"]), POLICY).await });
+            }
+            let mut prices = Vec::new();
+            while let Some(joined) = tasks.join_next().await {
+                prices.push(joined.expect("task").expect("quotes")["BTCUSDT"].price);
+            }
+            prices
+        });
+        assert_eq!(prices, vec![65000.0; 3]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
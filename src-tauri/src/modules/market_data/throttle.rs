@@ -0,0 +1,150 @@
+// Découpage en lots et limite de débit : chaque fournisseur construit par build_providers est
+// enveloppé dans un Throttled qui découpe les symboles selon max_batch() et prend un jeton par requête
+
+use super::{Bar, BarInterval, MarketQuote, ProviderKind, QuoteProvider};
+use crate::modules::error::AppError;
+use crate::modules::logging::MARKET_DATA;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Seau à jetons : `burst` requêtes d'affilée, puis `per_second` en régime établi
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub per_second: f64,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self { burst: 5, per_second: 1.0 }
+    }
+}
+
+pub struct TokenBucket {
+    limit: RateLimit,
+    /// Jetons disponibles, date du dernier remplissage
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    pub fn new(limit: RateLimit) -> Self {
+        Self { limit, state: Mutex::new((f64::from(limit.burst.max(1)), Instant::now())) }
+    }
+
+    /// Attend qu'un jeton soit disponible puis le consomme
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.try_take(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// None si un jeton a été pris, sinon l'attente avant le prochain
+    fn try_take(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let (tokens, last) = *state;
+        let per_second = self.limit.per_second.max(f64::EPSILON);
+        let capacity = f64::from(self.limit.burst.max(1));
+        let tokens = (tokens + now.saturating_duration_since(last).as_secs_f64() * per_second).min(capacity);
+        if tokens >= 1.0 {
+            *state = (tokens - 1.0, now);
+            None
+        } else {
+            *state = (tokens, now);
+            Some(Duration::from_secs_f64((1.0 - tokens) / per_second))
+        }
+    }
+}
+
+pub struct Throttled {
+    inner: Box<dyn QuoteProvider>,
+    bucket: TokenBucket,
+}
+
+impl Throttled {
+    pub fn new(inner: Box<dyn QuoteProvider>) -> Self {
+        let bucket = TokenBucket::new(inner.rate_limit());
+        Self { inner, bucket }
+    }
+}
+
+#[async_trait]
+impl QuoteProvider for Throttled {
+    fn kind(&self) -> ProviderKind {
+        self.inner.kind()
+    }
+
+    fn supports(&self, symbol: &str) -> bool {
+        self.inner.supports(symbol)
+    }
+
+    fn max_batch(&self) -> usize {
+        self.inner.max_batch()
+    }
+
+    fn rate_limit(&self) -> RateLimit {
+        self.inner.rate_limit()
+    }
+
+    /// Un lot en échec n'annule pas les autres ; l'erreur n'est renvoyée que si rien n'a été trouvé
+    async fn quotes(&self, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError> {
+        let mut found = HashMap::new();
+        let mut first_error = None;
+        for chunk in symbols.chunks(self.inner.max_batch().max(1)) {
+            self.bucket.acquire().await;
+            match self.inner.quotes(chunk).await {
+                Ok(quotes) => found.extend(quotes),
+                Err(e) => {
+                    tracing::warn!(target: MARKET_DATA, provider = ?self.kind(), batch = chunk.len(), error = %e,
+                        "Quote batch failed");
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if found.is_empty() => Err(e),
+            _ => Ok(found),
+        }
+    }
+
+    async fn bars(
+        &self,
+        symbol: &str,
+        interval: BarInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, AppError> {
+        self.bucket.acquire().await;
+        self.inner.bars(symbol, interval, from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockProvider;
+    use super::*;
+
+    #[test]
+    fn test_bucket_allows_burst_then_paces_requests() {
+        let bucket = TokenBucket::new(RateLimit { burst: 2, per_second: 2.0 });
+        let start = Instant::now();
+        assert!(bucket.try_take(start).is_none());
+        assert!(bucket.try_take(start).is_none());
+        let wait = bucket.try_take(start).expect("empty bucket");
+        assert!((wait.as_secs_f64() - 0.5).abs() < 1e-6);
+        assert!(bucket.try_take(start + Duration::from_millis(500)).is_none());
+    }
+
+    #[test]
+    fn test_symbols_are_sent_in_provider_sized_batches() {
+        let mock = MockProvider::new(ProviderKind::Binance).with_quote("A", 1.0).with_quote("E", 5.0).batch_of(2);
+        let calls = mock.calls();
+        let throttled = Throttled::new(Box::new(mock));
+        let symbols: Vec<String> = ["A", "B", "C", "D", "E"].iter().map(|s| s.to_string()).collect();
+        let quotes = tauri::async_runtime::block_on(throttled.quotes(&symbols)).expect("quotes");
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,109 @@
+// TWS / IB Gateway (API socket, crate ibapi) : premier tick bid/ask de chaque action (routage
+// SMART, USD), milieu de fourchette comme prix. ibapi 0.1 n'expose pas de requête snapshot :
+// abonnements tick-by-tick un par un (annulés dès le premier tick, plafond d'abonnements simultanés
+// bas), lecture bornée par SNAPSHOT_DEADLINE, client détruit avant que `quotes` ne rende la main
+
+use super::{MarketQuote, PriceField, ProviderKind, QuoteProvider, RateLimit};
+use crate::modules::error::AppError;
+use crate::modules::logging::MARKET_DATA;
+use crate::modules::tws_socket::TWSConfig;
+use async_trait::async_trait;
+use chrono::DateTime;
+use ibapi::contracts::Contract;
+use ibapi::Client;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Client id distinct de la sync TWS (deux connexions simultanées possibles)
+const MARKET_DATA_CLIENT_ID_OFFSET: i32 = 1;
+/// Attente maximale d'un message par ibapi (recv_timeout interne) : `next()` rend None au-delà
+const TICK_WAIT: Duration = Duration::from_secs(10);
+/// Aucun nouvel abonnement passé ce délai ; le lot en cours se termine au plus TICK_WAIT après
+const SNAPSHOT_DEADLINE: Duration = Duration::from_secs(15);
+/// Garde-fou (connexion bloquée) : le thread finit normalement avant, client et abonnements détruits
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(SNAPSHOT_DEADLINE.as_secs() + TICK_WAIT.as_secs() + 5);
+const MAX_TICKER_LEN: usize = 6;
+/// Un lot = une connexion, lu en série dans SNAPSHOT_DEADLINE
+const MAX_BATCH: usize = 5;
+
+#[derive(Default)]
+pub struct TwsProvider {
+    config: TWSConfig,
+}
+
+#[async_trait]
+impl QuoteProvider for TwsProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Tws
+    }
+
+    /// Actions US seulement ("AAPL", "BRK.B") : ni forex Yahoo, ni indices, ni options
+    fn supports(&self, symbol: &str) -> bool {
+        !symbol.is_empty()
+            && symbol.len() <= MAX_TICKER_LEN
+            && symbol.chars().all(|c| c.is_ascii_uppercase() || c == '.')
+    }
+
+    fn max_batch(&self) -> usize {
+        MAX_BATCH
+    }
+
+    /// Chaque lot ouvre une connexion API : on évite d'enchaîner les connexions
+    fn rate_limit(&self) -> RateLimit {
+        RateLimit { burst: 2, per_second: 0.5 }
+    }
+
+    async fn quotes(&self, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError> {
+        let address = format!("{}:{}", self.config.host, self.config.port);
+        let client_id = self.config.client_id + MARKET_DATA_CLIENT_ID_OFFSET;
+        let symbols = symbols.to_vec();
+        let task = tauri::async_runtime::spawn_blocking(move || snapshot(&address, client_id, &symbols));
+        match tokio::time::timeout(SNAPSHOT_TIMEOUT, task).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(AppError::Internal(format!("TWS market data task failed: {}", e))),
+            Err(_) => Err(AppError::TwsConnection("TWS market data timed out".to_string())),
+        }
+    }
+}
+
+fn snapshot(address: &str, client_id: i32, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError> {
+    let client = Client::connect(address, client_id)
+        .map_err(|e| AppError::TwsConnection(format!("TWS connection failed ({}): {}", address, e)))?;
+    let deadline = Instant::now() + SNAPSHOT_DEADLINE;
+    let mut quotes = HashMap::new();
+    for (index, symbol) in symbols.iter().enumerate() {
+        if Instant::now() >= deadline {
+            let skipped = symbols.len() - index;
+            tracing::warn!(target: MARKET_DATA, skipped, "TWS snapshot deadline reached");
+            break;
+        }
+        let mut ticks = match client.tick_by_tick_bid_ask(&Contract::stock(symbol), 0, false) {
+            Ok(ticks) => ticks,
+            Err(e) => {
+                tracing::warn!(target: MARKET_DATA, symbol = %symbol, error = %e, "TWS bid/ask request failed");
+                continue;
+            }
+        };
+        // Premier tick seulement : l'abonnement est annulé quand l'itérateur est détruit
+        let Some(tick) = ticks.next() else {
+            // TICK_WAIT écoulé sans tick (marché fermé, pas d'abonnement aux données) :
+            // les symboles suivants attendraient autant, on rend ce qui a été lu
+            tracing::debug!(target: MARKET_DATA, symbol = %symbol, "No TWS bid/ask tick, batch stopped");
+            break;
+        };
+        if tick.bid_price > 0.0 && tick.ask_price > 0.0 {
+            let price = (tick.bid_price + tick.ask_price) / 2.0;
+            let quote = MarketQuote {
+                price_field: PriceField::Mid,
+                bid: Some(tick.bid_price),
+                ask: Some(tick.ask_price),
+                currency: Some("USD".to_string()),
+                exchange: Some("SMART".to_string()),
+                quote_time: DateTime::from_timestamp(tick.time.unix_timestamp(), 0),
+                ..MarketQuote::new(price, 0.0)
+            };
+            quotes.insert(symbol.clone(), quote);
+        }
+    }
+    Ok(quotes)
+}
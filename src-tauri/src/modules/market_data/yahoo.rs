@@ -0,0 +1,86 @@
+// Yahoo Finance (actions, indices, forex "EURUSD=X") : client HTTP dans modules/yahoo.rs.
+// La session (cookie + crumb) est gardée entre deux appels et rouverte seulement si Yahoo la refuse
+
+use super::{Bar, BarInterval, MarketQuote, ProviderKind, QuoteProvider, RateLimit};
+use crate::modules::error::AppError;
+use crate::modules::logging::MARKET_DATA;
+use crate::modules::yahoo::{self, Session};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Au-delà, l'URL v7/quote devient trop longue et Yahoo tronque la réponse
+const MAX_BATCH: usize = 50;
+
+#[derive(Default)]
+pub struct YahooProvider {
+    session: Mutex<Option<Arc<Session>>>,
+}
+
+impl YahooProvider {
+    async fn session(&self, renew: bool) -> Result<Arc<Session>, AppError> {
+        let mut current = self.session.lock().await;
+        match current.as_ref() {
+            Some(session) if !renew => Ok(session.clone()),
+            _ => {
+                let session = Arc::new(Session::open().await?);
+                *current = Some(session.clone());
+                Ok(session)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl QuoteProvider for YahooProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Yahoo
+    }
+
+    fn supports(&self, _symbol: &str) -> bool {
+        true
+    }
+
+    fn max_batch(&self) -> usize {
+        MAX_BATCH
+    }
+
+    /// API non officielle : rafale courte puis une requête toutes les 2 s
+    fn rate_limit(&self) -> RateLimit {
+        RateLimit { burst: 5, per_second: 0.5 }
+    }
+
+    async fn quotes(&self, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError> {
+        let session = self.session(false).await?;
+        match yahoo::fetch_quotes(&session, symbols).await {
+            Err(AppError::Auth(reason)) => {
+                tracing::debug!(target: MARKET_DATA, reason = %reason, "Yahoo session refused, renewing crumb");
+                yahoo::fetch_quotes(&*self.session(true).await?, symbols).await
+            }
+            other => other,
+        }
+    }
+
+    /// Historique horaire limité par Yahoo aux 730 derniers jours
+    async fn bars(
+        &self,
+        symbol: &str,
+        interval: BarInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, AppError> {
+        let interval = match interval {
+            BarInterval::Day => "1d",
+            BarInterval::Hour => "60m",
+        };
+        let session = self.session(false).await?;
+        match yahoo::fetch_chart(&session, symbol, interval, from, to).await {
+            Err(AppError::Auth(_)) => {
+                yahoo::fetch_chart(&*self.session(true).await?, symbol, interval, from, to).await
+            }
+            other => other,
+        }
+    }
+}
@@ -0,0 +1,205 @@
+// Migrations de schéma de trading.db, exécutées au démarrage avant la création de la fenêtre
+// Chaque migration tourne dans sa propre transaction et est tracée dans schema_version ;
+// PRAGMA user_version suit la dernière version (reprise dans les manifestes de sauvegarde)
+
+mod steps;
+
+pub use steps::MIGRATIONS;
+
+/// Opération notée dans le journal des sauvegardes pour la sauvegarde pré-migration
+pub const BACKUP_OPERATION: &str = "schema_migration";
+
+use crate::modules::{backup, db};
+use rusqlite::{params, Connection, Transaction};
+use serde::Serialize;
+use std::fs;
+use tauri::{AppHandle, Runtime};
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub apply: fn(&Transaction) -> Result<(), String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub from_version: i64,
+    pub to_version: i64,
+    pub applied: Vec<String>,
+    /// Sauvegarde locale prise avant d'appliquer la première migration en attente ;
+    /// ses copies hors site sont envoyées après l'ouverture de la fenêtre
+    pub backup: Option<String>,
+}
+
+/// Met trading.db à jour ; toute erreur doit interrompre le démarrage
+pub fn run_at_startup<R: Runtime>(app_handle: &AppHandle<R>) -> Result<MigrationReport, String> {
+    let path = db::db_path(app_handle)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let existed = path.exists();
+    let mut conn = db::open_path(&path)?;
+    let current = current_version(&mut conn)?;
+
+    // Base existante avec migrations en attente : sauvegarde obligatoire, sinon rien n'est appliqué
+    let backup = if existed && pending(current, MIGRATIONS).next().is_some() {
+        let backup = tauri::async_runtime::block_on(backup::backup_local_before(app_handle, BACKUP_OPERATION))
+            .map_err(|e| format!("Pre-migration backup failed, schema left at version {}: {}", current, e))?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    let mut report = migrate(&mut conn, MIGRATIONS).map_err(|e| match &backup {
+        Some(b) => format!("{} (database left at version {}, backup: {})", e, current, b),
+        None => e,
+    })?;
+    report.backup = backup;
+    Ok(report)
+}
+
+/// Applique dans l'ordre les migrations de version supérieure à la version courante
+pub fn migrate(conn: &mut Connection, migrations: &[Migration]) -> Result<MigrationReport, String> {
+    let from_version = current_version(conn)?;
+    let latest = latest_version(migrations);
+    if from_version > latest {
+        return Err(format!(
+            "Database schema version {} is newer than this application (version {}); update the application",
+            from_version, latest
+        ));
+    }
+
+    let mut applied = Vec::new();
+    for migration in pending(from_version, migrations) {
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let label = format!("{:03}_{}", migration.version, migration.name);
+        (migration.apply)(&tx)
+            .and_then(|_| {
+                tx.execute("INSERT INTO schema_version (version, name) VALUES (?1, ?2)", params![
+                    migration.version,
+                    migration.name
+                ])
+                .map_err(|e| e.to_string())?;
+                tx.pragma_update(None, "user_version", migration.version).map_err(|e| e.to_string())
+            })
+            .map_err(|e| format!("Migration {} failed: {}", label, e))?;
+        // Sans commit, la transaction est annulée au drop : la base reste à la version précédente
+        tx.commit().map_err(|e| format!("Migration {} failed: {}", label, e))?;
+        applied.push(label);
+    }
+    Ok(MigrationReport { from_version, to_version: current_version(conn)?, applied, backup: None })
+}
+
+/// Version atteinte une fois toutes les migrations appliquées
+pub fn latest_version(migrations: &[Migration]) -> i64 {
+    migrations.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+fn pending(current: i64, migrations: &[Migration]) -> impl Iterator<Item = &Migration> {
+    let mut sorted: Vec<&Migration> = migrations.iter().filter(|m| m.version > current).collect();
+    sorted.sort_by_key(|m| m.version);
+    sorted.into_iter()
+}
+
+fn current_version(conn: &mut Connection) -> Result<i64, String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+           version INTEGER PRIMARY KEY,
+           name TEXT NOT NULL,
+           applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+         )",
+    )
+    .map_err(|e| e.to_string())?;
+    conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)").expect("prepare");
+        let rows = stmt.query_map([table], |r| r.get(0)).expect("query");
+        rows.collect::<Result<_, _>>().expect("columns")
+    }
+
+    #[test]
+    fn test_fresh_database_reaches_latest_version() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        let report = migrate(&mut conn, MIGRATIONS).expect("migrate");
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, MIGRATIONS.len() as i64);
+        let user_version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0)).expect("pragma");
+        assert_eq!(user_version, report.to_version);
+        assert!(columns(&conn, "trades").contains(&"entry_limit".to_string()));
+        let accounts: i64 = conn.query_row("SELECT COUNT(*) FROM accounts", [], |r| r.get(0)).expect("count");
+        assert_eq!(accounts, 2);
+
+        // Deuxième passage : rien à faire, pas de doublon de comptes
+        assert!(migrate(&mut conn, MIGRATIONS).expect("again").applied.is_empty());
+        let accounts: i64 = conn.query_row("SELECT COUNT(*) FROM accounts", [], |r| r.get(0)).expect("count");
+        assert_eq!(accounts, 2);
+    }
+
+    #[test]
+    fn test_legacy_database_keeps_data_and_gains_columns() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        conn.execute_batch(
+            "CREATE TABLE accounts (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL UNIQUE, capital REAL);
+             INSERT INTO accounts (name, capital) VALUES ('Perso', 1);
+             CREATE TABLE trades (id INTEGER PRIMARY KEY AUTOINCREMENT, account_id INTEGER, date TEXT NOT NULL,
+               symbol TEXT NOT NULL, strategy TEXT NOT NULL, is_deleted INTEGER DEFAULT 0);
+             INSERT INTO trades (account_id, date, symbol, strategy) VALUES (1, '2024-01-02', 'SPY', 'wheel');
+             CREATE TABLE kasper_daily_journal (id INTEGER PRIMARY KEY, date TEXT NOT NULL, profit_loss REAL,
+               risk_used REAL, notes TEXT, details TEXT, created_at TEXT);
+             INSERT INTO kasper_daily_journal (date, profit_loss) VALUES ('2024-01-03', 42);",
+        )
+        .expect("legacy");
+
+        migrate(&mut conn, MIGRATIONS).expect("migrate");
+        // Le trade sans jambe n'est plus supprimé au démarrage
+        let trades: i64 = conn.query_row("SELECT COUNT(*) FROM trades", [], |r| r.get(0)).expect("count");
+        assert_eq!(trades, 1);
+        assert!(columns(&conn, "trades").contains(&"exit_price".to_string()));
+        let (account, pnl): (i64, f64) = conn
+            .query_row("SELECT account_id, profit_loss FROM kasper_daily_journal", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .expect("journal");
+        let kasper_default: i64 = conn.query_row("SELECT id FROM kasper_accounts", [], |r| r.get(0)).expect("kasper");
+        assert_eq!((account, pnl), (kasper_default, 42.0));
+        let accounts: i64 = conn.query_row("SELECT COUNT(*) FROM accounts", [], |r| r.get(0)).expect("count");
+        assert_eq!(accounts, 1);
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back_and_stops() {
+        fn create_ok(tx: &Transaction) -> Result<(), String> {
+            tx.execute_batch("CREATE TABLE ok_table (id INTEGER)").map_err(|e| e.to_string())
+        }
+        fn half_done(tx: &Transaction) -> Result<(), String> {
+            tx.execute_batch("CREATE TABLE partial (id INTEGER); INSERT INTO missing VALUES (1);")
+                .map_err(|e| e.to_string())
+        }
+        let migrations = [
+            Migration { version: 1, name: "ok", apply: create_ok },
+            Migration { version: 2, name: "broken", apply: half_done },
+            Migration { version: 3, name: "never", apply: create_ok },
+        ];
+        let mut conn = Connection::open_in_memory().expect("db");
+        let err = migrate(&mut conn, &migrations).expect_err("broken");
+        assert!(err.contains("002_broken"));
+        assert_eq!(current_version(&mut conn).expect("version"), 1);
+        let partial: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'partial'", [], |r| r.get(0))
+            .expect("count");
+        assert_eq!(partial, 0);
+    }
+
+    #[test]
+    fn test_newer_database_is_rejected() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("migrate");
+        conn.execute("INSERT INTO schema_version (version, name) VALUES (999, 'future')", []).expect("future");
+        assert!(migrate(&mut conn, MIGRATIONS).expect_err("newer").contains("newer"));
+    }
+}
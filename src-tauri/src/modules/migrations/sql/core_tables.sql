@@ -0,0 +1,162 @@
+CREATE TABLE IF NOT EXISTS accounts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE,
+  capital REAL DEFAULT 0,
+  cash_used REAL DEFAULT 0,
+  currency TEXT DEFAULT 'EUR',
+  created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS trades (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER,
+  date TEXT NOT NULL,
+  symbol TEXT NOT NULL,
+  strategy TEXT NOT NULL,
+  status TEXT DEFAULT 'open',
+  notes TEXT,
+  created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(account_id) REFERENCES accounts(id)
+);
+
+CREATE TABLE IF NOT EXISTS legs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  trade_id INTEGER,
+  type TEXT NOT NULL,
+  side TEXT NOT NULL,
+  quantity REAL NOT NULL,
+  strike REAL,
+  expiration TEXT,
+  open_price REAL NOT NULL,
+  close_price REAL,
+  current_price REAL,
+  status TEXT DEFAULT 'open',
+  FOREIGN KEY(trade_id) REFERENCES trades(id) ON DELETE CASCADE
+);
+
+-- IB Gateway Historical Trades (Phase 1.2)
+CREATE TABLE IF NOT EXISTS rocket_trades_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  ib_trade_id TEXT UNIQUE NOT NULL,
+  symbol TEXT NOT NULL,
+  asset_class TEXT,
+  side TEXT NOT NULL,
+  quantity REAL NOT NULL,
+  price_avg REAL NOT NULL,
+  commission REAL DEFAULT 0,
+  realized_pnl REAL,
+  unrealized_pnl REAL,
+  open_date TEXT NOT NULL,
+  close_date TEXT,
+  expiry TEXT,
+  strike REAL,
+  strategy TEXT,
+  order_type TEXT,
+  synced_at TEXT DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(strategy) REFERENCES strategies(id)
+);
+CREATE INDEX IF NOT EXISTS idx_ib_trade_id ON rocket_trades_history(ib_trade_id);
+CREATE INDEX IF NOT EXISTS idx_strategy ON rocket_trades_history(strategy);
+CREATE INDEX IF NOT EXISTS idx_symbol ON rocket_trades_history(symbol);
+
+-- Flex Trades IBKR : 20 champs du struct FlexTrade + strategy (override utilisateur) + is_deleted
+CREATE TABLE IF NOT EXISTS flex_trades (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  trade_id TEXT UNIQUE NOT NULL,
+  account_id TEXT,
+  symbol TEXT NOT NULL,
+  asset_class TEXT,
+  side TEXT,
+  quantity INTEGER DEFAULT 0,
+  multiplier INTEGER DEFAULT 1,
+  price REAL DEFAULT 0,
+  commission REAL DEFAULT 0,
+  realized_pnl REAL DEFAULT 0,
+  date TEXT,
+  time TEXT,
+  expiry TEXT,
+  strike REAL DEFAULT 0,
+  put_call TEXT,
+  open_close TEXT,
+  exchange TEXT,
+  proceeds REAL DEFAULT 0,
+  cost_basis REAL DEFAULT 0,
+  notes TEXT,
+  strategy TEXT,
+  is_deleted INTEGER DEFAULT 0,
+  synced_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_flex_trade_id ON flex_trades(trade_id);
+CREATE INDEX IF NOT EXISTS idx_flex_symbol ON flex_trades(symbol);
+CREATE INDEX IF NOT EXISTS idx_flex_date ON flex_trades(date);
+
+-- Positions ouvertes saisies manuellement
+CREATE TABLE IF NOT EXISTS open_positions (
+  id            TEXT PRIMARY KEY,
+  strategy      TEXT NOT NULL,
+  symbol        TEXT NOT NULL,
+  asset_class   TEXT NOT NULL DEFAULT 'OPT',
+  side          TEXT NOT NULL,
+  quantity      INTEGER NOT NULL,
+  price         REAL NOT NULL,
+  commission    REAL DEFAULT 0,
+  open_date     TEXT NOT NULL,
+  expiry        TEXT,
+  strike        REAL,
+  put_call      TEXT,
+  notes         TEXT,
+  created_at    TEXT DEFAULT (datetime('now')),
+  updated_at    TEXT DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_op_strategy ON open_positions(strategy);
+CREATE INDEX IF NOT EXISTS idx_op_symbol ON open_positions(symbol);
+
+CREATE TABLE IF NOT EXISTS sync_metadata (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  last_sync_date TEXT,
+  trades_synced INTEGER DEFAULT 0,
+  next_sync_date TEXT,
+  account_id TEXT,
+  UNIQUE(account_id)
+);
+
+CREATE TABLE IF NOT EXISTS audit_logs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  table_name TEXT,
+  record_id TEXT,
+  action TEXT,
+  old_value TEXT,
+  new_value TEXT,
+  timestamp TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS assignments (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  wheel_trade_id INTEGER,
+  symbol TEXT NOT NULL,
+  assignment_date TEXT NOT NULL,
+  quantity REAL NOT NULL,
+  price REAL NOT NULL,
+  type TEXT CHECK(type IN ('PUT_ASSIGNMENT', 'CALL_ASSIGNMENT')),
+  created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(wheel_trade_id) REFERENCES trades(id) ON DELETE SET NULL
+);
+
+-- Module Kasper (forex)
+CREATE TABLE IF NOT EXISTS kasper_accounts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  initial_capital REAL DEFAULT 0,
+  currency TEXT DEFAULT 'USD',
+  is_default BOOLEAN DEFAULT 0,
+  created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS kasper_pairs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  symbol TEXT NOT NULL,
+  pip_value REAL DEFAULT 1,
+  sl_pips REAL DEFAULT 10,
+  risk_pct REAL DEFAULT 1,
+  created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,12 @@
+CREATE TABLE kasper_daily_journal (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER DEFAULT 1,
+  date TEXT NOT NULL,
+  profit_loss REAL DEFAULT 0,
+  risk_used REAL DEFAULT 0,
+  notes TEXT,
+  details TEXT,
+  created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+  UNIQUE(date, account_id),
+  FOREIGN KEY(account_id) REFERENCES kasper_accounts(id)
+);
@@ -0,0 +1,49 @@
+-- Cache des barres OHLC historiques (market_data) : time au format 2024-01-15T00:00:00Z
+CREATE TABLE IF NOT EXISTS price_bars (
+  symbol TEXT NOT NULL,
+  bar_interval TEXT NOT NULL,
+  time TEXT NOT NULL,
+  open REAL NOT NULL,
+  high REAL NOT NULL,
+  low REAL NOT NULL,
+  close REAL NOT NULL,
+  volume REAL,
+  PRIMARY KEY (symbol, bar_interval, time)
+);
+
+-- Plages [range_start, range_end[ déjà chargées : un trou dans price_bars (week-end, jour férié)
+-- n'impose pas de redemander la période
+CREATE TABLE IF NOT EXISTS price_bar_ranges (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  symbol TEXT NOT NULL,
+  bar_interval TEXT NOT NULL,
+  range_start TEXT NOT NULL,
+  range_end TEXT NOT NULL,
+  provider TEXT NOT NULL,
+  fetched_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_price_bar_ranges ON price_bar_ranges(symbol, bar_interval);
+
+-- MAE / MFE des trades clôturés (prix unitaires, pourcentages du prix d'entrée ou de sortie)
+CREATE TABLE IF NOT EXISTS trade_excursions (
+  trade_id INTEGER PRIMARY KEY,
+  symbol TEXT NOT NULL,
+  bar_interval TEXT NOT NULL,
+  provider TEXT NOT NULL,
+  side TEXT NOT NULL,
+  entry_price REAL NOT NULL,
+  exit_price REAL NOT NULL,
+  mae REAL NOT NULL,
+  mae_pct REAL NOT NULL,
+  mfe REAL NOT NULL,
+  mfe_pct REAL NOT NULL,
+  captured REAL NOT NULL,
+  capture_ratio REAL,
+  left_on_table REAL,
+  left_on_table_pct REAL,
+  post_exit_days INTEGER NOT NULL,
+  bars_held INTEGER NOT NULL,
+  bars_after INTEGER NOT NULL,
+  computed_at TEXT DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(trade_id) REFERENCES trades(id) ON DELETE CASCADE
+);
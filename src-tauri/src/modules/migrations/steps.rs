@@ -0,0 +1,200 @@
+// Migrations numérotées de trading.db — ne jamais modifier une migration publiée,
+// toujours en ajouter une nouvelle à la fin de MIGRATIONS
+// 1 à 5 reprennent le schéma historique de src/utils/db.js (bases existantes dans tous les états)
+
+use super::Migration;
+use crate::modules::audit;
+use rusqlite::Transaction;
+
+const CORE_TABLES: &str = include_str!("sql/core_tables.sql");
+const KASPER_JOURNAL: &str = include_str!("sql/kasper_journal.sql");
+const PRICE_HISTORY: &str = include_str!("sql/price_history.sql");
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "drop_pre_accounts_schema", apply: drop_pre_accounts_schema },
+    Migration { version: 2, name: "core_tables", apply: core_tables },
+    Migration { version: 3, name: "historical_columns", apply: historical_columns },
+    Migration { version: 4, name: "kasper_journal_accounts", apply: kasper_journal_accounts },
+    Migration { version: 5, name: "seed_accounts", apply: seed_accounts },
+    Migration { version: 6, name: "audit_trail", apply: audit_trail },
+    Migration { version: 7, name: "sync_high_water_marks", apply: sync_high_water_marks },
+    Migration { version: 8, name: "price_history_excursions", apply: price_history_excursions },
+    Migration { version: 9, name: "flex_sync_marks", apply: flex_sync_marks },
+];
+
+/// Schéma d'avant la table accounts : trades / portfolios / strategies incompatibles
+fn drop_pre_accounts_schema(tx: &Transaction) -> Result<(), String> {
+    if table_exists(tx, "trades")? && !table_exists(tx, "accounts")? {
+        tx.execute_batch(
+            "DROP TABLE IF EXISTS trades;
+             DROP TABLE IF EXISTS portfolios;
+             DROP TABLE IF EXISTS strategies;",
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn core_tables(tx: &Transaction) -> Result<(), String> {
+    tx.execute_batch(CORE_TABLES).map_err(|e| e.to_string())
+}
+
+/// Colonnes ajoutées au fil des versions par des ALTER TABLE silencieux : ajoutées si absentes
+fn historical_columns(tx: &Transaction) -> Result<(), String> {
+    const COLUMNS: &[(&str, &str, &str)] = &[
+        ("accounts", "alloc_wheel", "REAL DEFAULT 0"),
+        ("accounts", "margin_wheel_pct", "REAL DEFAULT 0"),
+        ("accounts", "alloc_growth", "REAL DEFAULT 0"),
+        ("accounts", "alloc_rocket", "REAL DEFAULT 0"),
+        ("trades", "asset_type", "TEXT DEFAULT 'option'"),
+        ("trades", "profit_loss", "REAL DEFAULT 0"),
+        ("trades", "open_date", "TEXT"),
+        ("trades", "target_yield", "REAL DEFAULT 0"),
+        ("trades", "position_size_pct", "REAL DEFAULT 0"),
+        ("trades", "exit_partial_price", "REAL"),
+        ("trades", "exit_partial_date", "TEXT"),
+        ("trades", "exit_partial_quantity", "REAL"),
+        ("trades", "trailing_stop", "REAL"),
+        ("trades", "entry_executed", "REAL"),
+        ("trades", "exit_price", "REAL"),
+        ("trades", "exit_date", "TEXT"),
+        ("trades", "is_deleted", "INTEGER DEFAULT 0"),
+        ("trades", "sub_strategy", "TEXT"),
+        ("trades", "broker", "TEXT"),
+        ("trades", "stop_loss", "REAL"),
+        ("trades", "entry_stop", "REAL"),
+        ("trades", "entry_limit", "REAL"),
+        ("legs", "open_date", "TEXT"),
+        ("rocket_trades_history", "is_deleted", "INTEGER DEFAULT 0"),
+        ("kasper_accounts", "account_number", "TEXT"),
+    ];
+    for (table, column, definition) in COLUMNS {
+        if !column_exists(tx, table, column)? {
+            tx.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                .map_err(|e| format!("{}.{}: {}", table, column, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Journal Kasper multi-comptes : l'ancienne table sans account_id est reconstruite
+/// et ses lignes rattachées au compte par défaut
+fn kasper_journal_accounts(tx: &Transaction) -> Result<(), String> {
+    let exists = table_exists(tx, "kasper_daily_journal")?;
+    if exists && column_exists(tx, "kasper_daily_journal", "account_id")? {
+        return Ok(());
+    }
+    if exists {
+        tx.execute_batch("ALTER TABLE kasper_daily_journal RENAME TO kasper_daily_journal_old")
+            .map_err(|e| e.to_string())?;
+    }
+    tx.execute_batch(KASPER_JOURNAL).map_err(|e| e.to_string())?;
+    if exists {
+        let default_account = ensure_kasper_account(tx)?;
+        tx.execute(
+            "INSERT INTO kasper_daily_journal (date, profit_loss, risk_used, notes, details, created_at, account_id)
+             SELECT date, profit_loss, risk_used, notes, details, created_at, ?1 FROM kasper_daily_journal_old",
+            [default_account],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute_batch("DROP TABLE kasper_daily_journal_old").map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Comptes par défaut d'une base neuve (faits une seule fois, plus à chaque démarrage)
+fn seed_accounts(tx: &Transaction) -> Result<(), String> {
+    let accounts: i64 = tx.query_row("SELECT COUNT(*) FROM accounts", [], |r| r.get(0)).map_err(|e| e.to_string())?;
+    if accounts == 0 {
+        tx.execute_batch(
+            "INSERT INTO accounts (name, capital) VALUES ('Rocket Academy', 10000);
+             INSERT INTO accounts (name, capital) VALUES ('Kasper Academy', 5000);",
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    ensure_kasper_account(tx).map(|_| ())
+}
+
+/// Journal d'audit alimenté par triggers (toutes les écritures, UI comprise) ;
+/// acteur et commande fournis par audit_context pendant les transactions Rust
+fn audit_trail(tx: &Transaction) -> Result<(), String> {
+    for column in ["actor", "command"] {
+        if !column_exists(tx, "audit_logs", column)? {
+            tx.execute_batch(&format!("ALTER TABLE audit_logs ADD COLUMN {} TEXT", column))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS audit_context (
+           id INTEGER PRIMARY KEY CHECK (id = 1),
+           actor TEXT NOT NULL,
+           command TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_audit_record ON audit_logs(table_name, record_id);",
+    )
+    .map_err(|e| e.to_string())?;
+    audit::install_triggers(tx)
+}
+
+/// Date du dernier trade synchronisé par compte IBKR (sync Flex incrémentale)
+fn sync_high_water_marks(tx: &Transaction) -> Result<(), String> {
+    if !column_exists(tx, "sync_metadata", "last_trade_date")? {
+        tx.execute_batch("ALTER TABLE sync_metadata ADD COLUMN last_trade_date TEXT").map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Cache des barres historiques et MAE / MFE des trades clôturés (modules/excursions)
+fn price_history_excursions(tx: &Transaction) -> Result<(), String> {
+    tx.execute_batch(PRICE_HISTORY).map_err(|e| e.to_string())
+}
+
+/// Date synchronisée par (profil, query, compte) : départ de la sync Flex incrémentale (sync/marks.rs)
+fn flex_sync_marks(tx: &Transaction) -> Result<(), String> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS flex_sync_marks (
+            profile TEXT NOT NULL,
+            query_id INTEGER NOT NULL,
+            account_id TEXT NOT NULL,
+            synced_through TEXT NOT NULL,
+            PRIMARY KEY (profile, query_id, account_id)
+        )",
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Id du compte Kasper par défaut, créé s'il n'existe aucun compte
+fn ensure_kasper_account(tx: &Transaction) -> Result<i64, String> {
+    let existing: Option<i64> = tx
+        .query_row("SELECT id FROM kasper_accounts ORDER BY is_default DESC, id LIMIT 1", [], |r| r.get(0))
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(e.to_string()),
+        })?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+    tx.execute(
+        "INSERT INTO kasper_accounts (name, initial_capital, is_default) VALUES ('Compte Principal', 5000, 1)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(tx.last_insert_rowid())
+}
+
+fn table_exists(tx: &Transaction, table: &str) -> Result<bool, String> {
+    tx.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1", [table], |r| {
+        r.get::<_, i64>(0)
+    })
+    .map(|n| n > 0)
+    .map_err(|e| e.to_string())
+}
+
+fn column_exists(tx: &Transaction, table: &str, column: &str) -> Result<bool, String> {
+    tx.query_row("SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2", [table, column], |r| {
+        r.get::<_, i64>(0)
+    })
+    .map(|n| n > 0)
+    .map_err(|e| e.to_string())
+}
@@ -1,3 +1,22 @@
 pub mod ib_gateway;    // Ancien module (REST) - gardé pour compatibilité
 pub mod tws_socket;    // Nouveau module (Socket TCP) - RECOMMANDÉ
 pub mod backup;
+pub mod dates;
+pub mod db;
+pub mod error;
+pub mod seasonality;
+pub mod pricing;
+pub mod portfolio;
+pub mod alerts;
+pub mod yahoo;
+pub mod market_data;
+pub mod settings;
+pub mod migrations;
+pub mod flex_trades;
+pub mod audit;
+pub mod reconciliation;
+pub mod sync;
+pub mod secrets;
+pub mod redact;
+pub mod logging;
+pub mod excursions;
@@ -0,0 +1,42 @@
+// Commandes Tauri des positions ouvertes : positions TWS, risque agrégé et grille de scénarios
+
+use super::{PortfolioRisk, PortfolioRiskRequest, ScenarioRequest, StrategyScenario};
+use crate::modules::db;
+use crate::modules::error::AppError;
+use crate::modules::tws_socket::{Position, TWSConfig, TWSSyncClient};
+
+/// Commande Tauri: Récupère les positions ouvertes actuelles (NOUVEAU - Socket TCP)
+#[tauri::command]
+pub async fn fetch_positions() -> Result<Vec<Position>, AppError> {
+    let client = TWSSyncClient::new(TWSConfig::default());
+    client.get_positions().await
+}
+
+/// Commande Tauri: Risque agrégé (open_positions + TWS) — greeks nets, delta bêta-pondéré SPY,
+/// capital à risque par spread et contrôle des allocations MM pour les StatCards
+#[tauri::command]
+pub async fn get_portfolio_risk(
+    app_handle: tauri::AppHandle,
+    request: PortfolioRiskRequest,
+) -> Result<PortfolioRisk, AppError> {
+    let allocations = {
+        let conn = db::open_connection(&app_handle).map_err(AppError::Database)?;
+        super::load_allocations(&conn).map_err(AppError::Database)?
+    };
+    let positions = super::load_open_positions(&app_handle, request.include_tws).await?;
+    let spots = super::fetch_spots(&app_handle, &positions).await?;
+    let today = chrono::Local::now().date_naive();
+    Ok(super::compute_risk(&positions, &spots, &allocations, &request, today))
+}
+
+/// Commande Tauri: Grille what-if (mouvement du sous-jacent × T+n × choc de vol) par stratégie ouverte
+#[tauri::command]
+pub async fn get_scenario_grid(
+    app_handle: tauri::AppHandle,
+    request: ScenarioRequest,
+) -> Result<Vec<StrategyScenario>, AppError> {
+    let positions = super::load_open_positions(&app_handle, request.include_tws).await?;
+    let spots = super::fetch_spots(&app_handle, &positions).await?;
+    let today = chrono::Local::now().date_naive();
+    super::build_scenarios(&positions, &spots, &request, today).map_err(AppError::Validation)
+}
@@ -0,0 +1,234 @@
+// Chargement des positions ouvertes : saisies manuelles (open_positions) + positions TWS
+
+use super::RiskPosition;
+use crate::modules::dates::parse_trade_date;
+use crate::modules::pricing::OptionKind;
+use crate::modules::tws_socket::Position;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+/// Les positions manuelles n'ont pas de colonne compte : elles appartiennent au compte Rocket
+pub const MANUAL_ACCOUNT: &str = "Rocket Academy";
+
+const OPTION_MULTIPLIER: f64 = 100.0;
+
+/// Allocations MM du compte Rocket (mêmes règles que useDashboardLogic)
+#[derive(Debug, Clone, Default)]
+pub struct Allocations {
+    pub wheel: f64, // alloc_wheel + marge
+    pub pcs: f64,   // alloc_growth
+    pub rockets: f64,
+}
+
+/// Lit toutes les lignes de open_positions et les convertit en positions signées
+pub fn load_manual_positions(conn: &Connection) -> Result<Vec<RiskPosition>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, strategy, symbol, COALESCE(asset_class, 'OPT'), side, quantity, price,
+                    expiry, strike, put_call
+             FROM open_positions",
+        )
+        .map_err(|e| format!("open_positions query error: {}", e))?;
+
+    let rows = stmt
+        .query_map([], |r| {
+            let side: String = r.get(4)?;
+            let quantity: f64 = r.get(5)?;
+            let sign = if side.eq_ignore_ascii_case("SELL") { -1.0 } else { 1.0 };
+            let expiry: Option<String> = r.get(7)?;
+            let put_call: Option<String> = r.get(9)?;
+            let asset_class: String = r.get(3)?;
+            Ok(build_position(
+                RawPosition {
+                    id: r.get(0)?,
+                    source: "manual",
+                    account: MANUAL_ACCOUNT.to_string(),
+                    strategy: r.get::<_, Option<String>>(1)?.map(|s| s.to_lowercase()),
+                    symbol: r.get(2)?,
+                    is_option: !asset_class.eq_ignore_ascii_case("STK"),
+                    quantity: sign * quantity.abs(),
+                    entry_price: r.get(6)?,
+                },
+                expiry.as_deref().and_then(parse_trade_date),
+                r.get::<_, Option<f64>>(8)?,
+                put_call.as_deref().and_then(parse_put_call),
+            ))
+        })
+        .map_err(|e| format!("open_positions query error: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Convertit une position TWS ; les options arrivent au format OCC ("MSFT 250620C00450000")
+/// avec un coût moyen par contrat
+pub fn from_tws_position(p: &Position) -> Option<RiskPosition> {
+    if p.position == 0.0 {
+        return None;
+    }
+    let occ = parse_occ_symbol(&p.symbol);
+    let is_option = occ.is_some();
+    let (expiry, strike, kind) = match &occ {
+        Some(o) => (Some(o.expiry), Some(o.strike), Some(o.kind)),
+        None => (None, None, None),
+    };
+    Some(build_position(
+        RawPosition {
+            id: format!("tws:{}:{}", p.account, p.symbol),
+            source: "tws",
+            account: p.account.clone(),
+            strategy: None,
+            symbol: p.symbol.clone(),
+            is_option,
+            quantity: p.position,
+            entry_price: if is_option { p.avg_cost / OPTION_MULTIPLIER } else { p.avg_cost },
+        },
+        expiry,
+        strike,
+        kind,
+    ))
+}
+
+/// Lit les allocations du compte Rocket : wheel = base + marge, PCS = growth, rockets = rocket
+pub fn load_allocations(conn: &Connection) -> Result<Allocations, String> {
+    let result = conn.query_row(
+        "SELECT COALESCE(alloc_wheel, 0), COALESCE(margin_wheel_pct, 0),
+                COALESCE(alloc_growth, 0), COALESCE(alloc_rocket, 0)
+         FROM accounts WHERE name = ?1",
+        [MANUAL_ACCOUNT],
+        |r| {
+            let wheel: f64 = r.get(0)?;
+            let margin_pct: f64 = r.get(1)?;
+            Ok(Allocations { wheel: wheel * (1.0 + margin_pct / 100.0), pcs: r.get(2)?, rockets: r.get(3)? })
+        },
+    );
+    match result {
+        Ok(a) => Ok(a),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(Allocations::default()),
+        Err(e) => Err(format!("accounts query error: {}", e)),
+    }
+}
+
+struct RawPosition {
+    id: String,
+    source: &'static str,
+    account: String,
+    strategy: Option<String>,
+    symbol: String,
+    is_option: bool,
+    quantity: f64,
+    entry_price: f64,
+}
+
+fn build_position(
+    raw: RawPosition,
+    expiry: Option<NaiveDate>,
+    strike: Option<f64>,
+    kind: Option<OptionKind>,
+) -> RiskPosition {
+    // Une saisie manuelle peut contenir directement le symbole OCC
+    let occ = if raw.is_option { parse_occ_symbol(&raw.symbol) } else { None };
+    let underlying = match &occ {
+        Some(o) => o.underlying.clone(),
+        None => raw.symbol.split_whitespace().next().unwrap_or(&raw.symbol).to_uppercase(),
+    };
+    RiskPosition {
+        id: raw.id,
+        source: raw.source.to_string(),
+        account: raw.account,
+        strategy: raw.strategy,
+        underlying,
+        kind: if raw.is_option { kind.or(occ.as_ref().map(|o| o.kind)) } else { None },
+        strike: strike.or(occ.as_ref().map(|o| o.strike)).unwrap_or(0.0),
+        expiry: expiry.or(occ.as_ref().map(|o| o.expiry)),
+        quantity: raw.quantity,
+        multiplier: if raw.is_option { OPTION_MULTIPLIER } else { 1.0 },
+        entry_price: raw.entry_price,
+    }
+}
+
+fn parse_put_call(raw: &str) -> Option<OptionKind> {
+    match raw.trim().to_uppercase().as_str() {
+        "P" | "PUT" => Some(OptionKind::Put),
+        "C" | "CALL" => Some(OptionKind::Call),
+        _ => None,
+    }
+}
+
+struct OccSymbol {
+    underlying: String,
+    expiry: NaiveDate,
+    kind: OptionKind,
+    strike: f64,
+}
+
+/// "MSFT 250620C00450000" → MSFT, 2025-06-20, Call, 450.0
+fn parse_occ_symbol(symbol: &str) -> Option<OccSymbol> {
+    let mut parts = symbol.split_whitespace();
+    let underlying = parts.next()?.to_uppercase();
+    let code = parts.next()?;
+    if parts.next().is_some() || code.len() != 15 || !code.is_ascii() {
+        return None;
+    }
+    let expiry = NaiveDate::parse_from_str(&code[..6], "%y%m%d").ok()?;
+    let kind = parse_put_call(&code[6..7])?;
+    let strike = code[7..].parse::<u32>().ok()? as f64 / 1000.0;
+    Some(OccSymbol { underlying, expiry, kind, strike })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_occ_symbol() {
+        let occ = parse_occ_symbol("MSFT 250620C00450000").expect("occ");
+        assert_eq!(occ.underlying, "MSFT");
+        assert_eq!(occ.expiry, NaiveDate::from_ymd_opt(2025, 6, 20).expect("date"));
+        assert_eq!(occ.kind, OptionKind::Call);
+        assert_eq!(occ.strike, 450.0);
+        assert!(parse_occ_symbol("AAPL").is_none());
+    }
+
+    #[test]
+    fn test_tws_option_cost_per_share() {
+        let p = Position {
+            symbol: "MSFT 250620C00450000".to_string(),
+            position: -2.0,
+            avg_cost: 1250.0,
+            unrealized_pnl: 0.0,
+            realized_pnl: 0.0,
+            account: "DU12345".to_string(),
+        };
+        let r = from_tws_position(&p).expect("position");
+        assert_eq!(r.underlying, "MSFT");
+        assert_eq!(r.entry_price, 12.5);
+        assert_eq!(r.quantity, -2.0);
+        assert_eq!(r.multiplier, 100.0);
+    }
+
+    #[test]
+    fn test_load_manual_positions_and_allocations() {
+        let conn = Connection::open_in_memory().expect("db");
+        conn.execute_batch(
+            "CREATE TABLE open_positions (id TEXT PRIMARY KEY, strategy TEXT, symbol TEXT, asset_class TEXT,
+                side TEXT, quantity INTEGER, price REAL, expiry TEXT, strike REAL, put_call TEXT);
+             INSERT INTO open_positions VALUES ('a', 'PCS', 'spy', 'OPT', 'SELL', 2, 3.1, '2025-06-20', 500, 'P');
+             INSERT INTO open_positions VALUES ('b', 'Wheel', 'KO', 'STK', 'BUY', 100, 60.0, NULL, NULL, NULL);
+             CREATE TABLE accounts (name TEXT, alloc_wheel REAL, margin_wheel_pct REAL, alloc_growth REAL, alloc_rocket REAL);
+             INSERT INTO accounts VALUES ('Rocket Academy', 10000, 50, 4000, 2000);",
+        )
+        .expect("schema");
+
+        let positions = load_manual_positions(&conn).expect("positions");
+        assert_eq!(positions[0].quantity, -2.0);
+        assert_eq!(positions[0].underlying, "SPY");
+        assert_eq!(positions[0].kind, Some(OptionKind::Put));
+        assert_eq!(positions[0].strategy.as_deref(), Some("pcs"));
+        assert_eq!(positions[1].kind, None);
+        assert_eq!(positions[1].multiplier, 1.0);
+
+        let alloc = load_allocations(&conn).expect("alloc");
+        assert_eq!(alloc.wheel, 15000.0);
+        assert_eq!(alloc.pcs, 4000.0);
+    }
+}
@@ -0,0 +1,278 @@
+// Risque agrégé du portefeuille : greeks nets, delta bêta-pondéré SPY, capital à risque, limites MM
+// Sources : positions manuelles (open_positions) + positions TWS
+
+pub mod commands;
+mod loader;
+mod scenario;
+mod sources;
+mod spreads;
+
+pub use loader::{from_tws_position, load_allocations, load_manual_positions, Allocations, MANUAL_ACCOUNT};
+pub use scenario::{build_scenarios, ScenarioCurve, ScenarioRequest, StrategyScenario};
+pub use sources::{fetch_spots, load_open_positions};
+pub use spreads::SpreadRisk;
+
+use crate::modules::pricing::{self, ExerciseStyle, Greeks, OptionKind, OptionParams, DAYS_PER_YEAR};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Référence du delta bêta-pondéré
+pub const BENCHMARK: &str = "SPY";
+
+/// Vol utilisée pour les greeks quand le frontend n'en fournit pas pour le sous-jacent
+pub const DEFAULT_VOLATILITY: f64 = 0.30;
+
+/// Position normalisée (quantité signée : > 0 long, < 0 short)
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskPosition {
+    pub id: String,
+    pub source: String, // "manual" | "tws"
+    pub account: String,
+    pub strategy: Option<String>,
+    pub underlying: String,
+    pub kind: Option<OptionKind>, // None : action
+    pub strike: f64,
+    pub expiry: Option<NaiveDate>,
+    pub quantity: f64,
+    pub multiplier: f64,
+    pub entry_price: f64,
+}
+
+impl RiskPosition {
+    pub fn days_to_expiry(&self, today: NaiveDate) -> f64 {
+        self.expiry.map_or(0.0, |e| (e - today).num_days().max(0) as f64)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PortfolioRiskRequest {
+    /// Bêta vs SPY par sous-jacent (défaut 1.0)
+    #[serde(default)]
+    pub betas: HashMap<String, f64>,
+    /// Vol implicite par sous-jacent (défaut DEFAULT_VOLATILITY)
+    #[serde(default)]
+    pub volatilities: HashMap<String, f64>,
+    /// Ajoute les positions TWS
+    #[serde(default = "crate::modules::tws_socket::default_include_tws")]
+    pub include_tws: bool,
+    #[serde(default = "default_rate")]
+    pub rate: f64,
+}
+
+fn default_rate() -> f64 {
+    pricing::DEFAULT_RISK_FREE_RATE
+}
+
+/// Greeks en dollars de position : delta en actions, theta en $/jour, vega en $ par point de vol
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskBucket {
+    pub key: String,
+    pub spot: Option<f64>,
+    pub greeks: Greeks,
+    pub dollar_delta: f64,
+    pub beta_weighted_delta: f64, // équivalent en actions SPY
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocationCheck {
+    pub strategy: String,
+    pub allocated: f64,
+    pub capital_at_risk: f64,
+    pub usage_pct: f64,
+    pub exceeded: bool,
+    pub has_undefined_risk: bool, // au moins une jambe à perte non bornée
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioRisk {
+    pub by_underlying: Vec<RiskBucket>,
+    pub by_account: Vec<RiskBucket>,
+    pub totals: RiskBucket,
+    pub spreads: Vec<SpreadRisk>,
+    pub book_max_loss: Option<f64>, // ≤ 0, None : au moins un risque non borné
+    pub allocations: Vec<AllocationCheck>,
+    pub missing_quotes: Vec<String>,
+    pub position_count: usize,
+}
+
+/// Sous-jacents à coter (benchmark inclus)
+pub fn quote_symbols(positions: &[RiskPosition]) -> Vec<String> {
+    let mut symbols: Vec<String> = positions.iter().map(|p| p.underlying.clone()).collect();
+    symbols.push(BENCHMARK.to_string());
+    symbols.sort();
+    symbols.dedup();
+    symbols
+}
+
+/// Agrège greeks, delta bêta-pondéré et capital à risque ; compare le risque de chaque
+/// stratégie Rocket à son allocation pour que le dashboard bloque les dépassements
+pub fn compute_risk(
+    positions: &[RiskPosition],
+    spots: &HashMap<String, f64>,
+    allocations: &Allocations,
+    request: &PortfolioRiskRequest,
+    today: NaiveDate,
+) -> PortfolioRisk {
+    let spy = spots.get(BENCHMARK).copied();
+    let mut by_underlying: BTreeMap<String, RiskBucket> = BTreeMap::new();
+    let mut by_account: BTreeMap<String, RiskBucket> = BTreeMap::new();
+    let mut totals = empty_bucket("TOTAL", None);
+
+    for p in positions {
+        let spot = spots.get(&p.underlying).copied();
+        let greeks = position_greeks(p, spot, request, today);
+        let dollar_delta = spot.map_or(0.0, |s| greeks.delta * s);
+        let beta = request.betas.get(&p.underlying).copied().unwrap_or(1.0);
+        let bwd = match spy {
+            Some(spy) if spy > 0.0 => dollar_delta * beta / spy,
+            _ => 0.0,
+        };
+        for bucket in [
+            by_underlying.entry(p.underlying.clone()).or_insert_with(|| empty_bucket(&p.underlying, spot)),
+            by_account.entry(p.account.clone()).or_insert_with(|| empty_bucket(&p.account, None)),
+            &mut totals,
+        ] {
+            bucket.greeks.add(&greeks);
+            bucket.dollar_delta += dollar_delta;
+            bucket.beta_weighted_delta += bwd;
+        }
+    }
+
+    let spreads = spreads::group_spreads(positions, spots, today, request.rate);
+    let book_max_loss = spreads
+        .iter()
+        .try_fold(0.0, |acc, s| s.capital_at_risk.map(|c| acc - c));
+
+    let mut missing_quotes: Vec<String> =
+        quote_symbols(positions).into_iter().filter(|s| !spots.contains_key(s)).collect();
+    missing_quotes.dedup();
+
+    PortfolioRisk {
+        by_underlying: by_underlying.into_values().collect(),
+        by_account: by_account.into_values().collect(),
+        totals,
+        allocations: allocation_checks(&spreads, allocations),
+        spreads,
+        book_max_loss,
+        missing_quotes,
+        position_count: positions.len(),
+    }
+}
+
+fn empty_bucket(key: &str, spot: Option<f64>) -> RiskBucket {
+    RiskBucket { key: key.to_string(), spot, greeks: Greeks::default(), dollar_delta: 0.0, beta_weighted_delta: 0.0 }
+}
+
+fn position_greeks(p: &RiskPosition, spot: Option<f64>, request: &PortfolioRiskRequest, today: NaiveDate) -> Greeks {
+    let size = p.quantity * p.multiplier;
+    let (Some(kind), Some(spot)) = (p.kind, spot) else {
+        // Action : delta = nombre d'actions ; option sans cotation : pas de greeks fiables
+        return if p.kind.is_none() { Greeks { delta: size, ..Greeks::default() } } else { Greeks::default() };
+    };
+    let params = OptionParams {
+        spot,
+        strike: p.strike,
+        time_years: p.days_to_expiry(today) / DAYS_PER_YEAR,
+        rate: request.rate,
+        dividend_yield: 0.0,
+        volatility: request.volatilities.get(&p.underlying).copied().unwrap_or(DEFAULT_VOLATILITY),
+    };
+    pricing::greeks(kind, ExerciseStyle::American, &params).scaled(size)
+}
+
+fn allocation_checks(spreads: &[SpreadRisk], allocations: &Allocations) -> Vec<AllocationCheck> {
+    [("wheel", allocations.wheel), ("pcs", allocations.pcs), ("rockets", allocations.rockets)]
+        .into_iter()
+        .map(|(strategy, allocated)| {
+            let groups: Vec<&SpreadRisk> =
+                spreads.iter().filter(|s| s.strategy.as_deref() == Some(strategy)).collect();
+            let capital_at_risk: f64 = groups.iter().filter_map(|s| s.capital_at_risk).sum();
+            let has_undefined_risk = groups.iter().any(|s| !s.defined_risk);
+            let usage_pct = if allocated > 0.0 { capital_at_risk / allocated * 100.0 } else { 0.0 };
+            AllocationCheck {
+                strategy: strategy.to_string(),
+                allocated,
+                capital_at_risk,
+                usage_pct,
+                exceeded: has_undefined_risk || (allocated > 0.0 && capital_at_risk > allocated),
+                has_undefined_risk,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> PortfolioRiskRequest {
+        PortfolioRiskRequest {
+            betas: HashMap::from([("TSLA".to_string(), 2.0)]),
+            volatilities: HashMap::new(),
+            include_tws: false,
+            rate: 0.04,
+        }
+    }
+
+    fn stock(id: &str, underlying: &str, account: &str, quantity: f64, strategy: &str) -> RiskPosition {
+        RiskPosition {
+            id: id.to_string(),
+            source: "manual".to_string(),
+            account: account.to_string(),
+            strategy: Some(strategy.to_string()),
+            underlying: underlying.to_string(),
+            kind: None,
+            strike: 0.0,
+            expiry: None,
+            quantity,
+            multiplier: 1.0,
+            entry_price: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_beta_weighted_delta_and_allocation() {
+        let positions = vec![
+            stock("a", "TSLA", "Rocket Academy", 10.0, "rockets"),
+            stock("b", "SPY", "DU12345", -5.0, "rockets"),
+        ];
+        let spots = HashMap::from([("TSLA".to_string(), 250.0), ("SPY".to_string(), 500.0)]);
+        let alloc = Allocations { wheel: 0.0, pcs: 0.0, rockets: 1500.0 };
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).expect("date");
+        let risk = compute_risk(&positions, &spots, &alloc, &request(), today);
+
+        // 10 TSLA × 250 × β2 / 500 = 10 SPY ; -5 SPY → total 5
+        assert!((risk.totals.beta_weighted_delta - 5.0).abs() < 1e-9);
+        assert_eq!(risk.by_account.len(), 2);
+        // Short SPY : perte non bornée → livre non borné et limite rockets dépassée
+        assert!(risk.book_max_loss.is_none());
+        let rockets = risk.allocations.iter().find(|a| a.strategy == "rockets").expect("rockets");
+        assert!(rockets.exceeded && rockets.has_undefined_risk);
+        assert!(risk.missing_quotes.is_empty());
+    }
+
+    #[test]
+    fn test_short_put_greeks() {
+        let put = RiskPosition {
+            kind: Some(OptionKind::Put),
+            strike: 95.0,
+            expiry: NaiveDate::from_ymd_opt(2025, 7, 1),
+            quantity: -1.0,
+            multiplier: 100.0,
+            entry_price: 2.0,
+            ..stock("p", "KO", "Rocket Academy", 0.0, "wheel")
+        };
+        let spots = HashMap::from([("KO".to_string(), 100.0)]);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).expect("date");
+        let alloc = Allocations { wheel: 20_000.0, pcs: 0.0, rockets: 0.0 };
+        let risk = compute_risk(&[put], &spots, &alloc, &request(), today);
+        let g = &risk.by_underlying[0].greeks;
+        assert!(g.delta > 0.0 && g.theta > 0.0 && g.vega < 0.0);
+        // Put vendu : perte max = 95 × 100 - 200
+        assert_eq!(risk.book_max_loss, Some(-9300.0));
+        let wheel = risk.allocations.iter().find(|a| a.strategy == "wheel").expect("wheel");
+        assert!(!wheel.exceeded);
+        assert!(risk.missing_quotes.contains(&"SPY".to_string()));
+    }
+}
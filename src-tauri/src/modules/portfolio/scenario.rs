@@ -0,0 +1,224 @@
+// Scénarios what-if : matrice P&L (mouvement du sous-jacent × jours écoulés × choc de vol)
+// Donne aux vues PCS / Wheel le diagramme à l'échéance et les courbes T+n
+
+use super::spreads::{common_strategy, group_positions};
+use super::{RiskPosition, DEFAULT_VOLATILITY};
+use crate::modules::pricing::{self, ExerciseStyle, OptionParams, DAYS_PER_YEAR};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MAX_PRICE_STEPS: u32 = 201;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioRequest {
+    /// Amplitude du mouvement testé en % (ex: 15 → -15% .. +15%)
+    #[serde(default = "default_move_pct")]
+    pub price_move_pct: f64,
+    /// Nombre de points de prix (impair pour inclure le spot)
+    #[serde(default = "default_price_steps")]
+    pub price_steps: u32,
+    /// Jours écoulés à simuler (T+n) ; le payoff à l'échéance est toujours renvoyé (expiry_pnl)
+    #[serde(default = "default_days_forward")]
+    pub days_forward: Vec<u32>,
+    /// Chocs de vol en points (ex: -5, 0, +5)
+    #[serde(default = "default_iv_shifts")]
+    pub iv_shifts: Vec<f64>,
+    #[serde(default)]
+    pub volatilities: HashMap<String, f64>,
+    /// Même source que le risque portefeuille
+    #[serde(default = "crate::modules::tws_socket::default_include_tws")]
+    pub include_tws: bool,
+    #[serde(default = "super::default_rate")]
+    pub rate: f64,
+    /// Filtre optionnel ("pcs", "wheel"...)
+    #[serde(default)]
+    pub strategy: Option<String>,
+}
+
+fn default_move_pct() -> f64 {
+    15.0
+}
+
+fn default_price_steps() -> u32 {
+    31
+}
+
+fn default_days_forward() -> Vec<u32> {
+    vec![0, 7]
+}
+
+fn default_iv_shifts() -> Vec<f64> {
+    vec![0.0]
+}
+
+/// Une courbe P&L pour un couple (jours écoulés, choc de vol)
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioCurve {
+    pub days_forward: u32,
+    pub iv_shift: f64,
+    pub pnl: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyScenario {
+    pub account: String,
+    pub underlying: String,
+    pub expiry: Option<String>,
+    pub strategy: Option<String>,
+    pub position_ids: Vec<String>,
+    pub spot: f64,
+    pub spot_is_estimate: bool, // pas de cotation : centré sur le strike
+    pub volatility: f64,
+    pub moves_pct: Vec<f64>,
+    pub prices: Vec<f64>,
+    pub expiry_pnl: Vec<f64>,
+    pub curves: Vec<ScenarioCurve>,
+}
+
+/// Calcule la grille P&L de chaque stratégie ouverte avec le modèle américain de la crate
+pub fn build_scenarios(
+    positions: &[RiskPosition],
+    spots: &HashMap<String, f64>,
+    request: &ScenarioRequest,
+    today: NaiveDate,
+) -> Result<Vec<StrategyScenario>, String> {
+    if !(request.price_move_pct > 0.0 && request.price_move_pct < 100.0) {
+        return Err(format!("Invalid price move: {}%", request.price_move_pct));
+    }
+    let steps = request.price_steps.clamp(3, MAX_PRICE_STEPS);
+    let moves_pct: Vec<f64> = (0..steps)
+        .map(|i| -request.price_move_pct + 2.0 * request.price_move_pct * f64::from(i) / f64::from(steps - 1))
+        .collect();
+    let iv_shifts = if request.iv_shifts.is_empty() { default_iv_shifts() } else { request.iv_shifts.clone() };
+
+    let scenarios = group_positions(positions)
+        .into_iter()
+        .map(|((account, underlying, expiry), legs)| (account, underlying, expiry, common_strategy(&legs), legs))
+        .filter(|(_, _, _, strategy, _)| request.strategy.is_none() || *strategy == request.strategy)
+        .map(|(account, underlying, expiry, strategy, legs)| {
+            let quoted = spots.get(&underlying).copied().filter(|s| *s > 0.0);
+            let spot = quoted
+                .or_else(|| legs.iter().map(|l| l.strike.max(l.entry_price)).reduce(f64::max))
+                .unwrap_or(1.0);
+            let volatility = request.volatilities.get(&underlying).copied().unwrap_or(DEFAULT_VOLATILITY);
+            let prices: Vec<f64> = moves_pct.iter().map(|m| spot * (1.0 + m / 100.0)).collect();
+            let horizon = legs.iter().filter(|l| l.kind.is_some()).map(|l| l.days_to_expiry(today)).fold(0.0, f64::max);
+
+            let expiry_pnl = prices.iter().map(|s| group_pnl(&legs, *s, horizon, volatility, request.rate, today)).collect();
+            let mut curves = Vec::new();
+            for days in &request.days_forward {
+                for shift in &iv_shifts {
+                    let vol = (volatility + shift / 100.0).max(0.01);
+                    curves.push(ScenarioCurve {
+                        days_forward: *days,
+                        iv_shift: *shift,
+                        pnl: prices
+                            .iter()
+                            .map(|s| group_pnl(&legs, *s, f64::from(*days), vol, request.rate, today))
+                            .collect(),
+                    });
+                }
+            }
+
+            StrategyScenario {
+                account,
+                underlying,
+                expiry: expiry.map(|d| d.format("%Y-%m-%d").to_string()),
+                strategy,
+                position_ids: legs.iter().map(|l| l.id.clone()).collect(),
+                spot,
+                spot_is_estimate: quoted.is_none(),
+                volatility,
+                moves_pct: moves_pct.clone(),
+                prices,
+                expiry_pnl,
+                curves,
+            }
+        })
+        .collect();
+    Ok(scenarios)
+}
+
+/// P&L du groupe si le sous-jacent vaut `s` dans `days_forward` jours
+fn group_pnl(legs: &[&RiskPosition], s: f64, days_forward: f64, vol: f64, rate: f64, today: NaiveDate) -> f64 {
+    legs.iter()
+        .map(|p| {
+            let value = match p.kind {
+                None => s,
+                Some(kind) => {
+                    let remaining = (p.days_to_expiry(today) - days_forward).max(0.0);
+                    let params = OptionParams {
+                        spot: s,
+                        strike: p.strike,
+                        time_years: remaining / DAYS_PER_YEAR,
+                        rate,
+                        dividend_yield: 0.0,
+                        volatility: vol,
+                    };
+                    pricing::price(kind, ExerciseStyle::American, &params)
+                }
+            };
+            p.quantity * p.multiplier * (value - p.entry_price)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::pricing::OptionKind;
+
+    fn short_put() -> RiskPosition {
+        RiskPosition {
+            id: "p".to_string(),
+            source: "manual".to_string(),
+            account: "Rocket Academy".to_string(),
+            strategy: Some("wheel".to_string()),
+            underlying: "KO".to_string(),
+            kind: Some(OptionKind::Put),
+            strike: 60.0,
+            expiry: NaiveDate::from_ymd_opt(2025, 7, 1),
+            quantity: -1.0,
+            multiplier: 100.0,
+            entry_price: 1.5,
+        }
+    }
+
+    fn request() -> ScenarioRequest {
+        serde_json::from_str(r#"{"price_move_pct": 10, "price_steps": 5, "days_forward": [0, 10], "iv_shifts": [0, 5]}"#)
+            .expect("json")
+    }
+
+    #[test]
+    fn test_expiry_diagram_and_curves() {
+        let spots = HashMap::from([("KO".to_string(), 60.0)]);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).expect("date");
+        let scenarios = build_scenarios(&[short_put()], &spots, &request(), today).expect("scenarios");
+        let s = &scenarios[0];
+        assert_eq!(s.prices, vec![54.0, 57.0, 60.0, 63.0, 66.0]);
+        // À l'échéance : -600 + 150 à 54, crédit entier au-dessus du strike
+        assert!((s.expiry_pnl[0] + 450.0).abs() < 1e-6);
+        assert!((s.expiry_pnl[4] - 150.0).abs() < 1e-6);
+        assert_eq!(s.curves.len(), 4);
+        // Put vendu : hausse de vol = perte
+        let flat = &s.curves[0].pnl[2];
+        let vol_up = &s.curves[1].pnl[2];
+        assert!(vol_up < flat);
+        // Le temps joue pour le vendeur
+        assert!(s.curves[2].pnl[2] > s.curves[0].pnl[2]);
+    }
+
+    #[test]
+    fn test_strategy_filter_and_missing_quote() {
+        let mut req = request();
+        req.strategy = Some("pcs".to_string());
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).expect("date");
+        assert!(build_scenarios(&[short_put()], &HashMap::new(), &req, today).expect("ok").is_empty());
+
+        req.strategy = None;
+        let scenarios = build_scenarios(&[short_put()], &HashMap::new(), &req, today).expect("ok");
+        assert!(scenarios[0].spot_is_estimate);
+        assert_eq!(scenarios[0].spot, 60.0);
+    }
+}
@@ -0,0 +1,42 @@
+// Sources partagées par les commandes portefeuille et le moniteur d'assignation
+
+use super::{from_tws_position, load_manual_positions, quote_symbols, RiskPosition};
+use crate::modules::error::AppError;
+use crate::modules::logging::TWS;
+use crate::modules::{db, market_data, tws_socket};
+use std::collections::HashMap;
+use tauri::{AppHandle, Runtime};
+
+/// Positions ouvertes : saisies manuelles + (optionnel) positions TWS
+/// TWS indisponible ou simulé : avertissement et positions manuelles seules
+pub async fn load_open_positions<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    include_tws: bool,
+) -> Result<Vec<RiskPosition>, AppError> {
+    let mut positions = {
+        let conn = db::open_connection(app_handle).map_err(AppError::Database)?;
+        load_manual_positions(&conn).map_err(AppError::Database)?
+    };
+    if !include_tws {
+        return Ok(positions);
+    }
+    if tws_socket::SIMULATED_POSITIONS {
+        tracing::warn!(target: TWS, "TWS positions are simulated, using manual positions only");
+        return Ok(positions);
+    }
+    let client = tws_socket::TWSSyncClient::new(tws_socket::TWSConfig::default());
+    match client.get_positions().await {
+        Ok(tws) => positions.extend(tws.iter().filter_map(from_tws_position)),
+        Err(e) => tracing::warn!(target: TWS, error = %e, "TWS positions unavailable, using manual positions only"),
+    }
+    Ok(positions)
+}
+
+/// Dernier cours Yahoo de chaque sous-jacent (+ benchmark)
+pub async fn fetch_spots<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    positions: &[RiskPosition],
+) -> Result<HashMap<String, f64>, AppError> {
+    let quotes = market_data::fetch_quotes(app_handle, quote_symbols(positions)).await?;
+    Ok(quotes.into_iter().map(|(symbol, q)| (symbol, q.price)).collect())
+}
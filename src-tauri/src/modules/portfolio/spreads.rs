@@ -0,0 +1,171 @@
+// Regroupement des jambes en spreads et capital à risque (perte max à l'échéance)
+
+use super::RiskPosition;
+use crate::modules::pricing::strategy::LegKind;
+use crate::modules::pricing::{analyze_strategy, ExerciseStyle, OptionKind, StrategyLeg, StrategyRequest};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Un groupe de jambes évalué ensemble (vertical, covered call, action seule...)
+#[derive(Debug, Clone, Serialize)]
+pub struct SpreadRisk {
+    pub account: String,
+    pub underlying: String,
+    pub expiry: Option<String>,
+    pub strategy: Option<String>,
+    pub position_ids: Vec<String>,
+    pub defined_risk: bool,
+    pub capital_at_risk: Option<f64>, // None : risque non borné (call vendu à découvert...)
+    pub max_profit: Option<f64>,
+}
+
+/// (compte, sous-jacent, échéance)
+pub type GroupKey = (String, String, Option<NaiveDate>);
+
+/// Groupe par (compte, sous-jacent, échéance) ; les actions rejoignent l'échéance la plus proche
+/// du même sous-jacent pour que covered calls et wheel assignée soient évalués d'un bloc
+pub fn group_positions(positions: &[RiskPosition]) -> BTreeMap<GroupKey, Vec<&RiskPosition>> {
+    let mut groups: BTreeMap<GroupKey, Vec<&RiskPosition>> = BTreeMap::new();
+    for p in positions.iter().filter(|p| p.kind.is_some()) {
+        groups.entry((p.account.clone(), p.underlying.clone(), p.expiry)).or_default().push(p);
+    }
+    for p in positions.iter().filter(|p| p.kind.is_none()) {
+        let nearest = groups
+            .keys()
+            .filter(|(a, u, _)| *a == p.account && *u == p.underlying)
+            .min_by_key(|(_, _, e)| *e)
+            .cloned();
+        let key = nearest.unwrap_or_else(|| (p.account.clone(), p.underlying.clone(), None));
+        groups.entry(key).or_default().push(p);
+    }
+    groups
+}
+
+/// Capital à risque de chaque groupe de jambes
+pub fn group_spreads(positions: &[RiskPosition], spots: &HashMap<String, f64>, today: NaiveDate, rate: f64) -> Vec<SpreadRisk> {
+    group_positions(positions)
+        .into_iter()
+        .map(|((account, underlying, expiry), legs)| {
+            let spot = spots.get(&underlying).copied();
+            let (max_profit, max_loss) = evaluate_group(&underlying, &legs, spot, today, rate);
+            SpreadRisk {
+                account,
+                underlying,
+                expiry: expiry.map(|d| d.format("%Y-%m-%d").to_string()),
+                strategy: common_strategy(&legs),
+                position_ids: legs.iter().map(|l| l.id.clone()).collect(),
+                defined_risk: max_loss.is_some(),
+                capital_at_risk: max_loss.map(|l| -l),
+                max_profit,
+            }
+        })
+        .collect()
+}
+
+fn evaluate_group(
+    underlying: &str,
+    legs: &[&RiskPosition],
+    spot: Option<f64>,
+    today: NaiveDate,
+    rate: f64,
+) -> (Option<f64>, Option<f64>) {
+    let request = StrategyRequest {
+        symbol: underlying.to_string(),
+        spot,
+        rate,
+        dividend_yield: 0.0,
+        style: ExerciseStyle::American,
+        volatility: None,
+        legs: legs.iter().map(|p| to_leg(p, today)).collect(),
+    };
+    // Sans cotation, le payoff à l'échéance reste calculable autour des strikes / prix d'entrée
+    let reference = spot
+        .or_else(|| legs.iter().map(|l| l.strike.max(l.entry_price)).reduce(f64::max))
+        .unwrap_or(1.0)
+        .max(0.01);
+    match analyze_strategy(&request, reference) {
+        Ok(a) => (a.max_profit, a.max_loss),
+        Err(_) => (None, None),
+    }
+}
+
+fn to_leg(p: &RiskPosition, today: NaiveDate) -> StrategyLeg {
+    StrategyLeg {
+        kind: match p.kind {
+            Some(OptionKind::Call) => LegKind::Call,
+            Some(OptionKind::Put) => LegKind::Put,
+            None => LegKind::Stock,
+        },
+        quantity: p.quantity,
+        strike: p.strike,
+        premium: p.entry_price,
+        days_to_expiry: p.days_to_expiry(today),
+        // Toutes les jambes d'un groupe expirent ensemble : la vol n'influe pas sur le payoff
+        volatility: Some(0.0),
+        multiplier: Some(p.multiplier),
+    }
+}
+
+pub fn common_strategy(legs: &[&RiskPosition]) -> Option<String> {
+    let first = legs.iter().find_map(|l| l.strategy.clone())?;
+    if legs.iter().all(|l| l.strategy.as_ref().is_none_or(|s| *s == first)) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(id: &str, kind: Option<OptionKind>, quantity: f64, strike: f64, price: f64) -> RiskPosition {
+        RiskPosition {
+            id: id.to_string(),
+            source: "manual".to_string(),
+            account: "Rocket Academy".to_string(),
+            strategy: Some("pcs".to_string()),
+            underlying: "SPY".to_string(),
+            kind,
+            strike,
+            expiry: if kind.is_some() { NaiveDate::from_ymd_opt(2025, 6, 20) } else { None },
+            quantity,
+            multiplier: if kind.is_some() { 100.0 } else { 1.0 },
+            entry_price: price,
+        }
+    }
+
+    #[test]
+    fn test_vertical_spread_capital_at_risk() {
+        let legs = vec![
+            position("short", Some(OptionKind::Put), -2.0, 500.0, 4.0),
+            position("long", Some(OptionKind::Put), 2.0, 495.0, 2.5),
+        ];
+        let spots = HashMap::from([("SPY".to_string(), 520.0)]);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).expect("date");
+        let spreads = group_spreads(&legs, &spots, today, 0.04);
+        assert_eq!(spreads.len(), 1);
+        // (5 - 1.5) × 100 × 2
+        assert!((spreads[0].capital_at_risk.unwrap_or_default() - 700.0).abs() < 1e-6);
+        assert_eq!(spreads[0].strategy.as_deref(), Some("pcs"));
+    }
+
+    #[test]
+    fn test_stock_joins_covered_call_group() {
+        let mut stock = position("stk", None, 100.0, 0.0, 50.0);
+        stock.strategy = Some("wheel".to_string());
+        let mut call = position("cc", Some(OptionKind::Call), -1.0, 55.0, 1.0);
+        call.strategy = Some("wheel".to_string());
+        let mut naked = position("naked", Some(OptionKind::Call), -1.0, 600.0, 1.0);
+        naked.underlying = "QQQ".to_string();
+
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).expect("date");
+        let spreads = group_spreads(&[stock, call, naked], &HashMap::new(), today, 0.04);
+        let cc = spreads.iter().find(|s| s.underlying == "SPY").expect("cc group");
+        assert_eq!(cc.position_ids.len(), 2);
+        assert!((cc.capital_at_risk.unwrap_or_default() - 4900.0).abs() < 1e-6);
+        let qqq = spreads.iter().find(|s| s.underlying == "QQQ").expect("naked group");
+        assert!(!qqq.defined_risk);
+    }
+}
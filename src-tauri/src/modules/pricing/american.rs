@@ -0,0 +1,106 @@
+// Approximation américaine Bjerksund-Stensland (1993) — actions à dividende continu
+
+use super::normal::cdf;
+use super::{black_scholes, OptionKind, OptionParams};
+
+/// Prix américain : exercice anticipé pris en compte via la frontière plate de Bjerksund-Stensland.
+/// Le put passe par la symétrie put-call P(S, K, r, b) = C(K, S, r - b, -b)
+pub fn price(kind: OptionKind, p: &OptionParams) -> f64 {
+    let european = black_scholes::price(kind, p);
+    if p.is_degenerate() {
+        return european.max(intrinsic(kind, p));
+    }
+    let american = match kind {
+        OptionKind::Call => call_price(p.spot, p.strike, p.time_years, p.rate, p.carry(), p.volatility),
+        OptionKind::Put => {
+            let b = p.carry();
+            call_price(p.strike, p.spot, p.time_years, p.rate - b, -b, p.volatility)
+        }
+    };
+    // L'approximation est une borne basse : jamais sous l'européen ni sous l'intrinsèque
+    american.max(european).max(intrinsic(kind, p))
+}
+
+fn intrinsic(kind: OptionKind, p: &OptionParams) -> f64 {
+    match kind {
+        OptionKind::Call => (p.spot - p.strike).max(0.0),
+        OptionKind::Put => (p.strike - p.spot).max(0.0),
+    }
+}
+
+/// Call américain avec coût de portage b (notation Haug)
+fn call_price(s: f64, k: f64, t: f64, r: f64, b: f64, v: f64) -> f64 {
+    // Sans dividende (b >= r) l'exercice anticipé d'un call n'est jamais optimal
+    if b >= r {
+        let q = r - b;
+        return black_scholes::price(
+            OptionKind::Call,
+            &OptionParams { spot: s, strike: k, time_years: t, rate: r, dividend_yield: q, volatility: v },
+        );
+    }
+
+    let v2 = v * v;
+    let beta = (0.5 - b / v2) + ((b / v2 - 0.5).powi(2) + 2.0 * r / v2).sqrt();
+    let b_inf = beta / (beta - 1.0) * k;
+    let b_zero = if r - b > 0.0 { k.max(r / (r - b) * k) } else { k };
+    let ht = -(b * t + 2.0 * v * t.sqrt()) * b_zero / (b_inf - b_zero);
+    let trigger = b_zero + (b_inf - b_zero) * (1.0 - ht.exp());
+
+    if s >= trigger {
+        return s - k;
+    }
+
+    let alpha = (trigger - k) * trigger.powf(-beta);
+    alpha * s.powf(beta) - alpha * phi(s, t, beta, trigger, trigger, r, b, v)
+        + phi(s, t, 1.0, trigger, trigger, r, b, v)
+        - phi(s, t, 1.0, k, trigger, r, b, v)
+        - k * phi(s, t, 0.0, trigger, trigger, r, b, v)
+        + k * phi(s, t, 0.0, k, trigger, r, b, v)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn phi(s: f64, t: f64, gamma: f64, h: f64, i: f64, r: f64, b: f64, v: f64) -> f64 {
+    let v2 = v * v;
+    let vol_sqrt_t = v * t.sqrt();
+    let lambda = (-r + gamma * b + 0.5 * gamma * (gamma - 1.0) * v2) * t;
+    let d = -((s / h).ln() + (b + (gamma - 0.5) * v2) * t) / vol_sqrt_t;
+    let kappa = 2.0 * b / v2 + (2.0 * gamma - 1.0);
+    lambda.exp() * s.powf(gamma)
+        * (cdf(d) - (i / s).powf(kappa) * cdf(d - 2.0 * (i / s).ln() / vol_sqrt_t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(spot: f64, strike: f64, t: f64, r: f64, q: f64, vol: f64) -> OptionParams {
+        OptionParams { spot, strike, time_years: t, rate: r, dividend_yield: q, volatility: vol }
+    }
+
+    #[test]
+    fn test_haug_reference_call() {
+        // Haug, table BS1993 : S=90, K=100, T=0.1, r=10%, b=0 (q=10%), σ=15% → 0.0205
+        let p = params(90.0, 100.0, 0.1, 0.10, 0.10, 0.15);
+        assert!((price(OptionKind::Call, &p) - 0.0205).abs() < 1e-3);
+        // S=110 → 10.0000 (exercice immédiat)
+        let p = params(110.0, 100.0, 0.1, 0.10, 0.10, 0.15);
+        assert!((price(OptionKind::Call, &p) - 10.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_call_without_dividend_equals_european() {
+        let p = params(100.0, 100.0, 0.5, 0.05, 0.0, 0.3);
+        let eu = black_scholes::price(OptionKind::Call, &p);
+        assert!((price(OptionKind::Call, &p) - eu).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_american_put_premium() {
+        // Put profondément ITM avec taux positifs : prime d'exercice anticipé > 0
+        let p = params(80.0, 100.0, 1.0, 0.08, 0.0, 0.25);
+        let eu = black_scholes::price(OptionKind::Put, &p);
+        let am = price(OptionKind::Put, &p);
+        assert!(am > eu);
+        assert!(am >= 20.0);
+    }
+}
@@ -0,0 +1,125 @@
+// Black-Scholes-Merton généralisé (coût de portage b = r - q) : prix européen + greeks analytiques
+
+use super::normal::{cdf, pdf};
+use super::{Greeks, OptionKind, OptionParams, DAYS_PER_YEAR};
+
+/// Prix européen ; à l'échéance ou sans volatilité on retombe sur la valeur intrinsèque actualisée
+pub fn price(kind: OptionKind, p: &OptionParams) -> f64 {
+    if p.is_degenerate() {
+        return degenerate_price(kind, p);
+    }
+    let (d1, d2) = d1_d2(p);
+    let carry_df = ((p.carry() - p.rate) * p.time_years).exp();
+    let df = (-p.rate * p.time_years).exp();
+    match kind {
+        OptionKind::Call => p.spot * carry_df * cdf(d1) - p.strike * df * cdf(d2),
+        OptionKind::Put => p.strike * df * cdf(-d2) - p.spot * carry_df * cdf(-d1),
+    }
+}
+
+/// Greeks analytiques — theta par jour calendaire, vega et rho pour 1 point (1%)
+pub fn greeks(kind: OptionKind, p: &OptionParams) -> Greeks {
+    if p.is_degenerate() {
+        return degenerate_greeks(kind, p);
+    }
+    let (d1, d2) = d1_d2(p);
+    let sqrt_t = p.time_years.sqrt();
+    let b = p.carry();
+    let carry_df = ((b - p.rate) * p.time_years).exp();
+    let df = (-p.rate * p.time_years).exp();
+
+    let gamma = carry_df * pdf(d1) / (p.spot * p.volatility * sqrt_t);
+    let vega = p.spot * carry_df * pdf(d1) * sqrt_t;
+    let decay = -p.spot * carry_df * pdf(d1) * p.volatility / (2.0 * sqrt_t);
+
+    let (delta, theta, rho) = match kind {
+        OptionKind::Call => (
+            carry_df * cdf(d1),
+            decay - (b - p.rate) * p.spot * carry_df * cdf(d1) - p.rate * p.strike * df * cdf(d2),
+            p.strike * p.time_years * df * cdf(d2),
+        ),
+        OptionKind::Put => (
+            carry_df * (cdf(d1) - 1.0),
+            decay + (b - p.rate) * p.spot * carry_df * cdf(-d1) + p.rate * p.strike * df * cdf(-d2),
+            -p.strike * p.time_years * df * cdf(-d2),
+        ),
+    };
+
+    Greeks {
+        delta,
+        gamma,
+        theta: theta / DAYS_PER_YEAR,
+        vega: vega / 100.0,
+        rho: rho / 100.0,
+    }
+}
+
+fn d1_d2(p: &OptionParams) -> (f64, f64) {
+    let vol_sqrt_t = p.volatility * p.time_years.sqrt();
+    let d1 = ((p.spot / p.strike).ln() + (p.carry() + 0.5 * p.volatility * p.volatility) * p.time_years)
+        / vol_sqrt_t;
+    (d1, d1 - vol_sqrt_t)
+}
+
+fn degenerate_price(kind: OptionKind, p: &OptionParams) -> f64 {
+    let t = p.time_years.max(0.0);
+    let forward_spot = p.spot * ((p.carry() - p.rate) * t).exp();
+    let pv_strike = p.strike * (-p.rate * t).exp();
+    match kind {
+        OptionKind::Call => (forward_spot - pv_strike).max(0.0),
+        OptionKind::Put => (pv_strike - forward_spot).max(0.0),
+    }
+}
+
+fn degenerate_greeks(kind: OptionKind, p: &OptionParams) -> Greeks {
+    let delta = match kind {
+        OptionKind::Call if p.spot > p.strike => 1.0,
+        OptionKind::Put if p.spot < p.strike => -1.0,
+        _ => 0.0,
+    };
+    Greeks { delta, ..Greeks::default() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(spot: f64, strike: f64, t: f64, r: f64, q: f64, vol: f64) -> OptionParams {
+        OptionParams { spot, strike, time_years: t, rate: r, dividend_yield: q, volatility: vol }
+    }
+
+    #[test]
+    fn test_reference_price() {
+        // Hull : S=42, K=40, T=0.5, r=10%, σ=20% → call 4.76, put 0.81
+        let p = params(42.0, 40.0, 0.5, 0.10, 0.0, 0.20);
+        assert!((price(OptionKind::Call, &p) - 4.7594).abs() < 1e-3);
+        assert!((price(OptionKind::Put, &p) - 0.8086).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_put_call_parity_with_dividends() {
+        let p = params(100.0, 95.0, 0.75, 0.05, 0.02, 0.3);
+        let lhs = price(OptionKind::Call, &p) - price(OptionKind::Put, &p);
+        let rhs = 100.0 * (-0.02_f64 * 0.75).exp() - 95.0 * (-0.05_f64 * 0.75).exp();
+        assert!((lhs - rhs).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_greeks_signs() {
+        let p = params(100.0, 100.0, 0.25, 0.04, 0.0, 0.25);
+        let call = greeks(OptionKind::Call, &p);
+        let put = greeks(OptionKind::Put, &p);
+        assert!(call.delta > 0.5 && call.delta < 0.6);
+        assert!((call.delta - put.delta - 1.0).abs() < 1e-9);
+        assert!((call.gamma - put.gamma).abs() < 1e-12);
+        assert!(call.theta < 0.0 && call.vega > 0.0 && call.rho > 0.0 && put.rho < 0.0);
+    }
+
+    #[test]
+    fn test_expired_option_is_intrinsic() {
+        let p = params(90.0, 100.0, 0.0, 0.04, 0.0, 0.25);
+        assert_eq!(price(OptionKind::Put, &p), 10.0);
+        assert_eq!(price(OptionKind::Call, &p), 0.0);
+        assert_eq!(greeks(OptionKind::Put, &p).delta, -1.0);
+    }
+}
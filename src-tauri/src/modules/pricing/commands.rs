@@ -0,0 +1,40 @@
+// Commandes Tauri du pricer d'options et de l'analyse de stratégies
+
+use super::{ExerciseStyle, OptionKind, OptionParams, OptionQuote, StrategyAnalysis, StrategyRequest};
+use crate::modules::error::AppError;
+use crate::modules::market_data;
+
+/// Commande Tauri: Prix théorique + greeks d'une option (américaine par défaut)
+#[tauri::command]
+pub fn price_option(kind: OptionKind, style: Option<ExerciseStyle>, params: OptionParams) -> OptionQuote {
+    super::quote(kind, style.unwrap_or_default(), &params)
+}
+
+/// Commande Tauri: Volatilité implicite à partir de la prime observée
+#[tauri::command]
+pub fn implied_volatility(
+    kind: OptionKind,
+    style: Option<ExerciseStyle>,
+    params: OptionParams,
+    market_price: f64,
+) -> Result<f64, AppError> {
+    super::implied_volatility(kind, style.unwrap_or_default(), &params, market_price).map_err(AppError::Validation)
+}
+
+/// Commande Tauri: PoP, breakevens et greeks d'une stratégie (PCS, Wheel, Covered Call)
+/// Sans spot fourni, on reprend le dernier cours des fournisseurs de cotations (market_data)
+#[tauri::command]
+pub async fn analyze_option_strategy(
+    app_handle: tauri::AppHandle,
+    request: StrategyRequest,
+) -> Result<StrategyAnalysis, AppError> {
+    let spot = match request.spot {
+        Some(spot) => spot,
+        None => market_data::fetch_quotes(&app_handle, vec![request.symbol.clone()])
+            .await?
+            .get(&request.symbol)
+            .map(|q| q.price)
+            .ok_or_else(|| AppError::Validation(format!("No market quote for {}", request.symbol)))?,
+    };
+    super::analyze_strategy(&request, spot).map_err(AppError::Validation)
+}
@@ -0,0 +1,76 @@
+// Solveur de volatilité implicite (Newton protégé par bissection)
+
+use super::{black_scholes, price, ExerciseStyle, OptionKind, OptionParams};
+
+const VOL_MIN: f64 = 1e-4;
+const VOL_MAX: f64 = 5.0;
+const PRICE_TOLERANCE: f64 = 1e-8;
+const MAX_ITERATIONS: u32 = 100;
+
+/// Retrouve la volatilité qui reproduit la prime observée (le champ volatility de `p` est ignoré).
+/// Newton utilise le vega Black-Scholes, la bissection garantit la convergence pour l'américain
+pub fn solve(kind: OptionKind, style: ExerciseStyle, p: &OptionParams, market_price: f64) -> Result<f64, String> {
+    if !(market_price.is_finite() && market_price > 0.0) {
+        return Err(format!("Invalid option price: {}", market_price));
+    }
+    if p.time_years <= 0.0 {
+        return Err("Option expired: implied volatility undefined".to_string());
+    }
+
+    let at = |vol: f64| price(kind, style, &OptionParams { volatility: vol, ..*p });
+    let (mut lo, mut hi) = (VOL_MIN, VOL_MAX);
+    let (price_lo, price_hi) = (at(lo), at(hi));
+    if market_price < price_lo - PRICE_TOLERANCE {
+        return Err(format!("Price {:.4} below model lower bound {:.4}", market_price, price_lo));
+    }
+    if market_price > price_hi + PRICE_TOLERANCE {
+        return Err(format!("Price {:.4} above model upper bound {:.4}", market_price, price_hi));
+    }
+
+    let mut vol = 0.3;
+    for _ in 0..MAX_ITERATIONS {
+        let diff = at(vol) - market_price;
+        if diff.abs() < PRICE_TOLERANCE {
+            return Ok(vol);
+        }
+        // Le prix est croissant en vol : on resserre l'intervalle à chaque itération
+        if diff > 0.0 { hi = vol } else { lo = vol }
+
+        // vega BS non mis à l'échelle (par unité de vol)
+        let vega = black_scholes::greeks(kind, &OptionParams { volatility: vol, ..*p }).vega * 100.0;
+        let newton = if vega > 1e-10 { vol - diff / vega } else { f64::NAN };
+        vol = if newton.is_finite() && newton > lo && newton < hi { newton } else { 0.5 * (lo + hi) };
+
+        if hi - lo < 1e-10 {
+            return Ok(vol);
+        }
+    }
+    Ok(vol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(vol: f64) -> OptionParams {
+        OptionParams { spot: 100.0, strike: 105.0, time_years: 0.4, rate: 0.04, dividend_yield: 0.01, volatility: vol }
+    }
+
+    #[test]
+    fn test_round_trip_european_and_american() {
+        for style in [ExerciseStyle::European, ExerciseStyle::American] {
+            for kind in [OptionKind::Call, OptionKind::Put] {
+                let target = price(kind, style, &params(0.37));
+                let iv = solve(kind, style, &params(0.0), target).expect("iv");
+                assert!((iv - 0.37).abs() < 1e-5, "{:?} {:?} → {}", style, kind, iv);
+            }
+        }
+    }
+
+    #[test]
+    fn test_price_below_intrinsic_rejected() {
+        let p = OptionParams { spot: 80.0, strike: 100.0, ..params(0.0) };
+        assert!(solve(OptionKind::Put, ExerciseStyle::American, &p, 5.0).is_err());
+        assert!(solve(OptionKind::Put, ExerciseStyle::American, &p, 0.0).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+// Pricing d'options : Black-Scholes européen, Bjerksund-Stensland américain, greeks, vol implicite
+// Utilisé par les formulaires PCS / Wheel / Covered Call (PoP, breakevens, greeks de position)
+
+mod american;
+mod black_scholes;
+pub mod commands;
+mod implied_vol;
+mod normal;
+pub mod strategy;
+
+pub use implied_vol::solve as implied_volatility;
+pub use strategy::{analyze_strategy, StrategyAnalysis, StrategyLeg, StrategyRequest};
+
+use serde::{Deserialize, Serialize};
+
+/// Convention de theta : décroissance par jour calendaire
+pub const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Taux sans risque par défaut si le formulaire n'en fournit pas
+pub const DEFAULT_RISK_FREE_RATE: f64 = 0.04;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+/// Les options sur actions US sont américaines : c'est le défaut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExerciseStyle {
+    European,
+    #[default]
+    American,
+}
+
+/// Paramètres de marché d'une option (taux et dividende en continu, annualisés)
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct OptionParams {
+    pub spot: f64,
+    pub strike: f64,
+    pub time_years: f64,
+    #[serde(default = "default_rate")]
+    pub rate: f64,
+    #[serde(default)]
+    pub dividend_yield: f64,
+    /// Ignorée par le solveur de vol implicite
+    #[serde(default)]
+    pub volatility: f64,
+}
+
+fn default_rate() -> f64 {
+    DEFAULT_RISK_FREE_RATE
+}
+
+impl OptionParams {
+    /// Coût de portage b = r - q
+    pub fn carry(&self) -> f64 {
+        self.rate - self.dividend_yield
+    }
+
+    /// Échéance atteinte, vol nulle ou prix invalides : pas de valeur temps calculable
+    fn is_degenerate(&self) -> bool {
+        self.time_years <= 0.0 || self.volatility <= 0.0 || self.spot <= 0.0 || self.strike <= 0.0
+    }
+}
+
+/// Sensibilités par contrat unitaire (delta en sous-jacent, theta/jour, vega et rho pour 1%)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+    pub rho: f64,
+}
+
+impl Greeks {
+    /// Greeks d'une position : quantité signée × multiplicateur
+    pub fn scaled(&self, factor: f64) -> Greeks {
+        Greeks {
+            delta: self.delta * factor,
+            gamma: self.gamma * factor,
+            theta: self.theta * factor,
+            vega: self.vega * factor,
+            rho: self.rho * factor,
+        }
+    }
+
+    pub fn add(&mut self, other: &Greeks) {
+        self.delta += other.delta;
+        self.gamma += other.gamma;
+        self.theta += other.theta;
+        self.vega += other.vega;
+        self.rho += other.rho;
+    }
+}
+
+/// Prix + greeks renvoyés au frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct OptionQuote {
+    pub price: f64,
+    pub greeks: Greeks,
+    pub style: ExerciseStyle,
+}
+
+/// Prix théorique selon le style d'exercice
+pub fn price(kind: OptionKind, style: ExerciseStyle, p: &OptionParams) -> f64 {
+    match style {
+        ExerciseStyle::European => black_scholes::price(kind, p),
+        ExerciseStyle::American => american::price(kind, p),
+    }
+}
+
+/// Greeks analytiques pour l'européen, différences finies sur l'approximation américaine
+pub fn greeks(kind: OptionKind, style: ExerciseStyle, p: &OptionParams) -> Greeks {
+    match style {
+        ExerciseStyle::European => black_scholes::greeks(kind, p),
+        ExerciseStyle::American if p.is_degenerate() => black_scholes::greeks(kind, p),
+        ExerciseStyle::American => numerical_greeks(|q| american::price(kind, q), p),
+    }
+}
+
+/// Prix + greeks en un appel
+pub fn quote(kind: OptionKind, style: ExerciseStyle, p: &OptionParams) -> OptionQuote {
+    OptionQuote { price: price(kind, style, p), greeks: greeks(kind, style, p), style }
+}
+
+fn numerical_greeks(f: impl Fn(&OptionParams) -> f64, p: &OptionParams) -> Greeks {
+    let ds = p.spot * 0.01;
+    let up = f(&OptionParams { spot: p.spot + ds, ..*p });
+    let mid = f(p);
+    let down = f(&OptionParams { spot: p.spot - ds, ..*p });
+
+    let dt = (1.0 / DAYS_PER_YEAR).min(p.time_years);
+    let later = f(&OptionParams { time_years: p.time_years - dt, ..*p });
+    let vol_up = f(&OptionParams { volatility: p.volatility + 0.01, ..*p });
+    let vol_down = f(&OptionParams { volatility: (p.volatility - 0.01).max(1e-4), ..*p });
+    let rate_up = f(&OptionParams { rate: p.rate + 0.01, ..*p });
+    let rate_down = f(&OptionParams { rate: p.rate - 0.01, ..*p });
+
+    Greeks {
+        delta: (up - down) / (2.0 * ds),
+        gamma: (up - 2.0 * mid + down) / (ds * ds),
+        // Variation sur un jour, ramenée à un jour si l'échéance est plus proche
+        theta: (later - mid) / (dt * DAYS_PER_YEAR),
+        vega: (vol_up - vol_down) / 2.0,
+        rho: (rate_up - rate_down) / 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numerical_greeks_match_analytic_for_european() {
+        let p = OptionParams { spot: 100.0, strike: 95.0, time_years: 0.5, rate: 0.03, dividend_yield: 0.01, volatility: 0.25 };
+        let analytic = black_scholes::greeks(OptionKind::Put, &p);
+        let numeric = numerical_greeks(|q| black_scholes::price(OptionKind::Put, q), &p);
+        assert!((analytic.delta - numeric.delta).abs() < 1e-3);
+        assert!((analytic.gamma - numeric.gamma).abs() < 1e-3);
+        assert!((analytic.theta - numeric.theta).abs() < 1e-3);
+        assert!((analytic.vega - numeric.vega).abs() < 1e-3);
+        assert!((analytic.rho - numeric.rho).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_params_default_rate_from_json() {
+        let p: OptionParams =
+            serde_json::from_str(r#"{"spot":100,"strike":90,"time_years":0.1,"volatility":0.2}"#).expect("json");
+        assert_eq!(p.rate, DEFAULT_RISK_FREE_RATE);
+        assert_eq!(p.dividend_yield, 0.0);
+    }
+}
@@ -0,0 +1,47 @@
+// Loi normale centrée réduite (densité + fonction de répartition)
+
+use std::f64::consts::{PI, SQRT_2};
+
+/// Densité n(x)
+pub fn pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+}
+
+/// Fonction de répartition N(x), précision ~1e-7 (suffisante pour des primes au centime)
+pub fn cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / SQRT_2)
+}
+
+/// Fonction d'erreur complémentaire (approximation de Tchebychev, Numerical Recipes)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let ans = t * poly.exp();
+    if x >= 0.0 { ans } else { 2.0 - ans }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cdf_reference_values() {
+        assert!((cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((cdf(1.96) - 0.975_002_1).abs() < 1e-6);
+        assert!((cdf(-1.0) - 0.158_655_25).abs() < 1e-6);
+        assert!((cdf(1.0) + cdf(-1.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pdf_peak() {
+        assert!((pdf(0.0) - 0.398_942_28).abs() < 1e-8);
+    }
+}
@@ -0,0 +1,291 @@
+// Analyse d'une stratégie multi-jambes (PCS, Wheel, Covered Call) : breakevens, PoP, greeks de position
+
+mod payoff;
+
+use super::{greeks, implied_volatility, price, ExerciseStyle, Greeks, OptionKind, OptionParams, DAYS_PER_YEAR};
+use payoff::{extremes, find_breakevens, probability_of_profit};
+use serde::{Deserialize, Serialize};
+
+const OPTION_MULTIPLIER: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LegKind {
+    Call,
+    Put,
+    Stock,
+}
+
+/// Jambe saisie dans le formulaire — quantité signée (> 0 achat, < 0 vente)
+#[derive(Debug, Clone, Deserialize)]
+pub struct StrategyLeg {
+    pub kind: LegKind,
+    pub quantity: f64,
+    #[serde(default)]
+    pub strike: f64,
+    /// Prime par action (options) ou prix de revient (action)
+    pub premium: f64,
+    #[serde(default)]
+    pub days_to_expiry: f64,
+    /// Absente : vol implicite déduite de la prime
+    #[serde(default)]
+    pub volatility: Option<f64>,
+    /// Absent : 100 pour une option, 1 pour une action
+    #[serde(default)]
+    pub multiplier: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StrategyRequest {
+    pub symbol: String,
+    /// Absent : spot récupéré via fetch_market_quotes
+    #[serde(default)]
+    pub spot: Option<f64>,
+    #[serde(default = "super::default_rate")]
+    pub rate: f64,
+    #[serde(default)]
+    pub dividend_yield: f64,
+    #[serde(default)]
+    pub style: ExerciseStyle,
+    /// Vol utilisée pour la PoP ; absente : moyenne des jambes pondérée par |quantité|
+    #[serde(default)]
+    pub volatility: Option<f64>,
+    pub legs: Vec<StrategyLeg>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LegAnalysis {
+    pub theoretical_price: f64,
+    pub volatility: Option<f64>,
+    pub greeks: Greeks, // position (quantité × multiplicateur)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyAnalysis {
+    pub symbol: String,
+    pub spot: f64,
+    pub horizon_days: f64, // première échéance de la stratégie
+    pub net_credit: f64,   // > 0 : crédit encaissé
+    pub breakevens: Vec<f64>,
+    pub probability_of_profit: f64,
+    pub max_profit: Option<f64>, // None : gain illimité
+    pub max_loss: Option<f64>,   // valeur ≤ 0, None : perte illimitée
+    pub greeks: Greeks,
+    pub legs: Vec<LegAnalysis>,
+}
+
+/// Jambe résolue (vol et multiplicateur connus)
+struct Resolved<'a> {
+    leg: &'a StrategyLeg,
+    volatility: f64,
+    multiplier: f64,
+}
+
+/// Analyse la stratégie au spot donné : P&L à la première échéance, probabilité de profit
+/// (loi log-normale risque-neutre) et greeks agrégés de la position
+pub fn analyze_strategy(request: &StrategyRequest, spot: f64) -> Result<StrategyAnalysis, String> {
+    if request.legs.is_empty() {
+        return Err("Strategy has no legs".to_string());
+    }
+    if !(spot.is_finite() && spot > 0.0) {
+        return Err(format!("Invalid spot price: {}", spot));
+    }
+
+    let resolved = request
+        .legs
+        .iter()
+        .map(|leg| resolve_leg(request, leg, spot))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let horizon_days = request
+        .legs
+        .iter()
+        .filter(|l| l.kind != LegKind::Stock)
+        .map(|l| l.days_to_expiry)
+        .fold(f64::INFINITY, f64::min);
+    let horizon_days = if horizon_days.is_finite() { horizon_days.max(0.0) } else { 0.0 };
+
+    let pnl = |s: f64| resolved.iter().map(|r| leg_pnl_at_horizon(request, r, s, horizon_days)).sum::<f64>();
+
+    let mut total_greeks = Greeks::default();
+    let mut legs = Vec::with_capacity(resolved.len());
+    for r in &resolved {
+        let analysis = analyze_leg(request, r, spot);
+        total_greeks.add(&analysis.greeks);
+        legs.push(analysis);
+    }
+
+    let upper = request.legs.iter().map(|l| l.strike).fold(spot, f64::max) * 3.0;
+    let breakevens = find_breakevens(&pnl, upper);
+    let (max_profit, max_loss) = extremes(&pnl, &request.legs, upper);
+
+    let pop_vol = request.volatility.unwrap_or_else(|| weighted_volatility(&resolved));
+    let probability_of_profit = probability_of_profit(
+        &pnl,
+        &breakevens,
+        spot,
+        request.rate - request.dividend_yield,
+        pop_vol,
+        horizon_days / DAYS_PER_YEAR,
+    );
+
+    Ok(StrategyAnalysis {
+        symbol: request.symbol.clone(),
+        spot,
+        horizon_days,
+        net_credit: resolved
+            .iter()
+            .filter(|r| r.leg.kind != LegKind::Stock)
+            .map(|r| -r.leg.quantity * r.multiplier * r.leg.premium)
+            .sum(),
+        breakevens,
+        probability_of_profit,
+        max_profit,
+        max_loss,
+        greeks: total_greeks,
+        legs,
+    })
+}
+
+fn option_kind(kind: LegKind) -> Option<OptionKind> {
+    match kind {
+        LegKind::Call => Some(OptionKind::Call),
+        LegKind::Put => Some(OptionKind::Put),
+        LegKind::Stock => None,
+    }
+}
+
+fn params(request: &StrategyRequest, leg: &StrategyLeg, spot: f64, days: f64, vol: f64) -> OptionParams {
+    OptionParams {
+        spot,
+        strike: leg.strike,
+        time_years: days.max(0.0) / DAYS_PER_YEAR,
+        rate: request.rate,
+        dividend_yield: request.dividend_yield,
+        volatility: vol,
+    }
+}
+
+fn resolve_leg<'a>(request: &StrategyRequest, leg: &'a StrategyLeg, spot: f64) -> Result<Resolved<'a>, String> {
+    let Some(kind) = option_kind(leg.kind) else {
+        return Ok(Resolved { leg, volatility: 0.0, multiplier: leg.multiplier.unwrap_or(1.0) });
+    };
+    if leg.strike <= 0.0 {
+        return Err(format!("Invalid strike for {:?} leg: {}", leg.kind, leg.strike));
+    }
+    let volatility = match leg.volatility {
+        Some(v) => v,
+        None if leg.days_to_expiry > 0.0 => {
+            let p = params(request, leg, spot, leg.days_to_expiry, 0.0);
+            implied_volatility(kind, request.style, &p, leg.premium)
+                .map_err(|e| format!("Leg K={} {:?}: {}", leg.strike, leg.kind, e))?
+        }
+        None => 0.0,
+    };
+    Ok(Resolved { leg, volatility, multiplier: leg.multiplier.unwrap_or(OPTION_MULTIPLIER) })
+}
+
+fn analyze_leg(request: &StrategyRequest, r: &Resolved, spot: f64) -> LegAnalysis {
+    let size = r.leg.quantity * r.multiplier;
+    match option_kind(r.leg.kind) {
+        None => LegAnalysis {
+            theoretical_price: spot,
+            volatility: None,
+            greeks: Greeks { delta: size, ..Greeks::default() },
+        },
+        Some(kind) => {
+            let p = params(request, r.leg, spot, r.leg.days_to_expiry, r.volatility);
+            LegAnalysis {
+                theoretical_price: price(kind, request.style, &p),
+                volatility: Some(r.volatility),
+                greeks: greeks(kind, request.style, &p).scaled(size),
+            }
+        }
+    }
+}
+
+/// P&L d'une jambe quand le sous-jacent vaut `s` à l'horizon ; les jambes plus lointaines
+/// (calendars) gardent leur valeur temps résiduelle
+fn leg_pnl_at_horizon(request: &StrategyRequest, r: &Resolved, s: f64, horizon_days: f64) -> f64 {
+    let size = r.leg.quantity * r.multiplier;
+    let value = match option_kind(r.leg.kind) {
+        None => s,
+        Some(kind) => {
+            let remaining = r.leg.days_to_expiry - horizon_days;
+            price(kind, request.style, &params(request, r.leg, s, remaining, r.volatility))
+        }
+    };
+    size * (value - r.leg.premium)
+}
+
+fn weighted_volatility(resolved: &[Resolved]) -> f64 {
+    let (sum, weight) = resolved
+        .iter()
+        .filter(|r| r.leg.kind != LegKind::Stock && r.volatility > 0.0)
+        .fold((0.0, 0.0), |(s, w), r| (s + r.volatility * r.leg.quantity.abs(), w + r.leg.quantity.abs()));
+    if weight > 0.0 { sum / weight } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(kind: LegKind, quantity: f64, strike: f64, premium: f64) -> StrategyLeg {
+        StrategyLeg { kind, quantity, strike, premium, days_to_expiry: 30.0, volatility: Some(0.3), multiplier: None }
+    }
+
+    fn request(legs: Vec<StrategyLeg>) -> StrategyRequest {
+        StrategyRequest {
+            symbol: "SPY".to_string(),
+            spot: None,
+            rate: 0.04,
+            dividend_yield: 0.0,
+            style: ExerciseStyle::American,
+            volatility: None,
+            legs,
+        }
+    }
+
+    #[test]
+    fn test_put_credit_spread() {
+        // PCS 95/90 pour 1.50 de crédit
+        let req = request(vec![leg(LegKind::Put, -1.0, 95.0, 2.5), leg(LegKind::Put, 1.0, 90.0, 1.0)]);
+        let a = analyze_strategy(&req, 100.0).expect("analysis");
+        assert!((a.net_credit - 150.0).abs() < 1e-9);
+        assert_eq!(a.breakevens, vec![93.5]);
+        assert!((a.max_profit.unwrap_or_default() - 150.0).abs() < 1e-6);
+        assert!((a.max_loss.unwrap_or_default() + 350.0).abs() < 1e-6);
+        assert!(a.probability_of_profit > 0.6 && a.probability_of_profit < 1.0);
+        // Spread vendeur de put : delta positif
+        assert!(a.greeks.delta > 0.0);
+    }
+
+    #[test]
+    fn test_covered_call() {
+        let mut stock = leg(LegKind::Stock, 100.0, 0.0, 100.0);
+        stock.volatility = None;
+        let req = request(vec![stock, leg(LegKind::Call, -1.0, 105.0, 2.0)]);
+        let a = analyze_strategy(&req, 100.0).expect("analysis");
+        assert_eq!(a.breakevens, vec![98.0]);
+        assert!((a.max_profit.unwrap_or_default() - 700.0).abs() < 1e-6);
+        assert!(a.greeks.delta < 100.0 && a.greeks.delta > 0.0);
+    }
+
+    #[test]
+    fn test_naked_call_loss_unbounded() {
+        let req = request(vec![leg(LegKind::Call, -1.0, 110.0, 1.2)]);
+        let a = analyze_strategy(&req, 100.0).expect("analysis");
+        assert!(a.max_loss.is_none());
+        assert_eq!(a.breakevens, vec![111.2]);
+    }
+
+    #[test]
+    fn test_volatility_implied_from_premium() {
+        let mut short_put = leg(LegKind::Put, -1.0, 95.0, 1.8);
+        short_put.volatility = None;
+        let a = analyze_strategy(&request(vec![short_put]), 100.0).expect("analysis");
+        let iv = a.legs[0].volatility.unwrap_or_default();
+        assert!(iv > 0.05 && iv < 1.0);
+        assert!((a.legs[0].theoretical_price - 1.8).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,96 @@
+// Calculs sur la courbe de P&L à l'échéance : breakevens (grille + bissection), gain / perte
+// extrêmes et probabilité de profit sous la loi log-normale
+
+use crate::modules::pricing::normal::cdf;
+use super::StrategyLeg;
+
+const GRID_STEPS: usize = 600;
+const BISECTION_STEPS: u32 = 60;
+
+/// Zéros du P&L sur ]0, upper], puis au-delà par extrapolation linéaire (arrondis au cent)
+pub(super) fn find_breakevens(pnl: &impl Fn(f64) -> f64, upper: f64) -> Vec<f64> {
+    let step = upper / GRID_STEPS as f64;
+    let mut roots = Vec::new();
+    let mut prev_s = 0.0;
+    let mut prev = pnl(prev_s);
+    for i in 1..=GRID_STEPS {
+        let s = step * i as f64;
+        let v = pnl(s);
+        if prev == 0.0 && i > 1 {
+            roots.push(prev_s);
+        } else if prev * v < 0.0 {
+            roots.push(bisect(pnl, prev_s, s));
+        }
+        prev_s = s;
+        prev = v;
+    }
+    // Au-delà de la grille le P&L est linéaire : extrapolation de la dernière pente
+    let slope = (pnl(upper * 2.0) - prev) / upper;
+    if slope.abs() > 1e-9 && prev * slope < 0.0 {
+        roots.push(upper - prev / slope);
+    }
+    roots.iter().map(|r| (r * 100.0).round() / 100.0).collect()
+}
+
+fn bisect(f: &impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    let f_lo = f(lo);
+    for _ in 0..BISECTION_STEPS {
+        let mid = 0.5 * (lo + hi);
+        if f(mid) * f_lo > 0.0 { lo = mid } else { hi = mid }
+    }
+    0.5 * (lo + hi)
+}
+
+/// (gain max, perte max) sur la grille et aux strikes ; None quand la pente au-delà de la grille
+/// rend le gain ou la perte illimités
+pub(super) fn extremes(
+    pnl: &impl Fn(f64) -> f64,
+    legs: &[StrategyLeg],
+    upper: f64,
+) -> (Option<f64>, Option<f64>) {
+    let mut points: Vec<f64> = (0..=GRID_STEPS).map(|i| upper * i as f64 / GRID_STEPS as f64).collect();
+    points.extend(legs.iter().map(|l| l.strike).filter(|k| *k > 0.0));
+    let values: Vec<f64> = points.iter().map(|s| pnl(*s)).collect();
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+
+    let tail_slope = (pnl(upper * 2.0) - pnl(upper)) / upper;
+    let max_profit = if tail_slope > 1e-6 { None } else { Some(max) };
+    let max_loss = if tail_slope < -1e-6 { None } else { Some(min.min(0.0)) };
+    (max_profit, max_loss)
+}
+
+/// P(S_T ∈ zones gagnantes) sous la loi log-normale de drift b = r - q
+pub(super) fn probability_of_profit(
+    pnl: &impl Fn(f64) -> f64,
+    breakevens: &[f64],
+    spot: f64,
+    carry: f64,
+    vol: f64,
+    t: f64,
+) -> f64 {
+    if t <= 0.0 || vol <= 0.0 {
+        return if pnl(spot) > 0.0 { 1.0 } else { 0.0 };
+    }
+    let below = |x: f64| {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        cdf(((x / spot).ln() - (carry - 0.5 * vol * vol) * t) / (vol * t.sqrt()))
+    };
+
+    let mut bounds = vec![0.0];
+    bounds.extend_from_slice(breakevens);
+    let mut probability = 0.0;
+    for (i, lo) in bounds.iter().enumerate() {
+        let hi = bounds.get(i + 1).copied();
+        let probe = match hi {
+            Some(h) => 0.5 * (lo + h),
+            None => lo.max(spot) * 2.0 + 1.0,
+        };
+        if pnl(probe) > 0.0 {
+            probability += hi.map_or(1.0, below) - below(*lo);
+        }
+    }
+    probability.clamp(0.0, 1.0)
+}
@@ -0,0 +1,13 @@
+// Commande Tauri de la réconciliation journal / Flex / TWS
+
+use super::{ReconcileRequest, ReconciliationReport};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Réconciliation journal / Flex / TWS — écarts et correction suggérée par contrat
+#[tauri::command]
+pub async fn reconcile_positions(
+    app_handle: tauri::AppHandle,
+    request: ReconcileRequest,
+) -> Result<ReconciliationReport, AppError> {
+    super::reconcile_positions(&app_handle, &request).await
+}
@@ -0,0 +1,244 @@
+// Comparaison contrat par contrat : journal vs courtier (TWS si disponible, sinon Flex),
+// puis Flex vs TWS ; chaque écart porte la correction suggérée
+
+use super::{ContractKey, FixAction, Holding, Mismatch, MismatchKind, Source, SuggestedFix, QUANTITY_EPSILON};
+use std::collections::BTreeMap;
+
+/// Cumul d'une source pour un contrat (prix moyen pondéré par la quantité)
+#[derive(Debug, Clone, Default)]
+struct Aggregate {
+    quantity: f64,
+    weighted_price: f64,
+    priced_quantity: f64,
+    references: Vec<String>,
+}
+
+impl Aggregate {
+    fn add(&mut self, h: &Holding) {
+        self.quantity += h.quantity;
+        // Jambes saisies à 0 : la prime nette du spread est portée par une autre jambe
+        if h.price > 0.0 {
+            self.weighted_price += h.price * h.quantity.abs();
+            self.priced_quantity += h.quantity.abs();
+        }
+        self.references.push(h.reference.clone());
+    }
+
+    fn price(&self) -> Option<f64> {
+        (self.priced_quantity > 0.0).then(|| self.weighted_price / self.priced_quantity)
+    }
+
+    fn is_open(&self) -> bool {
+        self.quantity.abs() > QUANTITY_EPSILON
+    }
+}
+
+/// `flex` / `tws` à None : source non chargée, ignorée dans la comparaison
+pub fn reconcile(
+    journal: &[Holding],
+    flex: Option<&[Holding]>,
+    tws: Option<&[Holding]>,
+    price_tolerance_pct: f64,
+) -> Vec<Mismatch> {
+    let journal = aggregate(journal);
+    let flex = flex.map(aggregate);
+    let tws = tws.map(aggregate);
+    let (broker_source, broker) = match (&tws, &flex) {
+        (Some(t), _) => (Source::Tws, t),
+        (None, Some(f)) => (Source::Flex, f),
+        (None, None) => return Vec::new(),
+    };
+
+    let mut keys: Vec<&ContractKey> = journal.keys().chain(broker.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let empty = Aggregate::default();
+    let mut mismatches = Vec::new();
+    for key in keys {
+        let j = journal.get(key).unwrap_or(&empty);
+        let b = broker.get(key).unwrap_or(&empty);
+        let base = |kind, fix| Mismatch {
+            kind,
+            contract: key.label(),
+            key: key.clone(),
+            source: broker_source,
+            journal_quantity: j.quantity,
+            broker_quantity: b.quantity,
+            journal_price: j.price(),
+            broker_price: b.price(),
+            references: j.references.clone(),
+            fix,
+        };
+        match (j.is_open(), b.is_open()) {
+            (false, true) => mismatches.push(base(
+                MismatchKind::MissingLeg,
+                SuggestedFix {
+                    action: FixAction::AddToJournal,
+                    quantity: Some(b.quantity),
+                    price: b.price(),
+                    description: format!(
+                        "Ajouter {} {} au journal ({} à {:.2})",
+                        b.quantity, key.label(), broker_source.label(), b.price().unwrap_or(0.0)
+                    ),
+                },
+            )),
+            (true, false) => mismatches.push(base(
+                MismatchKind::ClosedAtBroker,
+                SuggestedFix {
+                    action: FixAction::CloseInJournal,
+                    quantity: Some(0.0),
+                    price: None,
+                    description: format!(
+                        "Clôturer {} dans le journal : plus de position chez {}",
+                        key.label(), broker_source.label()
+                    ),
+                },
+            )),
+            (true, true) if (j.quantity - b.quantity).abs() > QUANTITY_EPSILON => mismatches.push(base(
+                MismatchKind::QuantityDiff,
+                SuggestedFix {
+                    action: FixAction::SetQuantity,
+                    quantity: Some(b.quantity),
+                    price: None,
+                    description: format!(
+                        "Passer la quantité de {} de {} à {} ({})",
+                        key.label(), j.quantity, b.quantity, broker_source.label()
+                    ),
+                },
+            )),
+            (true, true) => {
+                if let (Some(jp), Some(bp)) = (j.price(), b.price()) {
+                    if bp > 0.0 && ((jp - bp) / bp).abs() * 100.0 > price_tolerance_pct {
+                        mismatches.push(base(
+                            MismatchKind::PriceDiff,
+                            SuggestedFix {
+                                action: FixAction::SetEntryPrice,
+                                quantity: None,
+                                price: Some(bp),
+                                description: format!(
+                                    "Corriger le prix d'entrée de {} : {:.2} → {:.2} ({})",
+                                    key.label(), jp, bp, broker_source.label()
+                                ),
+                            },
+                        ));
+                    }
+                }
+            }
+            (false, false) => {}
+        }
+    }
+
+    if let (Some(flex), Some(tws)) = (&flex, &tws) {
+        mismatches.extend(flex_vs_tws(flex, tws, &journal));
+    }
+    mismatches
+}
+
+/// Flex en désaccord avec TWS : exécutions absentes de la période couverte par la Flex Query
+fn flex_vs_tws(
+    flex: &BTreeMap<ContractKey, Aggregate>,
+    tws: &BTreeMap<ContractKey, Aggregate>,
+    journal: &BTreeMap<ContractKey, Aggregate>,
+) -> Vec<Mismatch> {
+    let mut keys: Vec<&ContractKey> = flex.keys().chain(tws.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    let empty = Aggregate::default();
+    keys.into_iter()
+        .filter_map(|key| {
+            let f = flex.get(key).unwrap_or(&empty);
+            let t = tws.get(key).unwrap_or(&empty);
+            ((f.quantity - t.quantity).abs() > QUANTITY_EPSILON).then(|| Mismatch {
+                kind: MismatchKind::QuantityDiff,
+                contract: key.label(),
+                key: key.clone(),
+                source: Source::Flex,
+                journal_quantity: journal.get(key).map_or(0.0, |j| j.quantity),
+                broker_quantity: f.quantity,
+                journal_price: None,
+                broker_price: f.price(),
+                references: f.references.clone(),
+                fix: SuggestedFix {
+                    action: FixAction::ResyncFlex,
+                    quantity: Some(t.quantity),
+                    price: None,
+                    description: format!(
+                        "Flex indique {} {} contre {} dans TWS : élargir la période de la Flex Query puis resynchroniser",
+                        f.quantity, key.label(), t.quantity
+                    ),
+                },
+            })
+        })
+        .collect()
+}
+
+fn aggregate(holdings: &[Holding]) -> BTreeMap<ContractKey, Aggregate> {
+    let mut map: BTreeMap<ContractKey, Aggregate> = BTreeMap::new();
+    for h in holdings {
+        map.entry(h.key.clone()).or_default().add(h);
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::pricing::OptionKind;
+    use chrono::NaiveDate;
+
+    fn holding(source: Source, reference: &str, strike: f64, quantity: f64, price: f64) -> Holding {
+        Holding {
+            source,
+            reference: reference.into(),
+            key: ContractKey::new("SPY", Some(OptionKind::Put), strike, NaiveDate::from_ymd_opt(2025, 3, 21)),
+            quantity,
+            price,
+        }
+    }
+
+    fn kinds(mismatches: &[Mismatch]) -> Vec<(MismatchKind, f64)> {
+        mismatches.iter().map(|m| (m.kind, m.key.strike())).collect()
+    }
+
+    #[test]
+    fn test_journal_against_tws() {
+        let journal = [
+            holding(Source::Journal, "legs:1", 550.0, -1.0, 2.0),
+            holding(Source::Journal, "legs:2", 545.0, 1.0, 0.0),
+            holding(Source::Journal, "legs:3", 540.0, -2.0, 1.0),
+            holding(Source::Journal, "open_positions:a", 530.0, -1.0, 1.0),
+        ];
+        let tws = [
+            holding(Source::Tws, "tws:1", 550.0, -1.0, 2.5),
+            holding(Source::Tws, "tws:2", 545.0, 1.0, 0.8),
+            holding(Source::Tws, "tws:3", 540.0, -1.0, 1.0),
+            holding(Source::Tws, "tws:4", 520.0, 3.0, 0.5),
+        ];
+        let mismatches = reconcile(&journal, None, Some(&tws), 5.0);
+        assert_eq!(
+            kinds(&mismatches),
+            vec![
+                (MismatchKind::MissingLeg, 520.0),
+                (MismatchKind::ClosedAtBroker, 530.0),
+                (MismatchKind::QuantityDiff, 540.0),
+                (MismatchKind::PriceDiff, 550.0),
+            ]
+        );
+        assert_eq!(mismatches[1].references, vec!["open_positions:a".to_string()]);
+        assert_eq!(mismatches[2].fix.quantity, Some(-1.0));
+        assert_eq!(mismatches[3].fix.price, Some(2.5));
+    }
+
+    #[test]
+    fn test_flex_is_the_broker_without_tws_and_checked_against_it_otherwise() {
+        let journal = [holding(Source::Journal, "legs:1", 550.0, -1.0, 2.0)];
+        let flex = [holding(Source::Flex, "flex:a", 550.0, -1.0, 2.05)];
+        assert!(reconcile(&journal, Some(&flex), None, 5.0).is_empty());
+
+        let tws = [holding(Source::Tws, "tws:1", 550.0, -2.0, 2.0)];
+        let mismatches = reconcile(&journal, Some(&flex), Some(&tws), 5.0);
+        let actions: Vec<FixAction> = mismatches.iter().map(|m| m.fix.action).collect();
+        assert_eq!(actions, vec![FixAction::SetQuantity, FixAction::ResyncFlex]);
+    }
+}
@@ -0,0 +1,235 @@
+// Réconciliation des positions ouvertes entre le journal (open_positions + trades/legs),
+// les exécutions Flex et les positions TWS : jambes manquantes, écarts de quantité,
+// positions clôturées chez le courtier, écarts de prix au-delà d'une tolérance
+
+pub mod commands;
+mod compare;
+mod sources;
+
+pub use compare::reconcile;
+pub use sources::{flex_holdings, journal_holdings, tws_holdings};
+
+use crate::modules::db;
+use crate::modules::error::AppError;
+use crate::modules::logging::SYNC;
+use crate::modules::secrets::{self, SecretKind};
+use crate::modules::pricing::OptionKind;
+use crate::modules::tws_socket::{TWSConfig, TWSSyncClient, SIMULATED_POSITIONS};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+/// En dessous : quantité nulle (cumuls en f64)
+pub const QUANTITY_EPSILON: f64 = 1e-6;
+
+/// Écart de prix d'entrée toléré par défaut (commissions incluses dans le coût TWS)
+pub const DEFAULT_PRICE_TOLERANCE_PCT: f64 = 5.0;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReconcileRequest {
+    /// Flex Query à relire (toutes les exécutions) ; ignorée si absente
+    #[serde(default)]
+    pub flex_token: Option<String>,
+    /// Profil dont le token est lu dans le magasin de secrets si `flex_token` est absent
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub query_id: Option<i32>,
+    /// Compare aussi aux positions TWS (ignoré avec un avertissement tant qu'elles sont simulées)
+    #[serde(default = "crate::modules::tws_socket::default_include_tws")]
+    pub include_tws: bool,
+    #[serde(default = "default_price_tolerance")]
+    pub price_tolerance_pct: f64,
+}
+
+fn default_price_tolerance() -> f64 {
+    DEFAULT_PRICE_TOLERANCE_PCT
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Journal,
+    Flex,
+    Tws,
+}
+
+impl Source {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::Journal => "journal",
+            Source::Flex => "Flex",
+            Source::Tws => "TWS",
+        }
+    }
+}
+
+/// Contrat indépendant du compte : sous-jacent, type, strike (millièmes), échéance
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct ContractKey {
+    pub underlying: String,
+    pub expiry: Option<NaiveDate>,
+    pub right: String, // "P" | "C" | "STK"
+    strike_milli: i64,
+}
+
+impl ContractKey {
+    /// `symbol` : sous-jacent seul ou symbole complet ("SPY   250321P00550000")
+    pub fn new(symbol: &str, kind: Option<OptionKind>, strike: f64, expiry: Option<NaiveDate>) -> Self {
+        let underlying = symbol.split_whitespace().next().unwrap_or(symbol).to_uppercase();
+        let right = match kind {
+            Some(OptionKind::Put) => "P",
+            Some(OptionKind::Call) => "C",
+            None => "STK",
+        };
+        ContractKey {
+            underlying,
+            expiry: if kind.is_some() { expiry } else { None },
+            right: right.to_string(),
+            strike_milli: if kind.is_some() { (strike * 1000.0).round() as i64 } else { 0 },
+        }
+    }
+
+    pub fn strike(&self) -> f64 {
+        self.strike_milli as f64 / 1000.0
+    }
+
+    /// "SPY 2025-03-21 550P" / "KO"
+    pub fn label(&self) -> String {
+        match self.expiry {
+            Some(expiry) => format!("{} {} {}{}", self.underlying, expiry, self.strike(), self.right),
+            None if self.right == "STK" => self.underlying.clone(),
+            None => format!("{} {}{}", self.underlying, self.strike(), self.right),
+        }
+    }
+}
+
+/// Ligne d'une source (quantité signée : > 0 long, < 0 short ; prix unitaire)
+#[derive(Debug, Clone)]
+pub struct Holding {
+    pub source: Source,
+    pub reference: String, // "legs:<id>", "open_positions:<id>", "tws:<compte>:<symbole>", "flex:<contrat>"
+    pub key: ContractKey,
+    pub quantity: f64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    MissingLeg,
+    QuantityDiff,
+    ClosedAtBroker,
+    PriceDiff,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixAction {
+    AddToJournal,
+    CloseInJournal,
+    SetQuantity,
+    SetEntryPrice,
+    ResyncFlex,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuggestedFix {
+    pub action: FixAction,
+    pub quantity: Option<f64>,
+    pub price: Option<f64>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Mismatch {
+    pub kind: MismatchKind,
+    pub contract: String,
+    pub key: ContractKey,
+    /// Source comparée au journal (Flex pour un écart Flex / TWS)
+    pub source: Source,
+    pub journal_quantity: f64,
+    pub broker_quantity: f64,
+    pub journal_price: Option<f64>,
+    pub broker_price: Option<f64>,
+    /// Lignes concernées (journal, ou Flex pour un écart Flex / TWS)
+    pub references: Vec<String>,
+    pub fix: SuggestedFix,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationReport {
+    pub sources: Vec<Source>,
+    pub mismatches: Vec<Mismatch>,
+    /// Source courtier indisponible (TWS fermé, Flex en erreur) : comparaison partielle
+    pub warnings: Vec<String>,
+}
+
+/// Charge les trois sources ; une source courtier en erreur devient un avertissement,
+/// l'absence de toute source courtier est une erreur (celle de Flex si Flex a échoué)
+pub async fn reconcile_positions<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    request: &ReconcileRequest,
+) -> Result<ReconciliationReport, AppError> {
+    let journal =
+        db::open_connection(app_handle).and_then(|conn| journal_holdings(&conn)).map_err(AppError::Database)?;
+    let client = TWSSyncClient::new(TWSConfig::default());
+    let mut warnings = Vec::new();
+    let mut flex_error = None;
+
+    let token = match request.flex_token.clone().filter(|t| !t.is_empty()) {
+        Some(token) => Some(token),
+        None if request.query_id.is_some() => {
+            let profile = request.profile.as_deref().unwrap_or(secrets::DEFAULT_PROFILE);
+            secrets::get(app_handle, profile, SecretKind::FlexToken).unwrap_or_else(|e| {
+                warnings.push(format!("Flex: {}", e));
+                None
+            })
+        }
+        None => None,
+    };
+    let flex = match (token, request.query_id) {
+        (Some(token), Some(query_id)) => {
+            match client.get_flex_executions(&token, query_id, None).await {
+                Ok(executions) => Some(flex_holdings(&executions)),
+                Err(e) => {
+                    warnings.push(format!("Flex: {}", e));
+                    flex_error = Some(e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    let tws = if request.include_tws && SIMULATED_POSITIONS {
+        warnings.push("TWS: simulated positions ignored (socket connection not implemented)".to_string());
+        None
+    } else if request.include_tws {
+        match client.get_positions().await {
+            Ok(positions) => Some(tws_holdings(&positions)),
+            Err(e) => {
+                warnings.push(format!("TWS: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if flex.is_none() && tws.is_none() {
+        // Flex en erreur : code Flex et `retryable` conservés pour le frontend
+        if let Some(e) = flex_error {
+            return Err(e);
+        }
+        let detail =
+            if warnings.is_empty() { "no Flex query or TWS requested".to_string() } else { warnings.join("; ") };
+        return Err(AppError::Validation(format!("No broker source to reconcile against: {}", detail)));
+    }
+
+    let mut sources = vec![Source::Journal];
+    sources.extend(flex.as_ref().map(|_| Source::Flex));
+    sources.extend(tws.as_ref().map(|_| Source::Tws));
+    let mismatches = reconcile(&journal, flex.as_deref(), tws.as_deref(), request.price_tolerance_pct);
+    let (mismatch_count, warning_count) = (mismatches.len(), warnings.len());
+    tracing::info!(target: SYNC, mismatches = mismatch_count, warnings = warning_count, "Positions reconciled");
+    Ok(ReconciliationReport { sources, mismatches, warnings })
+}
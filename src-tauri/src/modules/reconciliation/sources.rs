@@ -0,0 +1,172 @@
+// Positions des trois sources ramenées à une clé de contrat commune :
+// journal (open_positions + jambes ouvertes), exécutions Flex cumulées, positions TWS
+
+use super::{ContractKey, Holding, Source};
+use crate::modules::dates::parse_trade_date;
+use crate::modules::portfolio::{from_tws_position, load_manual_positions, RiskPosition};
+use crate::modules::pricing::OptionKind;
+use crate::modules::tws_socket::{FlexTrade, Position};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+/// Journal : saisies manuelles + jambes ouvertes des trades non supprimés
+pub fn journal_holdings(conn: &Connection) -> Result<Vec<Holding>, String> {
+    let mut holdings: Vec<Holding> = load_manual_positions(conn)?
+        .iter()
+        .map(|p| from_risk_position(p, Source::Journal, format!("open_positions:{}", p.id)))
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT l.id, t.symbol, l.type, l.side, l.quantity, l.strike, l.expiration, l.open_price
+             FROM legs l JOIN trades t ON t.id = l.trade_id
+             WHERE COALESCE(l.status, 'open') = 'open' AND COALESCE(t.is_deleted, 0) = 0",
+        )
+        .map_err(|e| format!("legs query error: {}", e))?;
+    let rows = stmt
+        .query_map([], |r| {
+            let id: i64 = r.get(0)?;
+            let symbol: String = r.get(1)?;
+            let leg_type: String = r.get(2)?;
+            let side: String = r.get(3)?;
+            let quantity: f64 = r.get(4)?;
+            let strike: Option<f64> = r.get(5)?;
+            let expiration: Option<String> = r.get(6)?;
+            let kind = parse_kind(&leg_type);
+            let sign = if side.eq_ignore_ascii_case("short") { -1.0 } else { 1.0 };
+            Ok(Holding {
+                source: Source::Journal,
+                reference: format!("legs:{}", id),
+                key: ContractKey::new(
+                    &symbol,
+                    kind,
+                    if kind.is_some() { strike.unwrap_or(0.0) } else { 0.0 },
+                    expiration.as_deref().and_then(parse_trade_date).filter(|_| kind.is_some()),
+                ),
+                quantity: sign * quantity.abs(),
+                price: r.get::<_, Option<f64>>(7)?.unwrap_or(0.0),
+            })
+        })
+        .map_err(|e| format!("legs query error: {}", e))?;
+    for row in rows {
+        holdings.push(row.map_err(|e| e.to_string())?);
+    }
+    Ok(holdings)
+}
+
+/// Position nette par contrat impliquée par les exécutions Flex (achats - ventes) ;
+/// prix = moyenne pondérée des exécutions dans le sens de la position restante
+pub fn flex_holdings(executions: &[FlexTrade]) -> Vec<Holding> {
+    let mut nets: BTreeMap<ContractKey, FlexNet> = BTreeMap::new();
+    for t in executions {
+        let kind = parse_kind(&t.put_call);
+        let is_option = kind.is_some() && !t.asset_class.eq_ignore_ascii_case("STK");
+        let key = ContractKey::new(
+            &t.symbol,
+            kind.filter(|_| is_option),
+            if is_option { t.strike } else { 0.0 },
+            if is_option { parse_trade_date(&t.expiry) } else { None },
+        );
+        let quantity = (t.quantity as f64).abs();
+        let entry = nets.entry(key).or_default();
+        if t.side.eq_ignore_ascii_case("BUY") || t.side.eq_ignore_ascii_case("BOT") {
+            entry.net += quantity;
+            entry.bought = (entry.bought.0 + quantity, entry.bought.1 + quantity * t.price);
+        } else {
+            entry.net -= quantity;
+            entry.sold = (entry.sold.0 + quantity, entry.sold.1 + quantity * t.price);
+        }
+    }
+    nets.into_iter()
+        .filter(|(_, n)| n.net.abs() > super::QUANTITY_EPSILON)
+        .map(|(key, n)| {
+            let (quantity, amount) = if n.net > 0.0 { n.bought } else { n.sold };
+            Holding {
+                source: Source::Flex,
+                reference: format!("flex:{}", key.label()),
+                key,
+                quantity: n.net,
+                price: if quantity > 0.0 { amount / quantity } else { 0.0 },
+            }
+        })
+        .collect()
+}
+
+/// Cumul Flex d'un contrat : quantité nette, (quantité, montant) achetés et vendus
+#[derive(Default)]
+struct FlexNet {
+    net: f64,
+    bought: (f64, f64),
+    sold: (f64, f64),
+}
+
+pub fn tws_holdings(positions: &[Position]) -> Vec<Holding> {
+    positions
+        .iter()
+        .filter_map(from_tws_position)
+        .map(|p| {
+            let reference = p.id.clone();
+            from_risk_position(&p, Source::Tws, reference)
+        })
+        .collect()
+}
+
+fn from_risk_position(p: &RiskPosition, source: Source, reference: String) -> Holding {
+    Holding {
+        source,
+        reference,
+        key: ContractKey::new(&p.underlying, p.kind, p.strike, p.expiry),
+        quantity: p.quantity,
+        price: p.entry_price,
+    }
+}
+
+fn parse_kind(raw: &str) -> Option<OptionKind> {
+    match raw.trim().to_uppercase().as_str() {
+        "P" | "PUT" => Some(OptionKind::Put),
+        "C" | "CALL" => Some(OptionKind::Call),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(side: &str, quantity: i32, price: f64) -> FlexTrade {
+        FlexTrade {
+            account_id: "U1234567".into(),
+            trade_id: String::new(),
+            symbol: "SPY   250321P00550000".into(),
+            asset_class: "OPT".into(),
+            side: side.into(),
+            quantity,
+            multiplier: 100,
+            price,
+            commission: 0.0,
+            realized_pnl: 0.0,
+            date: "2025-03-14".into(),
+            time: String::new(),
+            expiry: "20250321".into(),
+            strike: 550.0,
+            put_call: "P".into(),
+            open_close: String::new(),
+            exchange: String::new(),
+            proceeds: 0.0,
+            cost_basis: 0.0,
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_flex_net_position_and_opening_price() {
+        let holdings = flex_holdings(&[execution("SELL", -2, 2.0), execution("SELL", 1, 3.5), execution("BUY", 1, 1.0)]);
+        assert_eq!(holdings.len(), 1);
+        assert_eq!(holdings[0].key.label(), "SPY 2025-03-21 550P");
+        assert_eq!(holdings[0].quantity, -2.0);
+        assert_eq!(holdings[0].price, 2.5);
+
+        // Position entièrement clôturée : plus rien à comparer
+        assert!(flex_holdings(&[execution("SELL", 1, 2.0), execution("BUY", 1, 1.0)]).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+// Masquage des données sensibles avant écriture dans les logs (stdout/stderr finissent dans
+// des fichiers comme app-logs.txt) : tokens Flex, ReferenceCode, numéros de compte IB
+// Les réponses brutes sont tronquées hors mode debug (TRADEVISION_DEBUG=1)
+
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+/// Variable d'environnement du mode debug (dumps complets, toujours masqués)
+pub const DEBUG_ENV: &str = "TRADEVISION_DEBUG";
+/// Caractères d'une réponse brute conservés hors mode debug
+const PAYLOAD_PREVIEW_CHARS: usize = 200;
+
+struct Patterns {
+    account: Regex,
+    url_token: Regex,
+    reference_code: Regex,
+    long_number: Regex,
+}
+
+/// None si un motif ne compile pas : le texte est alors entièrement masqué
+fn patterns() -> Option<&'static Patterns> {
+    static PATTERNS: OnceLock<Option<Patterns>> = OnceLock::new();
+    PATTERNS
+        .get_or_init(|| {
+            Some(Patterns {
+                // U1234567 (compte), DU1234567 (paper), F1234567 (conseiller)
+                account: Regex::new(r"\b(DU|U|F)(\d{5,9})\b").ok()?,
+                url_token: Regex::new(r#"([?&]t=)[^&\s"'<]+"#).ok()?,
+                reference_code: Regex::new(r"(ReferenceCode(?:>|=|:\s*))(\w+)").ok()?,
+                // Token Flex : longue suite de chiffres, quel que soit le contexte
+                long_number: Regex::new(r"\b\d{16,}\b").ok()?,
+            })
+        })
+        .as_ref()
+}
+
+pub fn debug_enabled() -> bool {
+    static DEBUG: OnceLock<bool> = OnceLock::new();
+    *DEBUG.get_or_init(|| std::env::var(DEBUG_ENV).is_ok_and(|v| matches!(v.as_str(), "1" | "true" | "yes")))
+}
+
+/// U1234567 → U12***67
+pub fn mask_account(account_id: &str) -> String {
+    match patterns() {
+        Some(p) => p.account.replace_all(account_id, |c: &Captures| mask_digits(&c[1], &c[2])).into_owned(),
+        None => mask_secret(account_id),
+    }
+}
+
+/// Token, ReferenceCode : seuls les deux derniers caractères restent visibles
+pub fn mask_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 4 {
+        return "***".to_string();
+    }
+    format!("***{}", chars[chars.len() - 2..].iter().collect::<String>())
+}
+
+/// Texte libre (message d'erreur, réponse HTTP) avec tous les motifs sensibles masqués
+pub fn redact(text: &str) -> String {
+    let Some(p) = patterns() else {
+        return format!("<{} chars masqués>", text.chars().count());
+    };
+    let text = p.url_token.replace_all(text, "${1}***");
+    let text = p.reference_code.replace_all(&text, |c: &Captures| format!("{}{}", &c[1], mask_secret(&c[2])));
+    let text = p.long_number.replace_all(&text, |c: &Captures| mask_secret(&c[0]));
+    p.account.replace_all(&text, |c: &Captures| mask_digits(&c[1], &c[2])).into_owned()
+}
+
+/// Réponse brute à journaliser : masquée, et tronquée hors mode debug
+pub fn payload(text: &str) -> String {
+    let redacted = redact(text);
+    let total = redacted.chars().count();
+    if debug_enabled() || total <= PAYLOAD_PREVIEW_CHARS {
+        return redacted;
+    }
+    let preview: String = redacted.chars().take(PAYLOAD_PREVIEW_CHARS).collect();
+    format!("{}… ({} chars, {}=1 pour tout afficher)", preview, total, DEBUG_ENV)
+}
+
+fn mask_digits(prefix: &str, digits: &str) -> String {
+    let (head, tail) = (&digits[..2], &digits[digits.len() - 2..]);
+    format!("{}{}***{}", prefix, head, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_ids_keep_prefix_and_edges() {
+        assert_eq!(mask_account("U1234567"), "U12***67");
+        assert_eq!(mask_account("DU7654321"), "DU76***21");
+        assert_eq!(redact("Import U1234567 failed; USD 1234 ok"), "Import U12***67 failed; USD 1234 ok");
+    }
+
+    #[test]
+    fn test_tokens_and_reference_codes_are_masked() {
+        let url = "https://x/SendRequest?t=123456789012345678901234&q=42&v=3";
+        assert_eq!(redact(url), "https://x/SendRequest?t=***&q=42&v=3");
+        let xml = "<Status>Success</Status><ReferenceCode>9876543210</ReferenceCode>";
+        assert_eq!(redact(xml), "<Status>Success</Status><ReferenceCode>***10</ReferenceCode>");
+        assert_eq!(redact("token 123456789012345678901234 rejected"), "token ***34 rejected");
+        assert_eq!(mask_secret("abc"), "***");
+    }
+
+    #[test]
+    fn test_payload_is_trimmed_without_debug_mode() {
+        let raw = format!("<FlexStatement accountId=\"U1234567\">{}</FlexStatement>", "é".repeat(500));
+        let logged = payload(&raw);
+        assert!(logged.contains("U12***67") && !logged.contains("U1234567"));
+        if !debug_enabled() {
+            assert!(logged.chars().count() < 300 && logged.contains(DEBUG_ENV));
+        }
+    }
+}
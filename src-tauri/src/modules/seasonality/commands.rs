@@ -0,0 +1,15 @@
+// Commande Tauri des vues Saisonnalité et Calendrier
+
+use super::{SeasonalityFilters, SeasonalityReport};
+use crate::modules::db;
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Agrège le P&L flex_trades par saisonnalité + calendrier (SaisonnaliteView, CalendrierView)
+#[tauri::command]
+pub async fn get_seasonality(
+    app_handle: tauri::AppHandle,
+    filters: SeasonalityFilters,
+) -> Result<SeasonalityReport, AppError> {
+    let conn = db::open_connection(&app_handle).map_err(AppError::Database)?;
+    super::aggregate(&conn, &filters).map_err(AppError::Database)
+}
@@ -0,0 +1,228 @@
+// Agrégation saisonnière du P&L réalisé (flex_trades) pour SaisonnaliteView et CalendrierView
+
+pub mod commands;
+mod stats;
+
+pub use stats::{Accumulator, BucketStats, MIN_TRADES_SIGNIFICANT};
+
+use crate::modules::dates::{parse_trade_date, parse_trade_hour};
+
+use chrono::{Datelike, NaiveDate};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const WEEKDAYS: [&str; 7] = ["Lundi", "Mardi", "Mercredi", "Jeudi", "Vendredi", "Samedi", "Dimanche"];
+const MONTHS: [&str; 12] = [
+    "Janvier", "Février", "Mars", "Avril", "Mai", "Juin",
+    "Juillet", "Août", "Septembre", "Octobre", "Novembre", "Décembre",
+];
+
+/// Filtres optionnels envoyés par la vue (None = pas de filtre)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SeasonalityFilters {
+    pub strategy: Option<String>,
+    pub symbol: Option<String>,
+    pub account_id: Option<String>,
+}
+
+/// Case du calendrier : une journée de trading
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarDay {
+    pub date: String, // YYYY-MM-DD
+    pub trade_count: u32,
+    pub win_count: u32,
+    pub pnl: f64,
+}
+
+/// Rapport complet renvoyé au frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonalityReport {
+    pub by_weekday: Vec<BucketStats>,
+    pub by_hour: Vec<BucketStats>,
+    pub by_day_of_month: Vec<BucketStats>,
+    pub by_month: Vec<BucketStats>,
+    pub by_iso_week: Vec<BucketStats>,
+    pub calendar: Vec<CalendarDay>,
+    pub total_trades: u32,
+    pub total_pnl: f64,
+    pub skipped_rows: u32, // date illisible → exclu des buckets
+    pub min_trades_significant: u32,
+}
+
+/// Ligne minimale lue dans flex_trades
+struct TradeRow {
+    date: String,
+    time: String,
+    pnl: f64,
+}
+
+/// Agrège le P&L réalisé des flex_trades non supprimés par jour de semaine, heure, jour du mois,
+/// mois, semaine ISO et par journée calendaire
+pub fn aggregate(conn: &Connection, filters: &SeasonalityFilters) -> Result<SeasonalityReport, String> {
+    let rows = load_rows(conn, filters)?;
+    Ok(build_report(&rows))
+}
+
+fn load_rows(conn: &Connection, filters: &SeasonalityFilters) -> Result<Vec<TradeRow>, String> {
+    // Filtres paramétrés : NULL = pas de filtre
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(date, ''), COALESCE(time, ''), COALESCE(realized_pnl, 0)
+             FROM flex_trades
+             WHERE COALESCE(is_deleted, 0) = 0
+               AND (?1 IS NULL OR strategy = ?1)
+               AND (?2 IS NULL OR symbol = ?2)
+               AND (?3 IS NULL OR account_id = ?3)",
+        )
+        .map_err(|e| format!("Seasonality query error: {}", e))?;
+
+    let rows = stmt
+        .query_map(
+            rusqlite::params![filters.strategy, filters.symbol, filters.account_id],
+            |r| {
+                Ok(TradeRow {
+                    date: r.get(0)?,
+                    time: r.get(1)?,
+                    pnl: r.get(2)?,
+                })
+            },
+        )
+        .map_err(|e| format!("Seasonality query error: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+fn build_report(rows: &[TradeRow]) -> SeasonalityReport {
+    let mut weekday: BTreeMap<u32, Accumulator> = BTreeMap::new();
+    let mut hour: BTreeMap<u32, Accumulator> = BTreeMap::new();
+    let mut day_of_month: BTreeMap<u32, Accumulator> = BTreeMap::new();
+    let mut month: BTreeMap<u32, Accumulator> = BTreeMap::new();
+    let mut iso_week: BTreeMap<u32, Accumulator> = BTreeMap::new();
+    let mut calendar: BTreeMap<NaiveDate, Accumulator> = BTreeMap::new();
+    let mut skipped_rows = 0u32;
+    let mut total_trades = 0u32;
+    let mut total_pnl = 0.0;
+
+    for row in rows {
+        let Some(date) = parse_trade_date(&row.date) else {
+            skipped_rows += 1;
+            continue;
+        };
+        total_trades += 1;
+        total_pnl += row.pnl;
+
+        weekday.entry(date.weekday().number_from_monday()).or_default().push(row.pnl);
+        day_of_month.entry(date.day()).or_default().push(row.pnl);
+        month.entry(date.month()).or_default().push(row.pnl);
+        iso_week.entry(date.iso_week().week()).or_default().push(row.pnl);
+        calendar.entry(date).or_default().push(row.pnl);
+        // L'heure n'est connue que pour les exports qui la fournissent
+        if let Some(h) = parse_trade_hour(&row.time) {
+            hour.entry(h).or_default().push(row.pnl);
+        }
+    }
+
+    SeasonalityReport {
+        by_weekday: finish_all(&weekday, |k| label_at(&WEEKDAYS, k)),
+        by_hour: finish_all(&hour, |k| format!("{:02}h", k)),
+        by_day_of_month: finish_all(&day_of_month, |k| k.to_string()),
+        by_month: finish_all(&month, |k| label_at(&MONTHS, k)),
+        by_iso_week: finish_all(&iso_week, |k| format!("S{:02}", k)),
+        calendar: calendar
+            .iter()
+            .map(|(d, acc)| CalendarDay {
+                date: d.format("%Y-%m-%d").to_string(),
+                trade_count: acc.count(),
+                win_count: acc.wins(),
+                pnl: acc.total(),
+            })
+            .collect(),
+        total_trades,
+        total_pnl,
+        skipped_rows,
+        min_trades_significant: MIN_TRADES_SIGNIFICANT,
+    }
+}
+
+fn finish_all(buckets: &BTreeMap<u32, Accumulator>, label: impl Fn(u32) -> String) -> Vec<BucketStats> {
+    buckets.iter().map(|(k, acc)| acc.finish(*k, label(*k))).collect()
+}
+
+/// Libellé français d'une clé 1-indexée (lundi = 1, janvier = 1)
+fn label_at(labels: &[&str], key: u32) -> String {
+    (key as usize)
+        .checked_sub(1)
+        .and_then(|i| labels.get(i))
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        conn.execute_batch(
+            "CREATE TABLE flex_trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                trade_id TEXT UNIQUE NOT NULL,
+                account_id TEXT, symbol TEXT NOT NULL, date TEXT, time TEXT,
+                realized_pnl REAL DEFAULT 0, strategy TEXT, is_deleted INTEGER DEFAULT 0
+            );
+            INSERT INTO flex_trades (trade_id, account_id, symbol, date, time, realized_pnl, strategy, is_deleted) VALUES
+                ('1', 'U1', 'AAPL', '20240115', '143022', 100.0, 'Rockets', 0),
+                ('2', 'U1', 'AAPL', '2024-01-15', '09:31:00', -40.0, 'Rockets', 0),
+                ('3', 'U1', 'TSLA', '20240216', '', 60.0, 'Wheel', 0),
+                ('4', 'U2', 'AAPL', '20240115', '150000', 999.0, 'Rockets', 1),
+                ('5', 'U1', 'AAPL', 'n/a', '', 5.0, 'Rockets', 0);",
+        )
+        .expect("schema");
+        conn
+    }
+
+    #[test]
+    fn test_aggregate_buckets_and_calendar() {
+        let conn = setup();
+        let report = aggregate(&conn, &SeasonalityFilters::default()).expect("aggregate");
+
+        assert_eq!(report.total_trades, 3);
+        assert_eq!(report.skipped_rows, 1);
+        assert!((report.total_pnl - 120.0).abs() < 1e-9);
+
+        // 15/01/2024 = lundi, 16/02/2024 = vendredi
+        let monday = &report.by_weekday[0];
+        assert_eq!(monday.label, "Lundi");
+        assert_eq!(monday.trade_count, 2);
+        assert!((monday.mean_pnl - 30.0).abs() < 1e-9);
+
+        assert_eq!(report.by_month.len(), 2);
+        assert_eq!(report.by_month[1].label, "Février");
+        // Le trade sans heure n'alimente pas by_hour
+        assert_eq!(report.by_hour.iter().map(|b| b.trade_count).sum::<u32>(), 2);
+
+        assert_eq!(report.calendar.len(), 2);
+        assert_eq!(report.calendar[0].date, "2024-01-15");
+        assert_eq!(report.calendar[0].trade_count, 2);
+    }
+
+    #[test]
+    fn test_aggregate_filters() {
+        let conn = setup();
+        let filters = SeasonalityFilters {
+            strategy: Some("Wheel".to_string()),
+            ..Default::default()
+        };
+        let report = aggregate(&conn, &filters).expect("aggregate");
+        assert_eq!(report.total_trades, 1);
+        assert_eq!(report.by_iso_week[0].label, "S07");
+
+        let filters = SeasonalityFilters {
+            account_id: Some("U2".to_string()),
+            ..Default::default()
+        };
+        // Le seul trade de U2 est supprimé (soft delete)
+        assert_eq!(aggregate(&conn, &filters).expect("aggregate").total_trades, 0);
+    }
+}
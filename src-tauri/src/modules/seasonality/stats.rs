@@ -0,0 +1,128 @@
+// Statistiques par bucket de saisonnalité (moyenne/écart-type incrémentaux)
+
+use serde::Serialize;
+
+/// Nombre minimum de trades avant de considérer un bucket comme exploitable
+pub const MIN_TRADES_SIGNIFICANT: u32 = 10;
+
+/// Seuil |t| (≈ 95% bilatéral) au-delà duquel la moyenne est jugée différente de 0
+const T_STAT_THRESHOLD: f64 = 1.96;
+
+/// Résultat agrégé d'un bucket (un jour de semaine, une heure, un mois...)
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketStats {
+    pub key: u32,
+    pub label: String,
+    pub trade_count: u32,
+    pub win_count: u32,
+    pub total_pnl: f64,
+    pub mean_pnl: f64,
+    pub std_dev: Option<f64>,   // None si moins de 2 trades
+    pub std_error: Option<f64>,
+    pub t_stat: Option<f64>,
+    pub win_rate: f64,
+    pub is_significant: bool,   // assez de trades ET moyenne distincte de 0
+}
+
+/// Accumulateur de Welford : une seule passe, stable numériquement
+#[derive(Debug, Clone, Default)]
+pub struct Accumulator {
+    count: u32,
+    wins: u32,
+    total: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Accumulator {
+    /// Ajoute le P&L d'un trade au bucket
+    pub fn push(&mut self, pnl: f64) {
+        self.count += 1;
+        self.total += pnl;
+        if pnl > 0.0 {
+            self.wins += 1;
+        }
+        let delta = pnl - self.mean;
+        self.mean += delta / f64::from(self.count);
+        self.m2 += delta * (pnl - self.mean);
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    pub fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    /// Produit les statistiques finales, le test de significativité évite de sur-interpréter
+    /// un mois qui ne contient que deux trades
+    pub fn finish(&self, key: u32, label: String) -> BucketStats {
+        let n = f64::from(self.count);
+        let std_dev = if self.count >= 2 {
+            Some((self.m2 / (n - 1.0)).sqrt())
+        } else {
+            None
+        };
+        let std_error = std_dev.map(|s| s / n.sqrt());
+        let t_stat = std_error.and_then(|se| if se > 0.0 { Some(self.mean / se) } else { None });
+        let is_significant = self.count >= MIN_TRADES_SIGNIFICANT
+            && t_stat.is_some_and(|t| t.abs() >= T_STAT_THRESHOLD);
+
+        BucketStats {
+            key,
+            label,
+            trade_count: self.count,
+            win_count: self.wins,
+            total_pnl: self.total,
+            mean_pnl: if self.count > 0 { self.mean } else { 0.0 },
+            std_dev,
+            std_error,
+            t_stat,
+            win_rate: if self.count > 0 { f64::from(self.wins) / n } else { 0.0 },
+            is_significant,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean_and_std_dev() {
+        let mut acc = Accumulator::default();
+        for pnl in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            acc.push(pnl);
+        }
+        let stats = acc.finish(1, "x".to_string());
+        assert_eq!(stats.trade_count, 8);
+        assert!((stats.mean_pnl - 5.0).abs() < 1e-9);
+        // Écart-type échantillon de la série = sqrt(32/7)
+        let expected = (32.0_f64 / 7.0).sqrt();
+        assert!((stats.std_dev.unwrap_or_default() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_two_trades_never_significant() {
+        let mut acc = Accumulator::default();
+        acc.push(500.0);
+        acc.push(510.0);
+        let stats = acc.finish(3, "Mars".to_string());
+        assert!(stats.t_stat.unwrap_or_default() > T_STAT_THRESHOLD);
+        assert!(!stats.is_significant);
+    }
+
+    #[test]
+    fn test_single_trade_has_no_dispersion() {
+        let mut acc = Accumulator::default();
+        acc.push(-20.0);
+        let stats = acc.finish(0, "Lundi".to_string());
+        assert!(stats.std_dev.is_none());
+        assert_eq!(stats.win_rate, 0.0);
+    }
+}
@@ -0,0 +1,49 @@
+// Commandes Tauri du magasin de secrets : aucune ne renvoie la valeur d'un secret
+
+use super::{SecretCheck, SecretInfo, SecretKind, VaultStatus};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Enregistre un secret (token Flex, identifiant IB) ; seule la fiche d'index est renvoyée
+#[tauri::command]
+pub fn save_secret(
+    app_handle: tauri::AppHandle,
+    profile: String,
+    kind: SecretKind,
+    value: String,
+) -> Result<SecretInfo, AppError> {
+    super::save(&app_handle, &profile, kind, &value)
+}
+
+/// Commande Tauri: Vérifie les secrets d'un profil (token Flex testé auprès d'IBKR)
+#[tauri::command]
+pub async fn test_secret(app_handle: tauri::AppHandle, profile: String) -> Result<Vec<SecretCheck>, AppError> {
+    super::test(&app_handle, &profile).await
+}
+
+#[tauri::command]
+pub fn delete_secret(app_handle: tauri::AppHandle, profile: String, kind: SecretKind) -> Result<(), AppError> {
+    super::delete(&app_handle, &profile, kind)
+}
+
+/// Commande Tauri: Secrets enregistrés (profil, type, emplacement), sans leur valeur
+#[tauri::command]
+pub fn list_secrets(app_handle: tauri::AppHandle) -> Result<Vec<SecretInfo>, AppError> {
+    super::list(&app_handle)
+}
+
+/// Commande Tauri: Déverrouille le coffre de repli (créé au premier usage) pour la session
+#[tauri::command]
+pub fn unlock_secret_vault(app_handle: tauri::AppHandle, master_password: String) -> Result<VaultStatus, AppError> {
+    super::unlock_vault(&app_handle, &master_password)
+}
+
+#[tauri::command]
+pub fn lock_secret_vault() -> Result<(), AppError> {
+    super::lock_vault()
+}
+
+/// Commande Tauri: Trousseau disponible, coffre créé / déverrouillé
+#[tauri::command]
+pub fn get_secret_vault_status(app_handle: tauri::AppHandle) -> Result<VaultStatus, AppError> {
+    super::vault_status(&app_handle)
+}
@@ -0,0 +1,299 @@
+// Secrets (tokens Flex, identifiants IB, clés S3 des copies de sauvegarde) stockés côté Rust, par profil :
+// trousseau du système s'il répond, sinon coffre chiffré par mot de passe maître (vault.rs)
+// settings.json ne garde qu'un index sans valeur ; aucune valeur ne repart vers la webview
+
+pub mod commands;
+mod os_keyring;
+mod vault;
+
+pub use vault::VAULT_FILE;
+
+use crate::modules::error::AppError;
+use crate::modules::settings;
+use crate::modules::logging::SECRETS;
+use crate::modules::sync;
+use crate::modules::tws_socket::{TWSConfig, TWSSyncClient};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, Manager, Runtime};
+use vault::VaultKey;
+
+/// Section "secrets" de settings.json (index : profil, type, emplacement)
+pub const SETTINGS_KEY: &str = "secrets";
+/// Profil des identifiants IB et de la query Flex principale
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Clé du coffre déverrouillé pour la session
+static VAULT: Mutex<Option<VaultKey>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    FlexToken,
+    IbUsername,
+    IbPassword,
+    /// Clé secrète d'une destination S3 de sauvegarde (profil = nom de la destination)
+    S3SecretKey,
+}
+
+impl SecretKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretKind::FlexToken => "flex_token",
+            SecretKind::IbUsername => "ib_username",
+            SecretKind::IbPassword => "ib_password",
+            SecretKind::S3SecretKey => "s3_secret_key",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretBackend {
+    Keyring,
+    Vault,
+}
+
+/// Entrée de l'index : jamais la valeur
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub profile: String,
+    pub kind: SecretKind,
+    pub backend: SecretBackend,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SecretIndex {
+    #[serde(default)]
+    entries: Vec<SecretInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultStatus {
+    pub keyring_available: bool,
+    pub vault_exists: bool,
+    pub vault_unlocked: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretCheck {
+    pub kind: SecretKind,
+    pub ok: bool,
+    pub message: String,
+    /// Erreur typée de la vérification (code Flex, `retryable`)
+    pub error: Option<AppError>,
+}
+
+/// Enregistre (ou remplace) un secret : trousseau d'abord, coffre déverrouillé sinon
+pub fn save<R: Runtime>(
+    app: &AppHandle<R>,
+    profile: &str,
+    kind: SecretKind,
+    value: &str,
+) -> Result<SecretInfo, AppError> {
+    let account = account(profile, kind)?;
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::Validation(format!("Empty value for {}", account)));
+    }
+    let mut index = load_index(app)?;
+    let previous = find(&index.entries, profile, kind).map(|i| i.backend);
+    let backend = if os_keyring::available() && os_keyring::set(&account, value).is_ok() {
+        // Une ancienne copie dans le coffre ne doit pas survivre au changement d'emplacement
+        if previous == Some(SecretBackend::Vault) {
+            if let Err(e) = with_vault(|key| remove_from_vault(&vault_path(app)?, key, &account)) {
+                tracing::warn!(target: SECRETS, account = %account, error = %e, "Vault copy not removed");
+            }
+        }
+        SecretBackend::Keyring
+    } else {
+        let path = vault_path(app)?;
+        with_vault(|key| {
+            let mut entries = key.read(&path)?;
+            entries.insert(account.clone(), value.to_string());
+            key.write(&path, &entries)
+        })?;
+        SecretBackend::Vault
+    };
+
+    let info = SecretInfo {
+        profile: profile.trim().to_string(),
+        kind,
+        backend,
+        updated_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+    };
+    upsert(&mut index.entries, info.clone());
+    settings::save_section(app, SETTINGS_KEY, &index)?;
+    tracing::info!(target: SECRETS, account = %account, backend = ?backend, "Secret saved");
+    Ok(info)
+}
+
+/// Valeur d'un secret, pour usage interne au backend uniquement (jamais renvoyée par une commande)
+pub(crate) fn get<R: Runtime>(
+    app: &AppHandle<R>,
+    profile: &str,
+    kind: SecretKind,
+) -> Result<Option<String>, AppError> {
+    let account = account(profile, kind)?;
+    match find(&load_index(app)?.entries, profile, kind).map(|i| i.backend) {
+        Some(SecretBackend::Keyring) => Ok(os_keyring::get(&account)?),
+        Some(SecretBackend::Vault) => with_vault(|key| Ok(key.read(&vault_path(app)?)?.remove(&account))),
+        None => Ok(None),
+    }
+}
+
+pub fn contains<R: Runtime>(app: &AppHandle<R>, profile: &str, kind: SecretKind) -> bool {
+    load_index(app).is_ok_and(|index| find(&index.entries, profile, kind).is_some())
+}
+
+pub fn delete<R: Runtime>(app: &AppHandle<R>, profile: &str, kind: SecretKind) -> Result<(), AppError> {
+    let account = account(profile, kind)?;
+    let mut index = load_index(app)?;
+    match find(&index.entries, profile, kind).map(|i| i.backend) {
+        Some(SecretBackend::Keyring) => os_keyring::delete(&account)?,
+        Some(SecretBackend::Vault) => with_vault(|key| remove_from_vault(&vault_path(app)?, key, &account))?,
+        None => return Ok(()),
+    }
+    index.entries.retain(|i| !(i.profile == profile.trim() && i.kind == kind));
+    settings::save_section(app, SETTINGS_KEY, &index)?;
+    tracing::info!(target: SECRETS, account = %account, "Secret deleted");
+    Ok(())
+}
+
+pub fn list<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<SecretInfo>, AppError> {
+    Ok(load_index(app)?.entries)
+}
+
+/// Relit chaque secret du profil ; le token Flex est vérifié auprès d'IBKR (SendRequest seul)
+/// si une query est configurée pour ce profil
+pub async fn test<R: Runtime>(app: &AppHandle<R>, profile: &str) -> Result<Vec<SecretCheck>, AppError> {
+    let kinds: Vec<SecretKind> =
+        load_index(app)?.entries.iter().filter(|i| i.profile == profile.trim()).map(|i| i.kind).collect();
+    if kinds.is_empty() {
+        return Err(AppError::Validation(format!("No secret saved for profile '{}'", profile)));
+    }
+    let jobs = sync::load_settings(app)?.all_jobs();
+    let query_id = jobs.iter().find(|j| j.profile == profile.trim()).map(|j| j.query_id);
+    let client = TWSSyncClient::new(TWSConfig::default());
+
+    let mut checks = Vec::new();
+    for kind in kinds {
+        let checked = match get(app, profile, kind) {
+            Err(e) => Err(e),
+            Ok(None) => Err(AppError::Auth("Secret introuvable (supprimé du trousseau ?)".to_string())),
+            Ok(Some(token)) if kind == SecretKind::FlexToken => match query_id {
+                Some(query_id) => client
+                    .check_flex_token(&token, query_id)
+                    .await
+                    .map(|_| format!("Token accepté par IBKR (query {})", query_id)),
+                None => Ok("Token lisible, aucune query Flex configurée pour ce profil".to_string()),
+            },
+            Ok(Some(_)) => Ok("Valeur lisible".to_string()),
+        };
+        checks.push(match checked {
+            Ok(message) => SecretCheck { kind, ok: true, message, error: None },
+            Err(e) => SecretCheck { kind, ok: false, message: e.to_string(), error: Some(e) },
+        });
+    }
+    Ok(checks)
+}
+
+/// Déverrouille le coffre pour la session (le crée au premier usage)
+pub fn unlock_vault<R: Runtime>(app: &AppHandle<R>, master_password: &str) -> Result<VaultStatus, AppError> {
+    let key = VaultKey::open(&vault_path(app)?, master_password).map_err(AppError::Auth)?;
+    *vault_key()? = Some(key);
+    tracing::info!(target: SECRETS, "Vault unlocked");
+    vault_status(app)
+}
+
+pub fn lock_vault() -> Result<(), AppError> {
+    *vault_key()? = None;
+    Ok(())
+}
+
+pub fn vault_status<R: Runtime>(app: &AppHandle<R>) -> Result<VaultStatus, AppError> {
+    Ok(VaultStatus {
+        keyring_available: os_keyring::available(),
+        vault_exists: vault_path(app)?.exists(),
+        vault_unlocked: vault_key()?.is_some(),
+    })
+}
+
+fn vault_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(VAULT_FILE))
+}
+
+fn vault_key() -> Result<MutexGuard<'static, Option<VaultKey>>, AppError> {
+    VAULT.lock().map_err(|_| AppError::Internal("Secret vault state poisoned".to_string()))
+}
+
+fn with_vault<T>(f: impl FnOnce(&VaultKey) -> Result<T, String>) -> Result<T, AppError> {
+    match vault_key()?.as_ref() {
+        Some(key) => Ok(f(key)?),
+        None => Err(AppError::Auth(
+            "No OS keyring available and the secret vault is locked (unlock it with the master password)".to_string(),
+        )),
+    }
+}
+
+fn remove_from_vault(path: &std::path::Path, key: &VaultKey, account: &str) -> Result<(), String> {
+    let mut entries = key.read(path)?;
+    if entries.remove(account).is_some() {
+        key.write(path, &entries)?;
+    }
+    Ok(())
+}
+
+fn load_index<R: Runtime>(app: &AppHandle<R>) -> Result<SecretIndex, String> {
+    settings::load_section(app, SETTINGS_KEY)
+}
+
+/// Compte "<profil>:<type>" ; le profil ne peut pas contenir ':'
+fn account(profile: &str, kind: SecretKind) -> Result<String, AppError> {
+    let profile = profile.trim();
+    if profile.is_empty() || profile.contains(':') {
+        return Err(AppError::Validation(format!("Invalid secret profile '{}'", profile)));
+    }
+    Ok(format!("{}:{}", profile, kind.as_str()))
+}
+
+fn find<'a>(entries: &'a [SecretInfo], profile: &str, kind: SecretKind) -> Option<&'a SecretInfo> {
+    entries.iter().find(|i| i.profile == profile.trim() && i.kind == kind)
+}
+
+fn upsert(entries: &mut Vec<SecretInfo>, info: SecretInfo) {
+    entries.retain(|i| !(i.profile == info.profile && i.kind == info.kind));
+    entries.push(info);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_keeps_one_entry_per_profile_and_kind() {
+        assert_eq!(account(" kasper ", SecretKind::FlexToken).expect("account"), "kasper:flex_token");
+        assert!(account("a:b", SecretKind::IbPassword).is_err());
+        assert!(account("  ", SecretKind::IbPassword).is_err());
+
+        let info = |profile: &str, kind, backend| SecretInfo {
+            profile: profile.into(),
+            kind,
+            backend,
+            updated_at: String::new(),
+        };
+        let mut entries = vec![info("default", SecretKind::FlexToken, SecretBackend::Vault)];
+        upsert(&mut entries, info("default", SecretKind::FlexToken, SecretBackend::Keyring));
+        upsert(&mut entries, info("default", SecretKind::IbUsername, SecretBackend::Keyring));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(find(&entries, "default", SecretKind::FlexToken).map(|i| i.backend), Some(SecretBackend::Keyring));
+        assert!(find(&entries, "kasper", SecretKind::FlexToken).is_none());
+        // L'index sérialisé ne contient que des métadonnées
+        let json = serde_json::to_string(&SecretIndex { entries }).expect("json");
+        assert!(json.contains("\"flex_token\"") && !json.contains("value"));
+    }
+}
@@ -0,0 +1,44 @@
+// Trousseau du système (Keychain, Credential Manager, Secret Service)
+// Entrée : service "TradeVision", compte "<profil>:<type>"
+
+use crate::modules::logging::SECRETS;
+use keyring::{Entry, Error};
+
+const SERVICE: &str = "TradeVision";
+/// Entrée jamais écrite : sert à savoir si le trousseau répond
+const PROBE_ACCOUNT: &str = "__probe__";
+
+fn entry(account: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, account).map_err(|e| format!("Keyring error: {}", e))
+}
+
+/// Faux si aucun trousseau n'est joignable (Linux sans Secret Service, session sans D-Bus)
+pub fn available() -> bool {
+    match entry(PROBE_ACCOUNT).map(|e| e.get_password()) {
+        Ok(Ok(_)) | Ok(Err(Error::NoEntry)) => true,
+        Ok(Err(e)) => {
+            tracing::warn!(target: SECRETS, error = %e, "OS keyring unavailable");
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+pub fn set(account: &str, value: &str) -> Result<(), String> {
+    entry(account)?.set_password(value).map_err(|e| format!("Keyring error: {}", e))
+}
+
+pub fn get(account: &str) -> Result<Option<String>, String> {
+    match entry(account)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Keyring error: {}", e)),
+    }
+}
+
+pub fn delete(account: &str) -> Result<(), String> {
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Keyring error: {}", e)),
+    }
+}
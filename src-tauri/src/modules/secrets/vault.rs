@@ -0,0 +1,128 @@
+// Coffre de secrets de repli (pas de trousseau système) : app_data_dir/secrets.vault
+// Format : MAGIC | sel (16) | nonce (24) | XChaCha20-Poly1305(JSON { "profil:type": valeur }),
+// en-tête authentifié ; clé dérivée du mot de passe maître (Argon2), gardée en mémoire
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const VAULT_FILE: &str = "secrets.vault";
+
+const MAGIC: &[u8; 8] = b"TVSECRT1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const MIN_PASSWORD_LEN: usize = 8;
+
+/// Clé déverrouillée (le mot de passe maître n'est pas conservé)
+pub struct VaultKey {
+    key: [u8; 32],
+    salt: [u8; SALT_LEN],
+}
+
+impl VaultKey {
+    /// Déverrouille le coffre existant, ou le crée vide au premier usage
+    pub fn open(path: &Path, master_password: &str) -> Result<Self, String> {
+        if path.exists() {
+            let bytes = fs::read(path).map_err(|e| format!("Vault read error: {}", e))?;
+            let header = header(&bytes)?;
+            let mut salt = [0u8; SALT_LEN];
+            salt.copy_from_slice(&header[MAGIC.len()..]);
+            let vault = VaultKey { key: derive_key(master_password, &salt)?, salt };
+            vault.decrypt(&bytes)?;
+            return Ok(vault);
+        }
+        if master_password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(format!("Master password must be at least {} characters", MIN_PASSWORD_LEN));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let vault = VaultKey { key: derive_key(master_password, &salt)?, salt };
+        vault.write(path, &BTreeMap::new())?;
+        Ok(vault)
+    }
+
+    pub fn read(&self, path: &Path) -> Result<BTreeMap<String, String>, String> {
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let bytes = fs::read(path).map_err(|e| format!("Vault read error: {}", e))?;
+        let json = self.decrypt(&bytes)?;
+        serde_json::from_slice(&json).map_err(|e| format!("Invalid vault content: {}", e))
+    }
+
+    /// Réécrit tout le coffre (nouveau nonce), de façon atomique
+    pub fn write(&self, path: &Path, entries: &BTreeMap<String, String>) -> Result<(), String> {
+        let json = serde_json::to_vec(entries).map_err(|e| e.to_string())?;
+        let cipher = XChaCha20Poly1305::new(&self.key.into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + json.len() + 16);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.salt);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: &json, aad: &out })
+            .map_err(|_| "Vault encryption failed".to_string())?;
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let tmp = path.with_extension("vault.tmp");
+        fs::write(&tmp, out).map_err(|e| format!("Vault write error: {}", e))?;
+        fs::rename(&tmp, path).map_err(|e| format!("Vault write error: {}", e))
+    }
+
+    fn decrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let header = header(bytes)?;
+        if header[MAGIC.len()..] != self.salt {
+            return Err("Vault was recreated, unlock it again".to_string());
+        }
+        let (nonce, ciphertext) = bytes[header.len()..].split_at(NONCE_LEN);
+        XChaCha20Poly1305::new(&self.key.into())
+            .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+            .map_err(|_| "Wrong master password or corrupted vault".to_string())
+    }
+}
+
+/// MAGIC | sel, après vérification de la taille minimale
+fn header(bytes: &[u8]) -> Result<&[u8], String> {
+    let header_len = MAGIC.len() + SALT_LEN;
+    if bytes.len() < header_len + NONCE_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err("Invalid or truncated secret vault".to_string());
+    }
+    Ok(&bytes[..header_len])
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation error: {}", e))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_round_trip_and_wrong_password() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(VAULT_FILE);
+        assert!(VaultKey::open(&path, "short").is_err());
+
+        let vault = VaultKey::open(&path, "correct horse").expect("create");
+        let entries = BTreeMap::from([("default:flex_token".to_string(), "123456789".to_string())]);
+        vault.write(&path, &entries).expect("write");
+        assert!(!fs::read(&path).expect("bytes").windows(9).any(|w| w == b"123456789"));
+
+        let reopened = VaultKey::open(&path, "correct horse").expect("unlock");
+        assert_eq!(reopened.read(&path).expect("read"), entries);
+        assert!(VaultKey::open(&path, "wrong horse").is_err());
+    }
+}
@@ -0,0 +1,79 @@
+// Réglages backend persistés dans app_config_dir/settings.json (embarqué dans les sauvegardes)
+// Un objet JSON par section ("backup", ...) : les sections inconnues sont conservées telles quelles
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+pub fn settings_path<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Section `key` ; valeur par défaut si le fichier ou la section n'existe pas
+pub fn load_section<R: Runtime, T: DeserializeOwned + Default>(app_handle: &AppHandle<R>, key: &str) -> Result<T, String> {
+    read_section(&settings_path(app_handle)?, key)
+}
+
+pub fn save_section<R: Runtime, T: Serialize>(app_handle: &AppHandle<R>, key: &str, value: &T) -> Result<(), String> {
+    write_section(&settings_path(app_handle)?, key, value)
+}
+
+fn read_section<T: DeserializeOwned + Default>(path: &Path, key: &str) -> Result<T, String> {
+    match read_all(path)?.remove(key) {
+        Some(value) => serde_json::from_value(value).map_err(|e| format!("Invalid settings '{}': {}", key, e)),
+        None => Ok(T::default()),
+    }
+}
+
+fn write_section<T: Serialize>(path: &Path, key: &str, value: &T) -> Result<(), String> {
+    let mut all = read_all(path)?;
+    all.insert(key.to_string(), serde_json::to_value(value).map_err(|e| e.to_string())?);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // Écriture atomique : un crash ne doit pas laisser un settings.json tronqué
+    let tmp = path.with_extension("json.tmp");
+    let json = serde_json::to_string_pretty(&Value::Object(all)).map_err(|e| e.to_string())?;
+    fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+fn read_all(path: &Path) -> Result<Map<String, Value>, String> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    match serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", SETTINGS_FILE, e))? {
+        Value::Object(map) => Ok(map),
+        _ => Err(format!("Invalid {}: expected an object", SETTINGS_FILE)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Section {
+        enabled: bool,
+    }
+
+    #[test]
+    fn test_sections_are_independent() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(SETTINGS_FILE);
+        assert_eq!(read_section::<Section>(&path, "backup").expect("default"), Section::default());
+
+        fs::write(&path, r#"{"theme":"dark"}"#).expect("write");
+        write_section(&path, "backup", &Section { enabled: true }).expect("save");
+        assert_eq!(read_section::<Section>(&path, "backup").expect("load"), Section { enabled: true });
+        assert!(fs::read_to_string(&path).expect("read").contains("\"theme\": \"dark\""));
+    }
+}
@@ -0,0 +1,46 @@
+// Commandes Tauri de la sync Flex : réglages, statut, sync immédiate, multi-query et reprise d'historique
+
+use super::{FlexJob, FlexSyncOutcome, SyncAllReport, SyncRunResult, SyncSettings, SyncStatus, DEFAULT_BACKFILL_YEARS};
+use crate::modules::error::AppError;
+
+/// Commande Tauri: Réglages de la sync Flex planifiée (section "sync" de settings.json)
+#[tauri::command]
+pub fn get_sync_settings(app_handle: tauri::AppHandle) -> Result<SyncSettings, AppError> {
+    super::load_settings(&app_handle).map_err(AppError::Internal)
+}
+
+#[tauri::command]
+pub fn set_sync_settings(app_handle: tauri::AppHandle, settings: SyncSettings) -> Result<(), AppError> {
+    super::save_settings(&app_handle, &settings).map_err(AppError::Validation)
+}
+
+/// Commande Tauri: Dernière / prochaine sync planifiée (sync_metadata)
+#[tauri::command]
+pub fn get_sync_status(app_handle: tauri::AppHandle) -> Result<SyncStatus, AppError> {
+    super::status(&app_handle).map_err(AppError::Database)
+}
+
+/// Commande Tauri: Sync Flex immédiate ; le résultat est aussi émis sur l'event "ib-sync"
+#[tauri::command]
+pub async fn run_ib_sync(app_handle: tauri::AppHandle) -> Result<SyncRunResult, AppError> {
+    super::run_now(&app_handle).await
+}
+
+/// Commande Tauri: Reprise de l'historique Flex année par année (progression : event "ib-backfill-progress")
+#[tauri::command]
+pub async fn backfill_flex_history(
+    app_handle: tauri::AppHandle,
+    years: Option<u32>,
+) -> Result<FlexSyncOutcome, AppError> {
+    super::run_backfill(&app_handle, years.unwrap_or(DEFAULT_BACKFILL_YEARS)).await
+}
+
+/// Commande Tauri: Plusieurs (profil, query) en parallèle dans les limites IBKR,
+/// exécutions fusionnées par compte + statut de chaque job
+#[tauri::command]
+pub async fn sync_all_flex(
+    app_handle: tauri::AppHandle,
+    jobs: Option<Vec<FlexJob>>,
+) -> Result<SyncAllReport, AppError> {
+    super::run_all(&app_handle, jobs).await
+}
@@ -0,0 +1,207 @@
+// Sync Flex incrémentale : périodes fd/td à partir de la date synchronisée par compte
+// (voir marks.rs), et reprise d'historique année par année
+// (le Flex Web Service limite chaque requête à 365 jours)
+
+use super::{BACKFILL_EVENT, SYNC_LOCK};
+use crate::modules::audit::Actor;
+use crate::modules::dates::parse_trade_date;
+use crate::modules::flex_trades::{self, ImportReport};
+use crate::modules::logging::SYNC;
+use crate::modules::tws_socket::{FlexTrade, TWSConfig, TWSSyncClient};
+use crate::modules::db;
+use crate::modules::error::AppError;
+use chrono::{Duration, Local, NaiveDate, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Jours couverts par une requête Flex (bornes incluses)
+pub const MAX_RANGE_DAYS: i64 = 365;
+/// Pause entre deux requêtes du même token (10 requêtes / minute côté IBKR)
+pub(super) const REQUEST_PAUSE: std::time::Duration = std::time::Duration::from_secs(6);
+/// La reprise s'arrête après deux années consécutives sans exécution
+const EMPTY_YEARS_BEFORE_STOP: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DateRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FlexSyncOutcome {
+    pub report: ImportReport,
+    /// Périodes demandées ; vide = période enregistrée dans la query (première sync)
+    pub ranges: Vec<DateRange>,
+    pub executions: usize,
+}
+
+/// Progression de la reprise d'historique, une fois par année importée
+#[derive(Debug, Clone, Serialize)]
+pub struct BackfillProgress {
+    pub range: DateRange,
+    pub executions: usize,
+    pub report: ImportReport,
+}
+
+/// Périodes consécutives de MAX_RANGE_DAYS au plus couvrant [from, to]
+pub fn windows(from: NaiveDate, to: NaiveDate) -> Vec<DateRange> {
+    let mut ranges = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = (start + Duration::days(MAX_RANGE_DAYS - 1)).min(to);
+        ranges.push(DateRange { from: start, to: end });
+        start = end + Duration::days(1);
+    }
+    ranges
+}
+
+/// Nouveaux jours seulement : depuis la plus ancienne date synchronisée (jour inclus, le relevé
+/// de ce jour pouvait être incomplet) ; première sync = période de la query
+pub fn incremental_ranges(marks: &BTreeMap<String, NaiveDate>, today: NaiveDate) -> Vec<DateRange> {
+    match marks.values().min() {
+        Some(from) => windows((*from).min(today), today),
+        None => Vec::new(),
+    }
+}
+
+/// Reprise de l'historique complet : années successives en remontant depuis aujourd'hui,
+/// jusqu'à `max_years` ou deux années vides de suite
+pub async fn backfill<R: Runtime>(
+    app: &AppHandle<R>,
+    flex_token: &str,
+    query_id: i32,
+    max_years: u32,
+) -> Result<FlexSyncOutcome, AppError> {
+    let _guard = SYNC_LOCK.lock().await;
+    let client = TWSSyncClient::new(TWSConfig::default());
+    let mut outcome = FlexSyncOutcome::default();
+    let mut to = Local::now().date_naive();
+    let mut empty_years = 0;
+
+    for year in 0..max_years {
+        if year > 0 {
+            tokio::time::sleep(REQUEST_PAUSE).await;
+        }
+        let range = DateRange { from: to - Duration::days(MAX_RANGE_DAYS - 1), to };
+        let executions = client.get_flex_executions(flex_token, query_id, Some((range.from, range.to))).await?;
+        let report = import(app, &executions)?;
+        let count = executions.len();
+        tracing::info!(target: SYNC, from = %range.from, to = %range.to, executions = count, "Backfill year imported");
+        let progress = BackfillProgress { range, executions: executions.len(), report: report.clone() };
+        if let Err(e) = app.emit(BACKFILL_EVENT, progress) {
+            tracing::warn!(target: SYNC, error = %e, "Backfill event not emitted");
+        }
+
+        outcome.ranges.push(range);
+        outcome.executions += executions.len();
+        outcome.report.merge(report);
+        empty_years = if executions.is_empty() { empty_years + 1 } else { 0 };
+        if empty_years >= EMPTY_YEARS_BEFORE_STOP {
+            break;
+        }
+        to = range.from - Duration::days(1);
+    }
+    Ok(outcome)
+}
+
+/// Import (trades clôturés) puis avancée des marqueurs par compte (exécutions ouvertes comprises)
+pub(super) fn import<R: Runtime>(app: &AppHandle<R>, executions: &[FlexTrade]) -> Result<ImportReport, AppError> {
+    let report = flex_trades::import_flex_trades(app, executions, &HashMap::new(), Actor::Sync)
+        .map_err(AppError::Database)?;
+    db::open_connection(app)
+        .and_then(|conn| record_high_water_marks(&conn, executions))
+        .map_err(AppError::Database)?;
+    Ok(report)
+}
+
+/// Date du dernier trade importé par compte (sync_metadata.last_trade_date, informative) ;
+/// n'avance que vers l'avant (une reprise d'historique ne le fait pas reculer)
+pub fn record_high_water_marks(conn: &Connection, executions: &[FlexTrade]) -> Result<(), String> {
+    let mut latest: BTreeMap<&str, NaiveDate> = BTreeMap::new();
+    for t in executions.iter().filter(|t| !t.account_id.is_empty()) {
+        if let Some(date) = parse_trade_date(&t.date) {
+            let entry = latest.entry(t.account_id.as_str()).or_insert(date);
+            *entry = (*entry).max(date);
+        }
+    }
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+    for (account, date) in latest {
+        conn.execute(
+            "INSERT INTO sync_metadata (account_id, last_sync_date, last_trade_date) VALUES (?1, ?2, ?3)
+             ON CONFLICT(account_id) DO UPDATE SET last_sync_date = excluded.last_sync_date,
+               last_trade_date = MAX(COALESCE(last_trade_date, ''), excluded.last_trade_date)",
+            params![account, now, date.format("%Y-%m-%d").to_string()],
+        )
+        .map_err(|e| format!("sync_metadata update error: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::migrations::{migrate, MIGRATIONS};
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").expect("date")
+    }
+
+    fn execution(account: &str, date: &str) -> FlexTrade {
+        FlexTrade {
+            account_id: account.into(),
+            trade_id: format!("{}-{}", account, date),
+            symbol: "SPY".into(),
+            asset_class: "STK".into(),
+            side: "BUY".into(),
+            quantity: 1,
+            multiplier: 1,
+            price: 1.0,
+            commission: 0.0,
+            realized_pnl: 0.0,
+            date: date.into(),
+            time: String::new(),
+            expiry: String::new(),
+            strike: 0.0,
+            put_call: String::new(),
+            open_close: "O".into(),
+            exchange: String::new(),
+            proceeds: 0.0,
+            cost_basis: 0.0,
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_windows_respect_the_365_day_limit() {
+        let ranges = windows(day("2023-01-01"), day("2025-01-10"));
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0], DateRange { from: day("2023-01-01"), to: day("2023-12-31") });
+        assert_eq!(ranges[2], DateRange { from: day("2024-12-31"), to: day("2025-01-10") });
+        assert_eq!(windows(day("2025-01-10"), day("2025-01-10")).len(), 1);
+    }
+
+    #[test]
+    fn test_high_water_marks_only_move_forward() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("schema");
+        let executions = [execution("U1", "20250612"), execution("U1", "20250610"), execution("U2", "2025-06-05")];
+        record_high_water_marks(&conn, &executions).expect("record");
+        // Reprise d'un historique plus ancien : marqueurs inchangés
+        record_high_water_marks(&conn, &[execution("U1", "20240101")]).expect("backfill");
+
+        let last: String = conn
+            .query_row("SELECT last_trade_date FROM sync_metadata WHERE account_id = 'U1'", [], |r| r.get(0))
+            .expect("U1");
+        assert_eq!(last, "2025-06-12");
+    }
+
+    #[test]
+    fn test_ranges_start_at_the_oldest_synced_date() {
+        let marks = BTreeMap::from([("U1".to_string(), day("2025-06-12")), ("U2".to_string(), day("2025-06-05"))]);
+        let ranges = incremental_ranges(&marks, day("2025-06-14"));
+        assert_eq!(ranges, vec![DateRange { from: day("2025-06-05"), to: day("2025-06-14") }]);
+        assert!(incremental_ranges(&BTreeMap::new(), day("2025-06-14")).is_empty());
+    }
+}
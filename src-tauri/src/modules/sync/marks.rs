@@ -0,0 +1,95 @@
+// Date jusqu'à laquelle chaque job Flex (profil, query) a synchronisé chaque compte IBKR :
+// fin de la dernière période demandée avec succès, avancée même sans exécution revenue
+// (un compte inactif ne fait pas redemander le même trou à chaque sync)
+
+use super::multi::FlexJob;
+use crate::modules::tws_socket::FlexTrade;
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Date synchronisée par compte pour ce job ; vide : le job n'a encore renvoyé aucun compte
+pub(super) fn read_job_marks(conn: &Connection, job: &FlexJob) -> Result<BTreeMap<String, NaiveDate>, String> {
+    let mut stmt = conn
+        .prepare("SELECT account_id, synced_through FROM flex_sync_marks WHERE profile = ?1 AND query_id = ?2")
+        .map_err(|e| format!("flex_sync_marks query error: {}", e))?;
+    let rows = stmt
+        .query_map(params![job.profile, job.query_id], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))
+        .map_err(|e| format!("flex_sync_marks query error: {}", e))?;
+    let mut marks = BTreeMap::new();
+    for row in rows {
+        let (account, date) = row.map_err(|e| e.to_string())?;
+        if let Ok(date) = NaiveDate::parse_from_str(&date, DATE_FORMAT) {
+            marks.insert(account, date);
+        }
+    }
+    Ok(marks)
+}
+
+/// Comptes couverts par un job réussi : renvoyés cette fois ou déjà connus, hors imports en échec
+pub(super) fn synced_accounts<'a>(
+    executions: &'a [FlexTrade],
+    known: &'a BTreeMap<String, NaiveDate>,
+    failed: &BTreeSet<&str>,
+) -> BTreeSet<&'a str> {
+    executions
+        .iter()
+        .map(|t| t.account_id.as_str())
+        .chain(known.keys().map(String::as_str))
+        .filter(|a| !a.is_empty() && !failed.contains(a))
+        .collect()
+}
+
+/// La date n'avance que vers l'avant (une période plus ancienne ne la fait pas reculer)
+pub(super) fn record_job_marks(
+    conn: &Connection,
+    job: &FlexJob,
+    accounts: &BTreeSet<&str>,
+    through: NaiveDate,
+) -> Result<(), String> {
+    let through = through.format(DATE_FORMAT).to_string();
+    for account in accounts {
+        conn.execute(
+            "INSERT INTO flex_sync_marks (profile, query_id, account_id, synced_through) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(profile, query_id, account_id)
+             DO UPDATE SET synced_through = MAX(synced_through, excluded.synced_through)",
+            params![job.profile, job.query_id, account, through],
+        )
+        .map_err(|e| format!("flex_sync_marks update error: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::migrations::{migrate, MIGRATIONS};
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DATE_FORMAT).expect("date")
+    }
+
+    #[test]
+    fn test_idle_accounts_advance_and_failed_imports_do_not() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("schema");
+        let job = FlexJob { profile: "rocket".into(), flex_token: String::new(), query_id: 1 };
+        let accounts = BTreeSet::from(["U1", "U2"]);
+        record_job_marks(&conn, &job, &accounts, day("2025-06-10")).expect("first sync");
+
+        // Aucune exécution revenue : les comptes connus avancent quand même, sauf import en échec
+        let known = read_job_marks(&conn, &job).expect("marks");
+        let synced = synced_accounts(&[], &known, &BTreeSet::from(["U2"]));
+        assert_eq!(synced, BTreeSet::from(["U1"]));
+        record_job_marks(&conn, &job, &synced, day("2025-06-14")).expect("idle sync");
+        record_job_marks(&conn, &job, &synced, day("2025-01-01")).expect("older period");
+
+        let marks = read_job_marks(&conn, &job).expect("marks");
+        assert_eq!(marks.get("U1"), Some(&day("2025-06-14")));
+        assert_eq!(marks.get("U2"), Some(&day("2025-06-10")));
+        let other = FlexJob { query_id: 2, ..job };
+        assert!(read_job_marks(&conn, &other).expect("marks").is_empty());
+    }
+}
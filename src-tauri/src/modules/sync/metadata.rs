@@ -0,0 +1,58 @@
+// Ligne Flex de sync_metadata : dernière sync (écrite par l'import) et prochaine exécution planifiée
+
+use crate::modules::flex_trades::SYNC_ACCOUNT;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// (last_sync_date, next_sync_date) de la ligne Flex
+pub(super) fn read_metadata(conn: &Connection) -> Result<(Option<String>, Option<String>), String> {
+    conn.query_row(
+        "SELECT last_sync_date, next_sync_date FROM sync_metadata WHERE account_id = ?1",
+        [SYNC_ACCOUNT],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )
+    .optional()
+    .map(Option::unwrap_or_default)
+    .map_err(|e| format!("sync_metadata query error: {}", e))
+}
+
+pub(super) fn write_next_run(conn: &Connection, next: Option<DateTime<Utc>>) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO sync_metadata (account_id, next_sync_date) VALUES (?1, ?2)
+         ON CONFLICT(account_id) DO UPDATE SET next_sync_date = excluded.next_sync_date",
+        params![SYNC_ACCOUNT, next.map(timestamp)],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("sync_metadata update error: {}", e))
+}
+
+pub(super) fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Horodatages écrits par Rust ou par l'ancien code JS (toISOString)
+pub(super) fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw.trim()).ok().map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::audit::Actor;
+    use crate::modules::flex_trades;
+    use crate::modules::migrations::{migrate, MIGRATIONS};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_next_run_survives_import_and_last_sync_is_read_back() {
+        let mut conn = Connection::open_in_memory().expect("db");
+        migrate(&mut conn, MIGRATIONS).expect("schema");
+        let next = parse_timestamp("2025-06-17T02:30:00.000Z");
+        write_next_run(&conn, next).expect("next");
+        flex_trades::upsert(&mut conn, &[], &HashMap::new(), Actor::Sync).expect("import");
+
+        let (last, next_run) = read_metadata(&conn).expect("metadata");
+        assert!(last.as_deref().and_then(parse_timestamp).is_some());
+        assert_eq!(next_run.as_deref(), Some("2025-06-17T02:30:00.000Z"));
+    }
+}
@@ -0,0 +1,231 @@
+// Synchronisation Flex planifiée côté Rust, indépendante de la webview
+// Règles jours + heure (défaut : lun-ven après 22:30 New York, relevé du jour définitif),
+// rattrapage au lancement des créneaux manqués, dernière / prochaine exécution dans
+// sync_metadata, résultat de chaque exécution poussé au frontend (event SYNC_EVENT)
+// Seuls les jours nouveaux sont demandés à Flex (voir incremental.rs) ; les tokens sont
+// lus dans le magasin de secrets, jamais dans settings.json (voir tokens.rs)
+
+pub mod commands;
+mod incremental;
+mod marks;
+mod metadata;
+mod multi;
+mod rules;
+mod scheduler;
+mod tokens;
+
+pub use incremental::{backfill, BackfillProgress, DateRange, FlexSyncOutcome};
+pub use multi::{sync_all, AccountSummary, FlexJob, JobStatus, SyncAllReport};
+pub use rules::{is_due, latest_slot, next_slot, SyncRule};
+pub use scheduler::spawn;
+
+use crate::modules::error::AppError;
+use crate::modules::flex_trades::ImportReport;
+use crate::modules::logging::{self, SYNC};
+use crate::modules::secrets::{self, SecretKind};
+use crate::modules::{db, settings};
+use chrono::Utc;
+use metadata::{read_metadata, write_next_run};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tokens::{has_tokens, migrate_legacy_tokens, migrate_tokens, resolve_jobs};
+use tokio::sync::Mutex;
+use tracing::Instrument;
+
+/// Section "sync" de settings.json
+pub const SETTINGS_KEY: &str = "sync";
+/// Event écouté par le frontend (horodatage de la barre de titre, toast)
+pub const SYNC_EVENT: &str = "ib-sync";
+/// Progression de la reprise d'historique (une année importée)
+pub const BACKFILL_EVENT: &str = "ib-backfill-progress";
+/// Années remontées par défaut par la reprise d'historique
+pub const DEFAULT_BACKFILL_YEARS: u32 = 10;
+
+/// Profil de la query principale (query_id)
+const PRIMARY_PROFILE: &str = secrets::DEFAULT_PROFILE;
+
+/// Une seule sync à la fois (planifiée ou manuelle) ; tenu aussi pendant une restauration
+pub(crate) static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Ancien emplacement du token principal : migré vers le magasin de secrets, jamais sérialisé
+    #[serde(default, skip_serializing)]
+    pub flex_token: String,
+    #[serde(default)]
+    pub query_id: i32,
+    #[serde(default = "default_rules")]
+    pub rules: Vec<SyncRule>,
+    /// Queries supplémentaires (cash, confirmations, comptes d'une autre académie)
+    #[serde(default)]
+    pub jobs: Vec<FlexJob>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_rules() -> Vec<SyncRule> {
+    vec![SyncRule::default()]
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self { enabled: true, flex_token: String::new(), query_id: 0, rules: default_rules(), jobs: Vec::new() }
+    }
+}
+
+impl SyncSettings {
+    pub fn is_configured(&self) -> bool {
+        !self.all_jobs().is_empty()
+    }
+
+    /// Query principale (profil "default") + queries supplémentaires renseignées (tokens non résolus)
+    pub fn all_jobs(&self) -> Vec<FlexJob> {
+        let primary = FlexJob {
+            profile: PRIMARY_PROFILE.to_string(),
+            flex_token: self.flex_token.clone(),
+            query_id: self.query_id,
+        };
+        std::iter::once(primary)
+            .chain(self.jobs.iter().cloned())
+            .filter(|j| !j.profile.trim().is_empty() && j.query_id > 0)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncTrigger {
+    Scheduled,
+    /// Créneau manqué pendant que l'application était fermée
+    CatchUp,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncRunResult {
+    /// Identifiant repris par chaque ligne de log de l'exécution (panneau diagnostics)
+    pub correlation_id: String,
+    pub trigger: SyncTrigger,
+    pub started_at: String,
+    pub finished_at: String,
+    pub report: Option<ImportReport>,
+    /// Périodes fd/td demandées par job (JobStatus::ranges)
+    pub jobs: Vec<JobStatus>,
+    pub error: Option<String>,
+    pub next_run: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatus {
+    pub enabled: bool,
+    pub configured: bool,
+    pub rules: Vec<SyncRule>,
+    pub last_sync: Option<String>,
+    pub next_run: Option<String>,
+}
+
+/// Réglages sans token ; un token encore en clair est migré (settings.json réécrit sans lui)
+pub fn load_settings<R: Runtime>(app_handle: &AppHandle<R>) -> Result<SyncSettings, String> {
+    let mut sync: SyncSettings = settings::load_section(app_handle, SETTINGS_KEY)?;
+    migrate_legacy_tokens(app_handle, &mut sync)?;
+    Ok(sync)
+}
+
+/// Règles validées avant écriture ; un token fourni va dans le magasin de secrets ;
+/// la prochaine exécution est recalculée aussitôt
+pub fn save_settings<R: Runtime>(app_handle: &AppHandle<R>, sync: &SyncSettings) -> Result<(), String> {
+    for rule in &sync.rules {
+        rule.validate()?;
+    }
+    let mut sync = sync.clone();
+    migrate_tokens(app_handle, &mut sync)?;
+    settings::save_section(app_handle, SETTINGS_KEY, &sync)?;
+    let next = if sync.enabled { next_slot(&sync.rules, Utc::now())? } else { None };
+    write_next_run(&db::open_connection(app_handle)?, next)
+}
+
+/// État affiché par l'interface ; `configured` exige un token dans le magasin de secrets
+pub fn status<R: Runtime>(app_handle: &AppHandle<R>) -> Result<SyncStatus, String> {
+    let sync = load_settings(app_handle)?;
+    let conn = db::open_connection(app_handle)?;
+    let (last_sync, next_run) = read_metadata(&conn)?;
+    Ok(SyncStatus {
+        enabled: sync.enabled,
+        configured: sync.is_configured() && has_tokens(app_handle, &sync),
+        rules: sync.rules,
+        last_sync,
+        next_run,
+    })
+}
+
+/// Sync immédiate (bouton "Synchroniser maintenant")
+pub async fn run_now<R: Runtime>(app_handle: &AppHandle<R>) -> Result<SyncRunResult, AppError> {
+    let sync = load_settings(app_handle)?;
+    if !sync.is_configured() || !has_tokens(app_handle, &sync) {
+        return Err(AppError::Auth("Flex token and query id are not configured".to_string()));
+    }
+    Ok(scheduler::run(app_handle, &sync, SyncTrigger::Manual).await)
+}
+
+/// Tous les jobs donnés (défaut : ceux des réglages), résultats fusionnés par compte
+pub async fn run_all<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    jobs: Option<Vec<FlexJob>>,
+) -> Result<SyncAllReport, AppError> {
+    let jobs = match jobs {
+        Some(jobs) => resolve_jobs(app_handle, jobs),
+        None => resolve_jobs(app_handle, load_settings(app_handle)?.all_jobs()),
+    };
+    if jobs.is_empty() {
+        return Err(AppError::Validation("No Flex query configured".to_string()));
+    }
+    let _guard = SYNC_LOCK.lock().await;
+    let correlation_id = logging::correlation_id();
+    let span = tracing::info_span!(target: SYNC, "sync_run", correlation_id = %correlation_id, trigger = "all");
+    sync_all(app_handle, &jobs).instrument(span).await
+}
+
+/// Reprise de l'historique complet du compte (query principale), année par année
+pub async fn run_backfill<R: Runtime>(app_handle: &AppHandle<R>, years: u32) -> Result<FlexSyncOutcome, AppError> {
+    let sync = load_settings(app_handle)?;
+    let token = secrets::get(app_handle, PRIMARY_PROFILE, SecretKind::FlexToken)?;
+    let token = token.unwrap_or_default();
+    if token.is_empty() || sync.query_id <= 0 {
+        return Err(AppError::Auth("Flex token and query id are not configured".to_string()));
+    }
+    let correlation_id = logging::correlation_id();
+    let span = tracing::info_span!(target: SYNC, "backfill", correlation_id = %correlation_id, years);
+    backfill(app_handle, &token, sync.query_id, years.max(1)).instrument(span).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_defaults_to_weekdays_after_new_york_close() {
+        let sync: SyncSettings = serde_json::from_str(r#"{"flex_token":"t","query_id":42}"#).expect("json");
+        assert!(sync.enabled && sync.is_configured());
+        assert_eq!(sync.rules, vec![SyncRule::default()]);
+        assert!(!SyncSettings::default().is_configured());
+    }
+
+    #[test]
+    fn test_all_jobs_starts_with_the_primary_query() {
+        let sync: SyncSettings = serde_json::from_str(
+            r#"{"flex_token":"t","query_id":42,"jobs":[
+                {"profile":"kasper","flex_token":"k","query_id":7},
+                {"profile":"incomplete","query_id":0}]}"#,
+        )
+        .expect("json");
+        let profiles: Vec<(String, i32)> = sync.all_jobs().into_iter().map(|j| (j.profile, j.query_id)).collect();
+        assert_eq!(profiles, vec![("default".to_string(), 42), ("kasper".to_string(), 7)]);
+        // Anciens réglages : les tokens lus ne sont jamais réécrits ni renvoyés
+        let json = serde_json::to_string(&sync).expect("json");
+        assert!(!json.contains("flex_token") && json.contains("\"query_id\":42"));
+    }
+}
@@ -0,0 +1,295 @@
+// Sync de plusieurs Flex Queries (trades, cash, confirmations) et de plusieurs comptes
+// (une académie par profil) en une commande : les jobs de tokens différents tournent en
+// parallèle, ceux d'un même token à la suite (limite IBKR par token), puis les exécutions
+// sont dédoublonnées et importées compte par compte
+
+use super::incremental::{import, incremental_ranges, DateRange, REQUEST_PAUSE};
+use super::marks::{read_job_marks, record_job_marks, synced_accounts};
+use crate::modules::db;
+use crate::modules::error::AppError;
+use crate::modules::flex_trades::ImportReport;
+use crate::modules::logging::SYNC;
+use crate::modules::tws_socket::{FlexTrade, TWSConfig, TWSSyncClient};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Tokens interrogés simultanément
+const MAX_CONCURRENT_TOKENS: usize = 4;
+
+/// (profil, query) ; le profil regroupe les comptes d'une académie et désigne son token
+/// dans le magasin de secrets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlexJob {
+    pub profile: String,
+    /// Résolu depuis le magasin de secrets ; accepté en entrée (anciens réglages), jamais sérialisé
+    #[serde(default, skip_serializing)]
+    pub flex_token: String,
+    pub query_id: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub profile: String,
+    pub query_id: i32,
+    pub success: bool,
+    pub executions: usize,
+    pub accounts: Vec<String>,
+    /// Périodes fd/td demandées à la query (vide : sa propre période, job encore sans compte)
+    pub ranges: Vec<DateRange>,
+    /// Erreur typée (code Flex, `retryable`) : 1018 / 1019 peuvent être relancés
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSummary {
+    pub account_id: String,
+    pub profiles: Vec<String>,
+    /// Exécutions après dédoublonnage entre queries
+    pub executions: usize,
+    pub report: ImportReport,
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncAllReport {
+    pub jobs: Vec<JobStatus>,
+    pub accounts: Vec<AccountSummary>,
+    /// Cumul de tous les comptes
+    pub report: ImportReport,
+}
+
+impl SyncAllReport {
+    /// Jobs ou imports en échec, None si tout a réussi
+    pub fn errors(&self) -> Option<String> {
+        let errors: Vec<String> = self
+            .jobs
+            .iter()
+            .filter_map(|j| j.error.as_ref().map(|e| format!("{} #{}: {}", j.profile, j.query_id, e)))
+            .chain(self.accounts.iter().filter_map(|a| a.error.as_ref().map(|e| format!("{}: {}", a.account_id, e))))
+            .collect();
+        (!errors.is_empty()).then(|| errors.join("; "))
+    }
+}
+
+pub async fn sync_all<R: Runtime>(app: &AppHandle<R>, jobs: &[FlexJob]) -> Result<SyncAllReport, AppError> {
+    let today = Local::now().date_naive();
+    let marks = db::open_connection(app)
+        .and_then(|conn| jobs.iter().map(|job| read_job_marks(&conn, job)).collect::<Result<Vec<_>, _>>())
+        .map_err(AppError::Database)?;
+    // Périodes propres à chaque job : un nouveau profil ou une nouvelle query part de sa période
+    let ranges: Vec<Vec<DateRange>> = marks.iter().map(|m| incremental_ranges(m, today)).collect();
+    let fetched = fetch_all(jobs, &ranges).await;
+
+    let mut report = SyncAllReport::default();
+    for ((job, result), ranges) in jobs.iter().zip(&fetched).zip(ranges) {
+        let accounts: BTreeSet<String> = result.iter().flatten().map(|t| t.account_id.clone()).collect();
+        report.jobs.push(JobStatus {
+            profile: job.profile.clone(),
+            query_id: job.query_id,
+            success: result.is_ok(),
+            executions: result.as_ref().map_or(0, Vec::len),
+            accounts: accounts.into_iter().collect(),
+            ranges,
+            error: result.as_ref().err().cloned(),
+        });
+    }
+
+    for (account_id, (profiles, executions)) in merge_by_account(jobs, &fetched) {
+        let imported = import(app, &executions);
+        if let Err(e) = &imported {
+            tracing::error!(target: SYNC, account = %account_id, error = %e, "Account import failed");
+        }
+        report.accounts.push(AccountSummary {
+            account_id,
+            profiles: profiles.into_iter().collect(),
+            executions: executions.len(),
+            error: imported.as_ref().err().cloned(),
+            report: imported.unwrap_or_default(),
+        });
+    }
+
+    // Jobs réussis : leurs comptes sont synchronisés jusqu'à aujourd'hui, exécutions ou non
+    let failed: BTreeSet<&str> =
+        report.accounts.iter().filter(|a| a.error.is_some()).map(|a| a.account_id.as_str()).collect();
+    let recorded = db::open_connection(app).and_then(|conn| {
+        jobs.iter().zip(&fetched).zip(&marks).try_for_each(|((job, result), known)| match result {
+            Ok(executions) => record_job_marks(&conn, job, &synced_accounts(executions, known, &failed), today),
+            Err(_) => Ok(()),
+        })
+    });
+    if let Err(e) = recorded {
+        tracing::warn!(target: SYNC, error = %e, "Flex sync marks not updated");
+    }
+    for account in &report.accounts {
+        report.report.merge(account.report.clone());
+    }
+    Ok(report)
+}
+
+/// Résultat de chaque job (périodes `ranges[i]` pour `jobs[i]`), dans l'ordre de `jobs`
+async fn fetch_all(jobs: &[FlexJob], ranges: &[Vec<DateRange>]) -> Vec<Result<Vec<FlexTrade>, AppError>> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_TOKENS));
+    let mut tasks = JoinSet::new();
+
+    for (token, indices) in group_by_token(jobs) {
+        let group: Vec<(usize, i32, Vec<Request>)> =
+            indices.iter().map(|&i| (i, jobs[i].query_id, requests(&ranges[i]))).collect();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let client = TWSSyncClient::new(TWSConfig::default());
+            let mut results = Vec::new();
+            let mut first = true;
+            for (index, query_id, requests) in group {
+                let mut executions = Vec::new();
+                let mut outcome = Ok(());
+                for range in &requests {
+                    if !first {
+                        tokio::time::sleep(REQUEST_PAUSE).await;
+                    }
+                    first = false;
+                    match client.get_flex_executions(&token, query_id, *range).await {
+                        Ok(batch) => executions.extend(batch),
+                        Err(e) => {
+                            outcome = Err(e);
+                            break;
+                        }
+                    }
+                }
+                results.push((index, outcome.map(|_| executions)));
+            }
+            results
+        });
+    }
+
+    let mut fetched: Vec<Result<Vec<FlexTrade>, AppError>> = jobs
+        .iter()
+        .map(|j| {
+            if j.flex_token.is_empty() {
+                Err(AppError::Auth(format!("No Flex token saved for profile '{}'", j.profile)))
+            } else {
+                Err(AppError::Internal("Job not run".to_string()))
+            }
+        })
+        .collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(results) => results.into_iter().for_each(|(i, r)| fetched[i] = r),
+            Err(e) => tracing::error!(target: SYNC, error = %e, "Flex task failed"),
+        }
+    }
+    fetched
+}
+
+/// Période fd/td d'une requête Flex ; None : période enregistrée dans la query
+type Request = Option<(NaiveDate, NaiveDate)>;
+
+fn requests(ranges: &[DateRange]) -> Vec<Request> {
+    if ranges.is_empty() {
+        vec![None]
+    } else {
+        ranges.iter().map(|r| Some((r.from, r.to))).collect()
+    }
+}
+
+/// Jobs regroupés par token, dans l'ordre d'apparition (jobs sans token écartés)
+fn group_by_token(jobs: &[FlexJob]) -> Vec<(String, Vec<usize>)> {
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for (i, job) in jobs.iter().enumerate().filter(|(_, j)| !j.flex_token.is_empty()) {
+        match groups.iter_mut().find(|(token, _)| *token == job.flex_token) {
+            Some((_, indices)) => indices.push(i),
+            None => groups.push((job.flex_token.clone(), vec![i])),
+        }
+    }
+    groups
+}
+
+/// Exécutions des jobs réussis par compte, dédoublonnées par trade_id (queries qui se recoupent)
+fn merge_by_account(
+    jobs: &[FlexJob],
+    fetched: &[Result<Vec<FlexTrade>, AppError>],
+) -> BTreeMap<String, (BTreeSet<String>, Vec<FlexTrade>)> {
+    let mut merged: BTreeMap<String, (BTreeSet<String>, Vec<FlexTrade>)> = BTreeMap::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for (job, result) in jobs.iter().zip(fetched) {
+        for trade in result.iter().flatten() {
+            let entry = merged.entry(trade.account_id.clone()).or_default();
+            entry.0.insert(job.profile.clone());
+            if trade.trade_id.is_empty() || seen.insert((trade.account_id.clone(), trade.trade_id.clone())) {
+                entry.1.push(trade.clone());
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(profile: &str, token: &str, query_id: i32) -> FlexJob {
+        FlexJob { profile: profile.into(), flex_token: token.into(), query_id }
+    }
+
+    fn trade(account: &str, id: &str) -> FlexTrade {
+        FlexTrade {
+            account_id: account.into(),
+            trade_id: id.into(),
+            symbol: "SPY".into(),
+            asset_class: "STK".into(),
+            side: "BUY".into(),
+            quantity: 1,
+            multiplier: 1,
+            price: 1.0,
+            commission: 0.0,
+            realized_pnl: 0.0,
+            date: "20250612".into(),
+            time: String::new(),
+            expiry: String::new(),
+            strike: 0.0,
+            put_call: String::new(),
+            open_close: "C".into(),
+            exchange: String::new(),
+            proceeds: 0.0,
+            cost_basis: 0.0,
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_jobs_sharing_a_token_run_in_sequence() {
+        let jobs = [job("rocket", "A", 1), job("kasper", "B", 2), job("rocket", "A", 3), job("new", "", 4)];
+        assert_eq!(group_by_token(&jobs), vec![("A".to_string(), vec![0, 2]), ("B".to_string(), vec![1])]);
+        // Le token ne repart jamais vers la webview
+        assert_eq!(serde_json::to_string(&jobs[0]).expect("json"), r#"{"profile":"rocket","query_id":1}"#);
+    }
+
+    #[test]
+    fn test_job_without_marks_requests_its_own_period() {
+        let day = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").expect("date");
+        let known = BTreeMap::from([("U1".to_string(), day("2025-06-10"))]);
+        let today = day("2025-06-14");
+        assert_eq!(requests(&incremental_ranges(&known, today)), vec![Some((day("2025-06-10"), today))]);
+        assert_eq!(requests(&incremental_ranges(&BTreeMap::new(), today)), vec![None]);
+    }
+
+    #[test]
+    fn test_merge_dedups_overlapping_queries_per_account() {
+        let jobs = [job("rocket", "A", 1), job("rocket", "A", 2), job("kasper", "B", 3)];
+        let fetched = vec![
+            Ok(vec![trade("U1", "t1"), trade("U1", "t2")]),
+            Ok(vec![trade("U1", "t2")]), // confirmations : même exécution
+            Err(AppError::FlexService { code: 1020, message: "Invalid token".into() }),
+        ];
+        let merged = merge_by_account(&jobs, &fetched);
+        assert_eq!(merged.len(), 1);
+        let (profiles, executions) = &merged["U1"];
+        assert_eq!(profiles.iter().collect::<Vec<_>>(), vec!["rocket"]);
+        assert_eq!(executions.len(), 2);
+    }
+}
@@ -0,0 +1,214 @@
+// Règles de planification façon cron : jours de la semaine + heure dans un fuseau
+// ("mon-fri" après 22:30 America/New_York = relevé Flex du jour définitif)
+
+use crate::modules::dates::{new_york_to_utc, utc_to_new_york};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+const NEW_YORK: &str = "America/New_York";
+const DAYS: [(&str, Weekday); 7] = [
+    ("mon", Weekday::Mon),
+    ("tue", Weekday::Tue),
+    ("wed", Weekday::Wed),
+    ("thu", Weekday::Thu),
+    ("fri", Weekday::Fri),
+    ("sat", Weekday::Sat),
+    ("sun", Weekday::Sun),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncRule {
+    /// "mon-fri", "mon,wed,fri" ou "*"
+    #[serde(default = "default_days")]
+    pub days: String,
+    /// Heure "HH:MM" du fuseau à partir de laquelle la sync du jour est due
+    #[serde(default = "default_after")]
+    pub after: String,
+    /// "America/New_York", "UTC" ou "local"
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+}
+
+fn default_days() -> String {
+    "mon-fri".to_string()
+}
+
+fn default_after() -> String {
+    "22:30".to_string()
+}
+
+fn default_timezone() -> String {
+    NEW_YORK.to_string()
+}
+
+impl Default for SyncRule {
+    fn default() -> Self {
+        Self { days: default_days(), after: default_after(), timezone: default_timezone() }
+    }
+}
+
+impl SyncRule {
+    pub fn validate(&self) -> Result<(), String> {
+        self.weekdays()?;
+        self.time()?;
+        self.local_date(Utc::now())?;
+        Ok(())
+    }
+
+    /// Créneau le plus récent ≤ `now`
+    pub fn previous_slot(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+        let today = self.local_date(now)?;
+        for back in 0..=7 {
+            if let Some(slot) = self.slot(today - Duration::days(back))? {
+                if slot <= now {
+                    return Ok(Some(slot));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Premier créneau > `now`
+    pub fn next_slot(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+        let today = self.local_date(now)?;
+        for ahead in 0..=8 {
+            if let Some(slot) = self.slot(today + Duration::days(ahead))? {
+                if slot > now {
+                    return Ok(Some(slot));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn slot(&self, date: NaiveDate) -> Result<Option<DateTime<Utc>>, String> {
+        if !self.weekdays()?.contains(&date.weekday()) {
+            return Ok(None);
+        }
+        self.to_utc(date.and_time(self.time()?)).map(Some)
+    }
+
+    fn weekdays(&self) -> Result<Vec<Weekday>, String> {
+        let spec = self.days.trim().to_lowercase();
+        if spec == "*" {
+            return Ok(DAYS.iter().map(|(_, d)| *d).collect());
+        }
+        let index = |name: &str| {
+            DAYS.iter()
+                .position(|(n, _)| *n == name.trim())
+                .ok_or_else(|| format!("Invalid day '{}' in sync rule '{}'", name.trim(), self.days))
+        };
+        let mut days = Vec::new();
+        for part in spec.split(',') {
+            match part.split_once('-') {
+                Some((from, to)) => {
+                    let (from, to) = (index(from)?, index(to)?);
+                    if from > to {
+                        return Err(format!("Invalid day range '{}' in sync rule", part.trim()));
+                    }
+                    days.extend(DAYS[from..=to].iter().map(|(_, d)| *d));
+                }
+                None => days.push(DAYS[index(part)?].1),
+            }
+        }
+        Ok(days)
+    }
+
+    fn time(&self) -> Result<NaiveTime, String> {
+        NaiveTime::parse_from_str(self.after.trim(), "%H:%M")
+            .map_err(|_| format!("Invalid time '{}' in sync rule (expected HH:MM)", self.after))
+    }
+
+    fn local_date(&self, now: DateTime<Utc>) -> Result<NaiveDate, String> {
+        match self.timezone.as_str() {
+            NEW_YORK => Ok(utc_to_new_york(now).date()),
+            "UTC" => Ok(now.date_naive()),
+            "local" => Ok(now.with_timezone(&Local).date_naive()),
+            other => Err(format!("Unsupported timezone '{}' (America/New_York, UTC or local)", other)),
+        }
+    }
+
+    fn to_utc(&self, local: NaiveDateTime) -> Result<DateTime<Utc>, String> {
+        match self.timezone.as_str() {
+            NEW_YORK => Ok(new_york_to_utc(local)),
+            "UTC" => Ok(local.and_utc()),
+            "local" => Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+                .ok_or_else(|| format!("{} does not exist in the local timezone", local)),
+            other => Err(format!("Unsupported timezone '{}' (America/New_York, UTC or local)", other)),
+        }
+    }
+}
+
+/// Dernier créneau passé, toutes règles confondues
+pub fn latest_slot(rules: &[SyncRule], now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    let mut latest = None;
+    for rule in rules {
+        latest = latest.max(rule.previous_slot(now)?);
+    }
+    Ok(latest)
+}
+
+/// Prochain créneau, toutes règles confondues
+pub fn next_slot(rules: &[SyncRule], now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    let mut next: Option<DateTime<Utc>> = None;
+    for slot in rules.iter().map(|r| r.next_slot(now)) {
+        if let Some(slot) = slot? {
+            next = Some(next.map_or(slot, |n| n.min(slot)));
+        }
+    }
+    Ok(next)
+}
+
+/// Due si un créneau est passé depuis la dernière sync (rattrape les créneaux manqués)
+pub fn is_due(last_sync: Option<DateTime<Utc>>, latest_slot: Option<DateTime<Utc>>) -> bool {
+    latest_slot.is_some_and(|slot| last_sync.is_none_or(|last| last < slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).expect("rfc3339").with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_weekday_slots_after_new_york_close() {
+        let rule = SyncRule::default();
+        // Samedi 14 juin 2025 : dernier créneau vendredi 22:30 EDT, prochain lundi
+        let now = utc("2025-06-14T12:00:00Z");
+        assert_eq!(rule.previous_slot(now).expect("rule"), Some(utc("2025-06-14T02:30:00Z")));
+        assert_eq!(rule.next_slot(now).expect("rule"), Some(utc("2025-06-17T02:30:00Z")));
+
+        // Lundi 23:00 à Paris = 17:00 à New York : le créneau du jour n'est pas encore passé
+        let monday = utc("2025-06-16T21:00:00Z");
+        assert_eq!(rule.previous_slot(monday).expect("rule"), Some(utc("2025-06-14T02:30:00Z")));
+    }
+
+    #[test]
+    fn test_due_only_when_a_slot_passed_since_last_sync() {
+        let rules = [SyncRule::default(), SyncRule { days: "sat".into(), after: "08:00".into(), timezone: "UTC".into() }];
+        let now = utc("2025-06-14T12:00:00Z");
+        let latest = latest_slot(&rules, now).expect("rules");
+        assert_eq!(latest, Some(utc("2025-06-14T08:00:00Z")));
+        assert!(is_due(None, latest));
+        assert!(is_due(Some(utc("2025-06-14T03:00:00Z")), latest));
+        assert!(!is_due(Some(utc("2025-06-14T09:00:00Z")), latest));
+        assert_eq!(next_slot(&rules, now).expect("rules"), Some(utc("2025-06-17T02:30:00Z")));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let bad = |days: &str, after: &str, timezone: &str| {
+            SyncRule { days: days.into(), after: after.into(), timezone: timezone.into() }.validate().is_err()
+        };
+        assert!(bad("mon-xyz", "22:30", NEW_YORK));
+        assert!(bad("fri-mon", "22:30", NEW_YORK));
+        assert!(bad("mon", "25:00", NEW_YORK));
+        assert!(bad("mon", "22:30", "Europe/Paris"));
+        assert!(!bad("mon, wed,fri", "06:00", "local"));
+    }
+}
@@ -0,0 +1,103 @@
+// Boucle planifiée : créneau passé depuis la dernière sync → exécution, nouvel essai
+// après RETRY_AFTER_MINUTES en cas d'échec ; chaque exécution a son span "sync_run"
+// (identifiant de corrélation repris par toutes ses lignes de log)
+
+use super::metadata::{parse_timestamp, read_metadata, timestamp, write_next_run};
+use super::tokens::{has_tokens, resolve_jobs};
+use super::{
+    is_due, latest_slot, load_settings, next_slot, sync_all, SyncAllReport, SyncRunResult, SyncSettings, SyncTrigger,
+    SYNC_EVENT, SYNC_LOCK,
+};
+use crate::modules::db;
+use crate::modules::logging::{self, SYNC};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::Instrument;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Délai avant de retenter une sync planifiée en échec (Flex indisponible, token expiré)
+const RETRY_AFTER_MINUTES: i64 = 30;
+
+/// Lance la boucle planifiée (appelé depuis setup, après les migrations)
+pub fn spawn<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut trigger = SyncTrigger::CatchUp;
+        let mut last_failure: Option<DateTime<Utc>> = None;
+        loop {
+            match check(&app, trigger, last_failure).await {
+                Ok(Some(result)) if result.error.is_some() => last_failure = Some(Utc::now()),
+                Ok(Some(_)) => last_failure = None,
+                Ok(None) => {}
+                Err(e) => tracing::error!(target: SYNC, error = %e, "Scheduler error"),
+            }
+            trigger = SyncTrigger::Scheduled;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Exécute la sync si un créneau est passé depuis la dernière (manuelle comprise)
+async fn check<R: Runtime>(
+    app: &AppHandle<R>,
+    trigger: SyncTrigger,
+    last_failure: Option<DateTime<Utc>>,
+) -> Result<Option<SyncRunResult>, String> {
+    let sync = load_settings(app)?;
+    if !sync.enabled {
+        return Ok(None);
+    }
+    let now = Utc::now();
+    let last_sync = {
+        let conn = db::open_connection(app)?;
+        write_next_run(&conn, next_slot(&sync.rules, now)?)?;
+        read_metadata(&conn)?.0.as_deref().and_then(parse_timestamp)
+    };
+    if !is_due(last_sync, latest_slot(&sync.rules, now)?) {
+        return Ok(None);
+    }
+    if !sync.is_configured() || !has_tokens(app, &sync) {
+        if trigger == SyncTrigger::CatchUp {
+            tracing::warn!(target: SYNC, "Scheduled sync skipped: Flex token / query id not configured");
+        }
+        return Ok(None);
+    }
+    if last_failure.is_some_and(|at| (now - at).num_minutes() < RETRY_AFTER_MINUTES) {
+        return Ok(None);
+    }
+    Ok(Some(run(app, &sync, trigger).await))
+}
+
+pub(super) async fn run<R: Runtime>(app: &AppHandle<R>, sync: &SyncSettings, trigger: SyncTrigger) -> SyncRunResult {
+    let _guard = SYNC_LOCK.lock().await;
+    let correlation_id = logging::correlation_id();
+    let span = tracing::info_span!(target: SYNC, "sync_run", correlation_id = %correlation_id, trigger = ?trigger);
+    let started_at = timestamp(Utc::now());
+    let outcome = sync_all(app, &resolve_jobs(app, sync.all_jobs())).instrument(span.clone()).await;
+    let _entered = span.enter();
+
+    let next = if sync.enabled { next_slot(&sync.rules, Utc::now()).ok().flatten() } else { None };
+    if let Err(e) = db::open_connection(app).and_then(|conn| write_next_run(&conn, next)) {
+        tracing::warn!(target: SYNC, error = %e, "sync_metadata not updated");
+    }
+    let result = SyncRunResult {
+        correlation_id,
+        trigger,
+        started_at,
+        finished_at: timestamp(Utc::now()),
+        next_run: next.map(timestamp),
+        error: outcome.as_ref().map_or_else(|e| Some(e.to_string()), SyncAllReport::errors),
+        jobs: outcome.as_ref().map(|o| o.jobs.clone()).unwrap_or_default(),
+        report: outcome.ok().map(|o| o.report),
+    };
+    if let Some(r) = &result.report {
+        tracing::info!(target: SYNC, inserted = r.inserted, updated = r.updated, "Sync finished");
+    }
+    if let Some(e) = &result.error {
+        tracing::error!(target: SYNC, error = %e, "Sync failed");
+    }
+    if let Err(e) = app.emit(SYNC_EVENT, &result) {
+        tracing::warn!(target: SYNC, error = %e, "Sync event not emitted");
+    }
+    result
+}
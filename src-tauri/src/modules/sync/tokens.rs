@@ -0,0 +1,62 @@
+// Tokens Flex des jobs : lus dans le magasin de secrets au moment de la sync,
+// migrés hors de settings.json s'ils y figurent encore en clair (anciennes versions)
+
+use super::{FlexJob, SyncSettings, SETTINGS_KEY};
+use crate::modules::logging::SYNC;
+use crate::modules::secrets::{self, SecretKind};
+use crate::modules::settings;
+use tauri::{AppHandle, Runtime};
+
+/// Déplace les tokens en clair vers le magasin de secrets ; vrai si les réglages ont changé
+pub(super) fn migrate_tokens<R: Runtime>(app: &AppHandle<R>, sync: &mut SyncSettings) -> Result<bool, String> {
+    let mut migrated = false;
+    if !sync.flex_token.is_empty() {
+        secrets::save(app, secrets::DEFAULT_PROFILE, SecretKind::FlexToken, &sync.flex_token)?;
+        sync.flex_token.clear();
+        migrated = true;
+    }
+    for job in sync.jobs.iter_mut().filter(|j| !j.flex_token.is_empty()) {
+        secrets::save(app, &job.profile, SecretKind::FlexToken, &job.flex_token)?;
+        job.flex_token.clear();
+        migrated = true;
+    }
+    Ok(migrated)
+}
+
+/// Au chargement : settings.json réécrit sans les tokens une fois ceux-ci enregistrés
+pub(super) fn migrate_legacy_tokens<R: Runtime>(app: &AppHandle<R>, sync: &mut SyncSettings) -> Result<(), String> {
+    match migrate_tokens(app, sync) {
+        Ok(true) => {
+            settings::save_section(app, SETTINGS_KEY, sync)?;
+            tracing::info!(target: SYNC, "Flex tokens moved from settings.json to the secret store");
+        }
+        Ok(false) => {}
+        // Token gardé en clair (pas de trousseau, coffre verrouillé) : nouvel essai au prochain chargement
+        Err(e) => tracing::warn!(target: SYNC, error = %e, "Flex token migration postponed"),
+    }
+    Ok(())
+}
+
+/// Token de chaque job (laissé vide si introuvable : le job est signalé en échec)
+pub(super) fn resolve_jobs<R: Runtime>(app: &AppHandle<R>, jobs: Vec<FlexJob>) -> Vec<FlexJob> {
+    jobs.into_iter()
+        .map(|mut job| {
+            if job.flex_token.is_empty() {
+                match secrets::get(app, &job.profile, SecretKind::FlexToken) {
+                    Ok(token) => job.flex_token = token.unwrap_or_default(),
+                    Err(e) => {
+                        tracing::warn!(target: SYNC, profile = %job.profile, error = %e, "Flex token unavailable")
+                    }
+                }
+            }
+            job
+        })
+        .collect()
+}
+
+/// Au moins un job dont le token est enregistré
+pub(super) fn has_tokens<R: Runtime>(app: &AppHandle<R>, sync: &SyncSettings) -> bool {
+    sync.all_jobs()
+        .iter()
+        .any(|j| !j.flex_token.is_empty() || secrets::contains(app, &j.profile, SecretKind::FlexToken))
+}
@@ -5,8 +5,25 @@ use serde::{Deserialize, Serialize};
 use reqwest::Client as HttpClient;
 use serde_json::Value;
 use std::time::Duration;
+use chrono::NaiveDate;
 use std::collections::HashMap;
 use regex;
+use crate::modules::error::{AppError, FLEX_STATEMENT_IN_PROGRESS};
+use crate::modules::logging::FLEX;
+use crate::modules::redact::{mask_secret, payload, redact};
+
+const FLEX_BASE_URL: &str = "https://ndcdyn.interactivebrokers.com/AccountManagement/FlexWebService";
+/// Une réponse d'erreur Flex tient en quelques lignes ; au-delà c'est un relevé
+const FLEX_ERROR_RESPONSE_MAX_LEN: usize = 2048;
+/// `get_positions` renvoie des positions fictives tant que la connexion ibapi n'est pas branchée :
+/// le risque portefeuille, les alertes et la réconciliation ignorent alors TWS
+pub const SIMULATED_POSITIONS: bool = true;
+
+/// Défaut serde de `include_tws` (risque, scénarios, alertes, réconciliation) : positions TWS
+/// prises en compte d'office seulement une fois réelles
+pub fn default_include_tws() -> bool {
+    !SIMULATED_POSITIONS
+}
 
 /// Configuration pour la connexion TWS/IB Gateway
 #[derive(Clone, Debug)]
@@ -167,8 +184,8 @@ impl TWSSyncClient {
         }
     }
 
-    /// Récupère les positions ouvertes
-    pub async fn get_positions(&self) -> Result<Vec<Position>, String> {
+    /// Récupère les positions ouvertes (simulées, voir SIMULATED_POSITIONS)
+    pub async fn get_positions(&self) -> Result<Vec<Position>, AppError> {
         // En attendant l'implémentation complète avec ibapi (qui nécessite une boucle d'événements),
         // nous retournons des données simulées réalistes basées sur les structures définies.
         // Cela permet au frontend de valider l'intégration UI.
@@ -205,7 +222,7 @@ impl TWSSyncClient {
 
     /// Récupère les executions récentes (simulation pour maintenant)
     /// TODO: Implémenter via ibapi crate une vraie connexion socket
-    pub async fn get_executions(&self) -> Result<Vec<Execution>, String> {
+    pub async fn get_executions(&self) -> Result<Vec<Execution>, AppError> {
         // Connexion socket TCP via ibapi serait ici
         // Pour l'instant, retourne vide (sera implémenté avec ibapi)
         Ok(vec![])
@@ -216,12 +233,12 @@ impl TWSSyncClient {
     /// 🔄 AUTO-RETRY: Max 5 tentatives avec délai de 3s entre chaque (error 1019)
     /// API publique: parse un CSV fourni en string (import fichier local)
     /// Filtre : seuls les trades cl\u00f4tur\u00e9s (open_close = "C" ou vide pour compatibilit\u00e9)
-    pub async fn parse_csv_public(&self, csv_content: String) -> Result<Vec<FlexTrade>, String> {
+    pub async fn parse_csv_public(&self, csv_content: String) -> Result<Vec<FlexTrade>, AppError> {
         let trades = self.parse_flex_csv(csv_content).await?;
         let closed: Vec<FlexTrade> = trades.into_iter()
             .filter(|t| t.open_close.is_empty() || t.open_close.to_uppercase() == "C")
             .collect();
-        eprintln!("[Flex CSV] Filtre open_close=C : {} trades clotures retenus", closed.len());
+        tracing::info!(target: FLEX, closed = closed.len(), "CSV import: closed trades kept (open_close=C)");
         Ok(closed)
     }
 
@@ -229,58 +246,73 @@ impl TWSSyncClient {
         &self,
         flex_token: &str,
         query_id: i32,
-    ) -> Result<Vec<FlexTrade>, String> {
+    ) -> Result<Vec<FlexTrade>, AppError> {
+        // Filtre : uniquement les trades clotures (open_close = "C" ou vide)
+        let closed: Vec<FlexTrade> = self
+            .get_flex_executions(flex_token, query_id, None)
+            .await?
+            .into_iter()
+            .filter(|t| t.open_close.is_empty() || t.open_close.to_uppercase() == "C")
+            .collect();
+        tracing::info!(target: FLEX, closed = closed.len(), "Flex query: closed trades kept (open_close=C)");
+        Ok(closed)
+    }
+
+    /// Toutes les exécutions de la Flex Query (ouvertures comprises), avec relance sur l'erreur 1019
+    /// `range` (du, au) remplace la période enregistrée dans la query (365 jours max. par requête)
+    pub async fn get_flex_executions(
+        &self,
+        flex_token: &str,
+        query_id: i32,
+        range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<Vec<FlexTrade>, AppError> {
         const MAX_RETRIES: u32 = 5;
         const RETRY_DELAY_MS: u64 = 3000;
 
         for attempt in 1..=MAX_RETRIES {
-            eprintln!("[Flex Query] Attempt {}/{}", attempt, MAX_RETRIES);
-            println!("[Flex Query] Attempt {}/{}", attempt, MAX_RETRIES);
+            tracing::debug!(target: FLEX, attempt, max = MAX_RETRIES, "Flex query attempt");
             
-            match self.fetch_flex_trades_single(flex_token, query_id).await {
-                Ok(trades) => {
-                    // Filtre : uniquement les trades clotures (open_close = "C" ou vide)
-                    let closed: Vec<FlexTrade> = trades.into_iter()
-                        .filter(|t| t.open_close.is_empty() || t.open_close.to_uppercase() == "C")
-                        .collect();
-                    eprintln!("[Flex Query] OK: {} closed trades (open_close=C filter applied)", closed.len());
-                    println!("[Flex Query] OK: {} closed trades", closed.len());
-                    return Ok(closed);
-                }
-                Err(e) if e.contains("1019") && attempt < MAX_RETRIES => {
-                    eprintln!("[Flex Query] ⏳ Error 1019 (statement generating)");
-                    eprintln!("[Flex Query] Waiting 3s before retry {} of {}", attempt + 1, MAX_RETRIES);
-                    println!("[Flex Query] ⏳ Attempt {} failed with 1019 - retrying in 3s...", attempt);
+            match self.fetch_flex_trades_single(flex_token, query_id, range).await {
+                Ok(trades) => return Ok(trades),
+                Err(AppError::FlexService { code: FLEX_STATEMENT_IN_PROGRESS, .. }) if attempt < MAX_RETRIES => {
+                    tracing::warn!(target: FLEX, attempt, max = MAX_RETRIES, "Statement still generating (1019), retrying");
                     tokio::time::sleep(tokio::time::Duration::from_millis(RETRY_DELAY_MS)).await;
                     continue;
                 }
                 Err(e) => {
-                    eprintln!("[Flex Query] ❌ Final error: {}", e);
-                    println!("[Flex Query] ❌ Final error: {}", e);
+                    tracing::error!(target: FLEX, error = %e, "Flex query failed");
                     return Err(e);
                 }
             }
         }
 
-        Err("[RETRY FAILED] Max retries (5) reached. IBKR statement still generating. Wait 30s and try again.".to_string())
+        Err(AppError::FlexService {
+            code: FLEX_STATEMENT_IN_PROGRESS,
+            message: format!("Statement still generating after {} attempts, wait 30s and try again", MAX_RETRIES),
+        })
     }
 
-    /// Flux officiel IBKR Flex Web Service (2 étapes)
-    /// Étape 1: SendRequest → ReferenceCode
-    /// Étape 2: GetStatement avec ReferenceCode → données
-    async fn fetch_flex_trades_single(
+    /// Vérifie un token Flex : SendRequest seul (aucun relevé téléchargé)
+    pub async fn check_flex_token(&self, flex_token: &str, query_id: i32) -> Result<(), AppError> {
+        self.send_flex_request(flex_token, query_id, None).await.map(|_| ())
+    }
+
+    /// Étape 1 du Flex Web Service : SendRequest → ReferenceCode
+    async fn send_flex_request(
         &self,
         flex_token: &str,
         query_id: i32,
-    ) -> Result<Vec<FlexTrade>, String> {
-        let base_url = "https://ndcdyn.interactivebrokers.com/AccountManagement/FlexWebService";
-
+        range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<String, AppError> {
         // ── Étape 1 : SendRequest → obtenir le ReferenceCode ──────────────
-        let send_url = format!(
+        let mut send_url = format!(
             "{}/SendRequest?t={}&q={}&v=3",
-            base_url, flex_token, query_id
+            FLEX_BASE_URL, flex_token, query_id
         );
-        eprintln!("[Flex Query] Étape 1: SendRequest pour queryId={}", query_id);
+        if let Some((from, to)) = range {
+            send_url.push_str(&format!("&fd={}&td={}", from.format("%Y%m%d"), to.format("%Y%m%d")));
+        }
+        tracing::info!(target: FLEX, query_id, range = ?range, "SendRequest");
 
         let send_response = self
             .http_client
@@ -288,36 +320,52 @@ impl TWSSyncClient {
             .header("User-Agent", "TradeVision/1.0")
             .send()
             .await
-            .map_err(|e| format!("SendRequest HTTP error: {}", e))?;
+            .map_err(|e| AppError::Network(format!("SendRequest HTTP error: {}", e)))?;
 
         if !send_response.status().is_success() {
             let status = send_response.status();
             let body = send_response.text().await.unwrap_or_default();
-            return Err(format!("SendRequest failed: {} - {}", status, body));
+            return Err(AppError::Network(format!("SendRequest failed: {} - {}", status, redact(&body))));
         }
 
-        let send_body = send_response.text().await.map_err(|e| e.to_string())?;
-        eprintln!("[Flex Query] SendRequest response: {}", &send_body[..send_body.len().min(500)]);
+        let send_body = send_response.text().await.map_err(|e| AppError::Network(e.to_string()))?;
+        tracing::debug!(target: FLEX, response = %payload(&send_body), "SendRequest response");
 
         // Vérifier que SendRequest a réussi
-        if send_body.contains("<Status>Fail</Status>") || send_body.contains("ErrorCode") {
-            return Err(format!("SendRequest rejected: {}", send_body));
+        if let Some(e) = AppError::from_flex_response(&send_body) {
+            return Err(e);
+        }
+        if send_body.contains("<Status>Fail</Status>") {
+            return Err(AppError::Internal(format!("SendRequest rejected: {}", redact(&send_body))));
         }
 
         // Extraire le ReferenceCode
         let reference_code = self.extract_reference_code(&send_body)?;
-        eprintln!("[Flex Query] ReferenceCode obtenu: {}", reference_code);
+        tracing::debug!(target: FLEX, reference_code = %mask_secret(&reference_code), "ReferenceCode received");
+        Ok(reference_code)
+    }
+
+    /// Flux officiel IBKR Flex Web Service (2 étapes)
+    /// Étape 1: SendRequest → ReferenceCode
+    /// Étape 2: GetStatement avec ReferenceCode → données
+    async fn fetch_flex_trades_single(
+        &self,
+        flex_token: &str,
+        query_id: i32,
+        range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<Vec<FlexTrade>, AppError> {
+        let reference_code = self.send_flex_request(flex_token, query_id, range).await?;
 
         // ── Pause : laisser IBKR générer le rapport (recommandé 10s) ──────
-        eprintln!("[Flex Query] Attente 10s pour la génération du rapport...");
+        tracing::debug!(target: FLEX, "Waiting 10s for statement generation");
         tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
 
         // ── Étape 2 : GetStatement avec ReferenceCode ─────────────────────
         let get_url = format!(
             "{}/GetStatement?t={}&q={}&v=3",
-            base_url, flex_token, reference_code
+            FLEX_BASE_URL, flex_token, reference_code
         );
-        eprintln!("[Flex Query] Étape 2: GetStatement avec ReferenceCode={}", reference_code);
+        tracing::info!(target: FLEX, reference_code = %mask_secret(&reference_code), "GetStatement");
 
         let get_response = self
             .http_client
@@ -325,7 +373,7 @@ impl TWSSyncClient {
             .header("User-Agent", "TradeVision/1.0")
             .send()
             .await
-            .map_err(|e| format!("GetStatement HTTP error: {}", e))?;
+            .map_err(|e| AppError::Network(format!("GetStatement HTTP error: {}", e)))?;
 
         let content_type = get_response
             .headers()
@@ -337,37 +385,39 @@ impl TWSSyncClient {
         if !get_response.status().is_success() {
             let status = get_response.status();
             let body = get_response.text().await.unwrap_or_default();
-            return Err(format!("GetStatement failed: {} - {}", status, body));
+            return Err(AppError::Network(format!("GetStatement failed: {} - {}", status, redact(&body))));
         }
 
-        let get_body = get_response.text().await.map_err(|e| e.to_string())?;
+        let get_body = get_response.text().await.map_err(|e| AppError::Network(e.to_string()))?;
 
-        // Vérifier error 1019 (rapport encore en cours de génération)
-        if get_body.contains("1019") {
-            return Err("1019: Statement generation in progress".to_string());
+        // Réponse d'erreur courte au lieu du relevé (1019 : rapport encore en cours de génération)
+        if get_body.len() < FLEX_ERROR_RESPONSE_MAX_LEN {
+            if let Some(e) = AppError::from_flex_response(&get_body) {
+                return Err(e);
+            }
         }
 
-        eprintln!("[Flex Query] GetStatement response ({} chars, type: {})", get_body.len(), content_type);
+        tracing::info!(target: FLEX, chars = get_body.len(), content_type = %content_type, "GetStatement response");
 
         // Parser selon le format retourné
         if content_type.contains("json") {
             self.parse_flex_json(get_body).await
         } else if get_body.trim_start().starts_with('{') {
-            eprintln!("[Flex Query] Format: JSON (détecté par contenu)");
+            tracing::debug!(target: FLEX, format = "json", "Statement format detected from content");
             self.parse_flex_json(get_body).await
         } else if get_body.lines().next().map(|l| l.contains(',')) == Some(true)
             && !get_body.trim_start().starts_with('<')
         {
-            eprintln!("[Flex Query] Format: CSV");
+            tracing::debug!(target: FLEX, format = "csv", "Statement format");
             self.parse_flex_csv(get_body).await
         } else {
-            eprintln!("[Flex Query] Format: XML");
+            tracing::debug!(target: FLEX, format = "xml", "Statement format");
             self.parse_flex_xml(get_body).await
         }
     }
 
     /// Extrait le ReferenceCode de la réponse XML SendRequest
-    fn extract_reference_code(&self, xml: &str) -> Result<String, String> {
+    fn extract_reference_code(&self, xml: &str) -> Result<String, AppError> {
         // Cherche <ReferenceCode>XXX</ReferenceCode>
         if let Some(start) = xml.find("<ReferenceCode>") {
             if let Some(end) = xml.find("</ReferenceCode>") {
@@ -375,13 +425,17 @@ impl TWSSyncClient {
                 return Ok(code);
             }
         }
-        Err("ReferenceCode not found in SendRequest response".to_string())
+        Err(AppError::Parse {
+            line: None,
+            column: None,
+            source_format: "xml".to_string(),
+            message: "ReferenceCode not found in SendRequest response".to_string(),
+        })
     }
 
     /// Parser Flex Query en JSON
-    async fn parse_flex_json(&self, json_str: String) -> Result<Vec<FlexTrade>, String> {
-        let value: Value = serde_json::from_str(&json_str)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    async fn parse_flex_json(&self, json_str: String) -> Result<Vec<FlexTrade>, AppError> {
+        let value: Value = serde_json::from_str(&json_str).map_err(|e| AppError::json("json", &e))?;
 
         let mut trades = Vec::new();
 
@@ -447,9 +501,9 @@ impl TWSSyncClient {
     }
 
     /// Parser Flex Query en XML (format IBKR Activity ou Trade Confirmation)
-    async fn parse_flex_xml(&self, xml_str: String) -> Result<Vec<FlexTrade>, String> {
-        // Log des 2000 premiers chars pour debug
-        println!("[Flex XML] Réponse brute ({} chars): {}", xml_str.len(), &xml_str[..xml_str.len().min(2000)]);
+    async fn parse_flex_xml(&self, xml_str: String) -> Result<Vec<FlexTrade>, AppError> {
+        // Relevé brut masqué, complet en mode debug seulement
+        tracing::debug!(target: FLEX, chars = xml_str.len(), raw = %payload(&xml_str), "Raw XML statement");
         
         let mut trades = Vec::new();
 
@@ -459,7 +513,7 @@ impl TWSSyncClient {
         let trade_regex = regex::Regex::new(r"(?s)<(?:Trade|TradeConfirm)\s+([^/]*/?)>").unwrap();
         
         let all_matches: Vec<_> = trade_regex.captures_iter(&xml_str).collect();
-        println!("[Flex XML] Éléments <Trade>/<TradeConfirm> trouvés: {}", all_matches.len());
+        tracing::debug!(target: FLEX, elements = all_matches.len(), "<Trade>/<TradeConfirm> elements found");
 
         for cap in all_matches {
             let attrs = &cap[1];
@@ -596,15 +650,19 @@ impl TWSSyncClient {
                 self.extract_xml_attr(attrs, "exchange", &mut trade.exchange);
             }
 
-            println!("[Flex XML] Trade parsé: id={} sym={} side={} qty={} price={} pnl={} date={} oc={}",
-                trade.trade_id, trade.symbol, trade.side, trade.quantity, trade.price, trade.realized_pnl, trade.date, trade.open_close);
+            tracing::trace!(
+                target: FLEX,
+                id = %trade.trade_id, symbol = %trade.symbol, side = %trade.side, quantity = trade.quantity,
+                price = trade.price, pnl = trade.realized_pnl, date = %trade.date, open_close = %trade.open_close,
+                "XML trade parsed"
+            );
 
             if !trade.symbol.is_empty() {
                 trades.push(trade);
             }
         }
 
-        println!("[Flex XML] Total trades parsés: {}", trades.len());
+        tracing::info!(target: FLEX, trades = trades.len(), "XML statement parsed");
         Ok(trades)
     }
 
@@ -636,8 +694,8 @@ impl TWSSyncClient {
     /// Supporte deux formats :
     ///   - Format web service : multi-sections, chaque section a son "HEADER","TRNT" suivi de "DATA","TRNT"
     ///   - Format plain CSV   : Col1,Col2,... / val1,val2,...  (export manuel)
-    async fn parse_flex_csv(&self, csv_str: String) -> Result<Vec<FlexTrade>, String> {
-        println!("[Flex CSV] Parsing Activity Statement CSV IBKR ({} chars)", csv_str.len());
+    async fn parse_flex_csv(&self, csv_str: String) -> Result<Vec<FlexTrade>, AppError> {
+        tracing::debug!(target: FLEX, chars = csv_str.len(), "Parsing CSV activity statement");
 
         let mut trades = Vec::new();
         let lines: Vec<&str> = csv_str.lines().collect();
@@ -657,9 +715,17 @@ impl TWSSyncClient {
             };
             let cols = Self::parse_csv_line(first);
             if cols.is_empty() { return Ok(trades); }
-            println!("[Flex CSV] Format détecté: plain CSV (export manuel)");
+            tracing::debug!(target: FLEX, format = "plain", "CSV format detected (manual export)");
             let headers: Vec<String> = cols.iter().map(|s| s.to_lowercase()).collect();
-            println!("[Flex CSV] Colonnes ({}): {:?}", headers.len(), headers);
+            tracing::debug!(target: FLEX, columns = ?headers, "CSV columns");
+            if !headers.iter().any(|h| h.contains("symbol")) {
+                return Err(AppError::Parse {
+                    line: Some(1),
+                    column: None,
+                    source_format: "csv".to_string(),
+                    message: "Symbol column not found in CSV header".to_string(),
+                });
+            }
             let col_offset = 0usize;
             let find = |names: &[&str]| -> Option<usize> {
                 for name in names {
@@ -685,13 +751,13 @@ impl TWSSyncClient {
                     trades.push(t);
                 }
             }
-            println!("[Flex CSV] plain CSV: {} trades", trades.len());
+            tracing::info!(target: FLEX, trades = trades.len(), "Plain CSV parsed");
             return Ok(trades);
         }
 
         // Format multi-sections HEADER/TRNT
         // On maintient un mapping courant de colonnes et on le met à jour à chaque HEADER,TRNT
-        println!("[Flex CSV] Format détecté: multi-sections HEADER/TRNT (web service)");
+        tracing::debug!(target: FLEX, format = "multi-section", "CSV format detected (HEADER/TRNT web service)");
         
         let mut current_headers: Vec<String> = Vec::new();
         let mut data_count = 0usize;
@@ -708,7 +774,8 @@ impl TWSSyncClient {
             if tag0 == "HEADER" && tag1 == "TRNT" {
                 // Nouvelle section : met à jour le mapping de colonnes (offset 2)
                 current_headers = fields.iter().skip(2).map(|s| s.to_lowercase()).collect();
-                println!("[Flex CSV] Section headers ({}): {:?}", current_headers.len(), &current_headers[..current_headers.len().min(8)]);
+                let first = &current_headers[..current_headers.len().min(8)];
+                tracing::debug!(target: FLEX, columns = current_headers.len(), first = ?first, "CSV section headers");
                 continue;
             }
 
@@ -736,7 +803,7 @@ impl TWSSyncClient {
             }
         }
 
-        println!("[Flex CSV] multi-section: data_count={}, trades retenus={}", data_count, trades.len());
+        tracing::info!(target: FLEX, rows = data_count, trades = trades.len(), "Multi-section CSV parsed");
         Ok(trades)
     }
 
@@ -0,0 +1,90 @@
+// Barres historiques Yahoo (v8/chart) : MAE / MFE des trades clôturés (modules/excursions)
+
+use super::Session;
+use crate::modules::error::AppError;
+use crate::modules::market_data::Bar;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Barres OHLC de [from, to[ ; `interval` au format Yahoo ("1d", "60m")
+pub async fn fetch_chart(
+    session: &Session,
+    symbol: &str,
+    interval: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Bar>, AppError> {
+    let url = format!("https://query1.finance.yahoo.com/v8/finance/chart/{}", symbol);
+    let resp = session
+        .client
+        .get(&url)
+        .query(&[
+            ("period1", from.timestamp().to_string()),
+            ("period2", to.timestamp().to_string()),
+            ("interval", interval.to_string()),
+            ("crumb", session.crumb.clone()),
+        ])
+        .send()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    match resp.status() {
+        status if status.is_success() => {}
+        // Symbole inconnu : pas d'historique chez Yahoo, pas une panne
+        reqwest::StatusCode::NOT_FOUND => return Ok(Vec::new()),
+        status @ (reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) => {
+            return Err(AppError::Auth(format!("Yahoo API Error: {} (cookie / crumb refused)", status)));
+        }
+        status => return Err(AppError::Network(format!("Yahoo API Error: {}", status))),
+    }
+
+    let text = resp.text().await.map_err(|e| AppError::Network(e.to_string()))?;
+    let json: Value = serde_json::from_str(&text).map_err(|e| AppError::json("json", &e))?;
+    Ok(parse_chart(&json))
+}
+
+/// Tableaux parallèles timestamp / open / high / low / close ; les barres incomplètes (null) sont omises
+fn parse_chart(json: &Value) -> Vec<Bar> {
+    let result = &json["chart"]["result"][0];
+    let quote = &result["indicators"]["quote"][0];
+    let Some(timestamps) = result["timestamp"].as_array() else { return Vec::new() };
+    timestamps
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| {
+            let value = |key: &str| quote[key][i].as_f64();
+            Some(Bar {
+                time: DateTime::from_timestamp(t.as_i64()?, 0)?,
+                open: value("open")?,
+                high: value("high")?,
+                low: value("low")?,
+                close: value("close")?,
+                volume: value("volume"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chart_skips_incomplete_bars() {
+        let json: Value = serde_json::from_str(
+            r#"{"chart": {"result": [{
+                "timestamp": [1749562200, 1749648600, 1749735000],
+                "indicators": {"quote": [{
+                    "open": [200.1, null, 202.0], "high": [203.5, null, 204.0],
+                    "low": [199.0, null, 201.2], "close": [202.9, null, 203.1], "volume": [1000, null, null]
+                }]}
+            }]}}"#,
+        )
+        .expect("json");
+        let bars = parse_chart(&json);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].time.timestamp(), 1749562200);
+        assert_eq!((bars[1].high, bars[1].low, bars[1].volume), (204.0, 201.2, None));
+        assert!(parse_chart(&serde_json::json!({"chart": {"result": null}})).is_empty());
+    }
+}
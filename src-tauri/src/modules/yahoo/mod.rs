@@ -0,0 +1,232 @@
+// Client Yahoo Finance : cotations (v7/quote), barres historiques (v8/chart, voir bars.rs) et
+// calendrier des dividendes (quoteSummary)
+// Yahoo exige un cookie de session + un crumb pour toutes les requêtes
+
+mod bars;
+
+pub use bars::fetch_chart;
+
+use crate::modules::error::AppError;
+use crate::modules::market_data::{MarketQuote, MarketState, PriceField};
+use chrono::{DateTime, NaiveDate};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Les dividendes US sont trimestriels : dividendRate (annuel) / 4 ≈ montant détaché
+const DIVIDENDS_PER_YEAR: f64 = 4.0;
+
+/// Prochain détachement connu d'un sous-jacent
+#[derive(Debug, Clone, Serialize)]
+pub struct DividendInfo {
+    pub ex_date: NaiveDate,
+    pub amount: f64, // par action
+}
+
+/// Client avec cookies + crumb (crumb vide si Yahoo refuse, la requête est tentée quand même).
+/// Réutilisable tant que Yahoo l'accepte ; un 401/403 (AppError::Auth) impose d'en ouvrir une autre
+pub struct Session {
+    client: reqwest::Client,
+    crumb: String,
+}
+
+impl Session {
+    pub async fn open() -> Result<Self, AppError> {
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .cookie_store(true)
+            .build()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let _ = client.get("https://fc.yahoo.com").send().await;
+
+        let crumb_resp = client
+            .get("https://query1.finance.yahoo.com/v1/test/getcrumb")
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        let crumb = if crumb_resp.status().is_success() {
+            crumb_resp.text().await.map_err(|e| AppError::Network(e.to_string()))?
+        } else {
+            "".to_string()
+        };
+        Ok(Self { client, crumb })
+    }
+}
+
+/// Dernier cours + variation du jour pour chaque symbole reconnu par Yahoo
+pub async fn fetch_quotes(session: &Session, symbols: &[String]) -> Result<HashMap<String, MarketQuote>, AppError> {
+    if symbols.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let joined_symbols = symbols.join(",");
+    let resp = session
+        .client
+        .get("https://query1.finance.yahoo.com/v7/finance/quote")
+        .query(&[
+            ("symbols", joined_symbols.as_str()),
+            ("crumb", session.crumb.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    match resp.status() {
+        status if status.is_success() => {}
+        status @ (reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) => {
+            return Err(AppError::Auth(format!("Yahoo API Error: {} (cookie / crumb refused)", status)));
+        }
+        status => return Err(AppError::Network(format!("Yahoo API Error: {}", status))),
+    }
+
+    let text = resp.text().await.map_err(|e| AppError::Network(e.to_string()))?;
+    let json: Value = serde_json::from_str(&text).map_err(|e| AppError::json("json", &e))?;
+
+    let results = json["quoteResponse"]["result"].as_array().map(Vec::as_slice).unwrap_or_default();
+    Ok(results.iter().filter_map(parse_quote).collect())
+}
+
+/// Prix de la séance régulière ; le prix pré/post-marché n'est retenu (et signalé dans
+/// price_field) que si Yahoo ne donne pas de regularMarketPrice pendant cette séance étendue
+fn parse_quote(item: &Value) -> Option<(String, MarketQuote)> {
+    let symbol = item["symbol"].as_str()?;
+    let number = |key: &str| item[key].as_f64();
+    let time = |key: &str| item[key].as_i64().and_then(|t| DateTime::from_timestamp(t, 0));
+    let market_state = item["marketState"].as_str().and_then(MarketState::from_yahoo);
+
+    let (price, price_field, change_percent, quote_time) = match number("regularMarketPrice") {
+        Some(price) => {
+            let change = number("regularMarketChangePercent");
+            (price, PriceField::Regular, change, time("regularMarketTime"))
+        }
+        None => match market_state {
+            Some(MarketState::Pre) => {
+                let change = number("preMarketChangePercent");
+                (number("preMarketPrice")?, PriceField::PreMarket, change, time("preMarketTime"))
+            }
+            Some(MarketState::Post) => {
+                let change = number("postMarketChangePercent");
+                (number("postMarketPrice")?, PriceField::PostMarket, change, time("postMarketTime"))
+            }
+            _ => return None,
+        },
+    };
+
+    let text = |key: &str| item[key].as_str().map(str::to_string);
+    let quote = MarketQuote {
+        price_field,
+        bid: number("bid").filter(|b| *b > 0.0),
+        ask: number("ask").filter(|a| *a > 0.0),
+        last: number("regularMarketPrice"),
+        previous_close: number("regularMarketPreviousClose"),
+        day_high: number("regularMarketDayHigh"),
+        day_low: number("regularMarketDayLow"),
+        volume: number("regularMarketVolume"),
+        currency: text("currency"),
+        exchange: text("fullExchangeName").or_else(|| text("exchange")),
+        market_state,
+        quote_time,
+        ..MarketQuote::new(price, change_percent.unwrap_or(0.0))
+    };
+    Some((symbol.to_string(), quote))
+}
+
+/// Prochaine date ex-dividende par symbole ; un symbole sans dividende (ou en erreur) est omis
+pub async fn fetch_dividends(symbols: &[String]) -> Result<HashMap<String, DividendInfo>, AppError> {
+    let mut map = HashMap::new();
+    if symbols.is_empty() {
+        return Ok(map);
+    }
+    let Session { client, crumb } = Session::open().await?;
+
+    for symbol in symbols {
+        let url = format!("https://query2.finance.yahoo.com/v10/finance/quoteSummary/{}", symbol);
+        let resp = client
+            .get(&url)
+            .query(&[("modules", "calendarEvents,summaryDetail"), ("crumb", crumb.as_str())])
+            .send()
+            .await;
+        let Ok(resp) = resp else { continue };
+        if !resp.status().is_success() {
+            continue;
+        }
+        if let Some(info) = resp.json::<Value>().await.ok().as_ref().and_then(parse_dividend) {
+            map.insert(symbol.clone(), info);
+        }
+    }
+    Ok(map)
+}
+
+/// Extrait exDividendDate (timestamp unix) et le montant trimestriel d'une réponse quoteSummary
+fn parse_dividend(json: &Value) -> Option<DividendInfo> {
+    let result = &json["quoteSummary"]["result"][0];
+    let timestamp = result["calendarEvents"]["exDividendDate"]["raw"].as_i64()?;
+    let ex_date = DateTime::from_timestamp(timestamp, 0)?.date_naive();
+    let annual = result["summaryDetail"]["dividendRate"]["raw"]
+        .as_f64()
+        .or_else(|| result["summaryDetail"]["trailingAnnualDividendRate"]["raw"].as_f64())?;
+    if annual <= 0.0 {
+        return None;
+    }
+    Some(DividendInfo { ex_date, amount: annual / DIVIDENDS_PER_YEAR })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dividend() {
+        let json: Value = serde_json::from_str(
+            r#"{"quoteSummary": {"result": [{
+                "calendarEvents": {"exDividendDate": {"raw": 1749686400, "fmt": "2025-06-12"}},
+                "summaryDetail": {"dividendRate": {"raw": 2.04}}
+            }]}}"#,
+        )
+        .expect("json");
+        let info = parse_dividend(&json).expect("dividend");
+        assert_eq!(info.ex_date, NaiveDate::from_ymd_opt(2025, 6, 12).expect("date"));
+        assert!((info.amount - 0.51).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_quote_keeps_regular_price_and_labels_extended_sessions() {
+        let json: Value = serde_json::from_str(
+            r#"{"quoteResponse": {"result": [
+                {"symbol": "AAPL", "marketState": "POST", "regularMarketPrice": 190.5,
+                 "regularMarketChangePercent": 1.2, "postMarketPrice": 191.0, "regularMarketTime": 1749672000,
+                 "bid": 190.9, "ask": 0, "regularMarketVolume": 51234567, "currency": "USD",
+                 "fullExchangeName": "NasdaqGS", "regularMarketPreviousClose": 188.2},
+                {"symbol": "TSLA", "marketState": "PRE", "preMarketPrice": 250.0, "preMarketChangePercent": -2.0},
+                {"symbol": "XYZ", "marketState": "CLOSED", "postMarketPrice": 12.0}
+            ]}}"#,
+        )
+        .expect("json");
+        let quotes: HashMap<String, MarketQuote> =
+            json["quoteResponse"]["result"].as_array().expect("results").iter().filter_map(parse_quote).collect();
+
+        let aapl = &quotes["AAPL"];
+        assert_eq!((aapl.price, aapl.price_field), (190.5, PriceField::Regular));
+        assert_eq!(aapl.market_state, Some(MarketState::Post));
+        assert_eq!((aapl.bid, aapl.ask), (Some(190.9), None));
+        assert_eq!(aapl.exchange.as_deref(), Some("NasdaqGS"));
+        assert_eq!(aapl.quote_time.map(|t| t.timestamp()), Some(1749672000));
+
+        let tsla = &quotes["TSLA"];
+        assert_eq!((tsla.price, tsla.price_field, tsla.change_percent), (250.0, PriceField::PreMarket, -2.0));
+        assert!(!quotes.contains_key("XYZ"));
+    }
+
+    #[test]
+    fn test_parse_dividend_without_payout() {
+        let json: Value = serde_json::from_str(
+            r#"{"quoteSummary": {"result": [{"calendarEvents": {}, "summaryDetail": {}}]}}"#,
+        )
+        .expect("json");
+        assert!(parse_dividend(&json).is_none());
+    }
+}
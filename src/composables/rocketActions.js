@@ -1,4 +1,5 @@
 import { computed } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
 import { initDB } from "../utils/db.js";
 import { useRocketStore } from './rocketStore.js';
 import { processRawTrades } from '../utils/rocketProcessing.js';
@@ -16,14 +17,6 @@ export const useRocketActions = () => {
     async function init() {
         try {
             db.value = await initDB();
-            // Migrations (Idempotent)
-            try { await db.value.execute("ALTER TABLE accounts ADD COLUMN alloc_rocket REAL DEFAULT 0"); } catch (e) {}
-            try { await db.value.execute("ALTER TABLE trades ADD COLUMN sub_strategy TEXT"); } catch (e) {}
-            try { await db.value.execute("ALTER TABLE trades ADD COLUMN asset_type TEXT"); } catch (e) {}
-            try { await db.value.execute("ALTER TABLE trades ADD COLUMN broker TEXT"); } catch (e) {}
-            try { await db.value.execute("ALTER TABLE trades ADD COLUMN stop_loss REAL"); } catch (e) {}
-            try { await db.value.execute("ALTER TABLE trades ADD COLUMN entry_stop REAL"); } catch (e) {}
-            try { await db.value.execute("ALTER TABLE trades ADD COLUMN entry_limit REAL"); } catch (e) {}
 
             await loadAccountData();
             await loadActiveTrades();
@@ -218,6 +211,8 @@ export const useRocketActions = () => {
         if (!tradeToDelete.value) return;
         const trade = tradeToDelete.value;
         try {
+            // Sauvegarde préalable : si elle échoue, la suppression est annulée
+            await invoke('backup_before', { operation: 'delete_trade' });
             if (trade.strategy === 'pcs') {
                  await db.value.execute("DELETE FROM legs WHERE trade_id = ?", [trade.id]);
                  await db.value.execute("DELETE FROM trades WHERE id = ?", [trade.id]);
@@ -8,6 +8,7 @@ const allActiveTrades = ref([]);
 const livePositions = ref([]);
 const lastPositionUpdate = ref(null);
 const lastSyncTime = ref(null);
+const lastSyncError = ref(null);
 const isSyncing = ref(false);
 const strategyType = ref('wheel');
 const mmConfig = ref({ capital: 0, alloc_wheel: 0, margin_wheel_pct: 0, alloc_growth: 0, alloc_rocket: 0 });
@@ -33,6 +34,7 @@ export const useRocketStore = () => {
         livePositions,
         lastPositionUpdate,
         lastSyncTime,
+        lastSyncError,
         isSyncing,
         strategyType,
         mmConfig,
@@ -1,6 +1,7 @@
 
 import { computed, watchEffect } from 'vue';
 import { useRocketStore } from './rocketStore.js';
+import { quoteSession, joinSessions } from '../utils/quoteSession.js';
 
 export function useDashboardLogic(kasperAccounts, allKasperEntries, rocketAccount, allActiveTrades, livePrices, priceUtils) {
     
@@ -34,10 +35,12 @@ export function useDashboardLogic(kasperAccounts, allKasperEntries, rocketAccoun
 
     const rocketStatsByStrategy = computed(() => {
         const stats = {
-            wheel: { realizedPl: 0, capitalAllocated: 0, capitalUsed: 0, totalAssigned: 0, totalExpectedPremium: 0, plLatent: 0 },
-            pcs: { realizedPl: 0, capitalAllocated: 0, capitalUsed: 0, totalAssigned: 0, totalExpectedPremium: 0, plLatent: 0 },
-            rockets: { realizedPl: 0, capitalAllocated: 0, capitalUsed: 0, totalAssigned: 0, totalExpectedPremium: 0, plLatent: 0 },
+            wheel: { realizedPl: 0, capitalAllocated: 0, capitalUsed: 0, totalAssigned: 0, totalExpectedPremium: 0, plLatent: 0, plLatentSession: null },
+            pcs: { realizedPl: 0, capitalAllocated: 0, capitalUsed: 0, totalAssigned: 0, totalExpectedPremium: 0, plLatent: 0, plLatentSession: null },
+            rockets: { realizedPl: 0, capitalAllocated: 0, capitalUsed: 0, totalAssigned: 0, totalExpectedPremium: 0, plLatent: 0, plLatentSession: null },
         };
+        // Séances des cotations entrant dans le P/L latent (pré-marché, clôture...)
+        const sessions = { wheel: [], pcs: [], rockets: [] };
         
         // 0. Capital Allocation (Include Margin for Wheel)
         if (rocketAccount.value) {
@@ -116,6 +119,8 @@ export function useDashboardLogic(kasperAccounts, allKasperEntries, rocketAccoun
                         const currentCost = priceUtils.getSpreadPrice(t, true);
                         if (currentCost !== null && t.price !== undefined) {
                             tempPL = (t.price - currentCost) * 100 * t.quantity;
+                            const shortLeg = priceUtils.getOccSymbol({ ...t, strike: t.strike_short, type: 'put' });
+                            sessions.pcs.push(quoteSession(livePrices[shortLeg]));
                         }
                     } else if (t.strategy === 'wheel' && t.type !== 'stock') {
                          const sym = priceUtils.getOccSymbol(t);
@@ -123,6 +128,7 @@ export function useDashboardLogic(kasperAccounts, allKasperEntries, rocketAccoun
                              const currentPrice = livePrices[sym].price;
                              if (currentPrice !== undefined) {
                                  tempPL = (t.price - currentPrice) * 100 * t.quantity;
+                                 sessions.wheel.push(quoteSession(livePrices[sym]));
                              }
                          }
                     } else if (t.strategy === 'rockets' || (t.strategy === 'wheel' && t.type === 'stock')) {
@@ -135,12 +141,16 @@ export function useDashboardLogic(kasperAccounts, allKasperEntries, rocketAccoun
                         
                         if (!isNaN(curr) && !isNaN(entry) && !isNaN(qty)) {
                              tempPL = (curr - entry) * qty;
+                             sessions[t.strategy].push(quoteSession(livePrices[sym]));
                         }
                     }
                 }
                 stats[t.strategy].plLatent += tempPL;
             });
         }
+        Object.keys(sessions).forEach(strat => {
+            stats[strat].plLatentSession = joinSessions(sessions[strat]);
+        });
 
         return stats;
     });
@@ -0,0 +1,42 @@
+import { ref } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+import { describeError } from '../utils/appError.js';
+
+/** Cibles des logs backend (filtre "module" du panneau diagnostics) */
+export const LOG_MODULES = ['app', 'flex', 'tws', 'sync', 'backup', 'market_data', 'alerts', 'secrets'];
+export const LOG_LEVELS = ['error', 'warn', 'info', 'debug', 'trace'];
+
+/**
+ * Dernières lignes des fichiers de logs JSON (plus récentes d'abord), déjà masquées
+ * côté Rust. `correlationId` regroupe les lignes d'une même exécution de sync.
+ */
+export function useDiagnostics() {
+  const logs = ref([]);
+  const loading = ref(false);
+  const error = ref(null);
+
+  /**
+   * @param {{level?: string, module?: string, limit?: number}} [filters]
+   *   level : niveau minimal ("warn" garde warn + error)
+   */
+  async function loadLogs({ level = null, module = null, limit = null } = {}) {
+    loading.value = true;
+    error.value = null;
+    try {
+      const entries = await invoke('get_recent_logs', { level, module, limit });
+      logs.value = entries.map(e => ({ ...e, correlationId: e.correlation_id }));
+    } catch (e) {
+      error.value = describeError(e);
+    } finally {
+      loading.value = false;
+    }
+    return logs.value;
+  }
+
+  /** Lignes de la même exécution (sync, reprise d'historique) */
+  function linesOfRun(correlationId) {
+    return logs.value.filter(e => e.correlationId === correlationId);
+  }
+
+  return { logs, loading, error, loadLogs, linesOfRun };
+}
@@ -1,5 +1,6 @@
 import { ref, computed } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
+import { DEFAULT_PROFILE } from './useSecrets.js'
 
 // Overrides manuels de stratégie — module-level (partagé entre instances)
 // Remis à zéro à chaque nouveau fetchFlexTrades
@@ -25,9 +26,10 @@ export function useFlexQueries() {
     return (winners / trades.value.length * 100).toFixed(1)
   })
 
-  const fetchFlexTrades = async (flexToken, queryId) => {
-    if (!flexToken || !queryId) {
-      throw new Error('Flex Token and Query ID are required')
+  // Token lu côté Rust dans le magasin de secrets (profil)
+  const fetchFlexTrades = async (queryId, profile = DEFAULT_PROFILE) => {
+    if (!queryId) {
+      throw new Error('Query ID is required')
     }
 
     loading.value = true
@@ -36,11 +38,7 @@ export function useFlexQueries() {
     strategyOverrides.value = {}
 
     try {
-      const payload = {
-        flexToken: flexToken,
-        queryId: queryId
-      }
-      const rawTrades = await invoke('fetch_flex_trades', payload)
+      const rawTrades = await invoke('fetch_flex_trades', { queryId, profile })
 
       // Mapper les trades bruts Rust → format analytics
       trades.value = (rawTrades || []).map(t => {
@@ -1,6 +1,8 @@
 import { ref } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import { useRocketStore } from './rocketStore.js';
+import { DEFAULT_PROFILE } from './useSecrets.js';
+import { describeError, isRetryable } from '../utils/appError.js';
 
 const positionTimer = ref(null);
 
@@ -45,32 +47,13 @@ export function useIBSync() {
 
   /**
    * Synchronise les trades depuis IB Gateway Flex Query
+   * Le token est lu côté Rust dans le magasin de secrets (profil)
    * @param {Object} db - Instance Tauri Database
-   * @param {string} flexToken - Flex Query API Token
    * @param {number} queryId - Flex Query ID
+   * @param {string} [profile] - Profil du token (défaut : 'default')
    * @returns {Promise<{success: boolean, count: number, error?: string}>}
    */
-  /**
-   * Détection automatique de la stratégie d'un trade individuel (fallback)
-   * NB: PCS nécessite une analyse de groupe — non détectable ici sur un seul trade.
-   */
-  function detectStrategy(t) {
-    if (t.asset_class === 'CASH') return 'Autre'
-    const isOpt = t.asset_class === 'OPT' || /\d{6}[CP]\d+/.test(t.symbol || '')
-    if (isOpt) {
-      const isCall = t.put_call === 'C' || (t.put_call !== 'P' && /\d{6}C\d+/.test(t.symbol || ''))
-      const isSell = (t.side || '').toUpperCase() === 'SELL'
-      if (isCall) return isSell ? 'Naked Call' : 'Long Call'
-      return isSell ? 'Naked Put' : 'Long Put'
-    }
-    // STK : assignation → Wheel, sinon Rockets
-    const notes = (t.notes || '').toUpperCase()
-    const tokens = notes.split(/[;,\s]+/).filter(Boolean)
-    const isAssigned = tokens.some(c => c === 'A' || c === 'EX' || c === 'ASGN')
-    return isAssigned ? 'Wheel' : 'Rockets'
-  }
-
-  async function syncFromIB(db, flexToken, queryId, strategyOverrides = {}) {
+  async function syncFromIB(db, queryId, strategyOverrides = {}, profile = DEFAULT_PROFILE) {
     if (isSyncing.value) {
       return { success: false, error: 'Sync already in progress' };
     }
@@ -80,58 +63,19 @@ export function useIBSync() {
 
     try {
       // 1. Récupère les FlexTrade complets (20 champs) depuis Rust
-      const rawTrades = await invoke('fetch_flex_trades', {
-        flexToken: flexToken,
-        queryId: queryId
-      });
+      const rawTrades = await invoke('fetch_flex_trades', { queryId, profile });
 
       if (!rawTrades || rawTrades.length === 0) {
         throw new Error('No trades returned from Flex Query');
       }
 
-      // 2. Charger les stratégies déjà en base pour ne pas les écraser
-      let existingStrategies = {};
-      try {
-        const rows = await db.select('SELECT trade_id, strategy FROM flex_trades WHERE strategy IS NOT NULL');
-        for (const r of rows) existingStrategies[r.trade_id] = r.strategy;
-      } catch(e) { /* table inexistante au premier lancement */ }
-
-      // 3. Sauvegarder uniquement les trades CLOTURÉS dans flex_trades
-      let savedCount = 0;
-      let skippedCount = 0;
-      for (const t of rawTrades) {
-        // Ignorer les trades ouverts — ils sont suivis dans open_positions
-        const oc = (t.open_close || '').toUpperCase();
-        if (oc === 'O') { skippedCount++; continue; }
-
-        // Priorité override : paramètre > déjà en DB > auto-détection
-        const strategy = strategyOverrides[t.trade_id] ?? existingStrategies[t.trade_id] ?? detectStrategy(t);
-        try {
-          const res = await db.execute(
-            `INSERT OR IGNORE INTO flex_trades
-             (trade_id, account_id, symbol, asset_class, side, quantity, multiplier,
-              price, commission, realized_pnl, date, time, expiry, strike, put_call,
-              open_close, exchange, proceeds, cost_basis, notes, strategy, synced_at)
-             VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,CURRENT_TIMESTAMP)`,
-            [
-              t.trade_id, t.account_id, t.symbol, t.asset_class, t.side,
-              t.quantity, t.multiplier, t.price, t.commission, t.realized_pnl,
-              t.date, t.time, t.expiry, t.strike, t.put_call,
-              t.open_close, t.exchange, t.proceeds, t.cost_basis, t.notes,
-              strategy
-            ]
-          );
-          // rowsAffected = 0 si INSERT OR IGNORE a ignoré un doublon
-          if (res.rowsAffected > 0) savedCount++;
-          else skippedCount++;
-        } catch (e) {
-          console.warn(`[FlexSync] Could not save trade ${t.trade_id}:`, e.message);
-        }
-      }
+      // 2. Upsert transactionnel côté Rust (trades clôturés uniquement, strategy / is_deleted préservés)
+      const report = await invoke('import_flex_trades', { trades: rawTrades, strategyOverrides, actor: 'sync' });
+      const savedCount = report.inserted;
+      const skippedCount = report.unchanged + report.skipped_open + report.conflicts.length;
 
       tradesCount.value = savedCount;
       lastSyncTime.value = new Date().toISOString();
-      await updateSyncMetadata(db, savedCount);
 
       try {
         await invoke('create_backup');
@@ -139,11 +83,11 @@ export function useIBSync() {
         console.warn('[Backup] Failed auto-backup:', e);
       }
 
-      return { success: true, count: savedCount, skipped: skippedCount };
+      return { success: true, count: savedCount, skipped: skippedCount, report };
     } catch (error) {
-      const errorMsg = error.message || error.toString() || 'Unknown sync error';
+      const errorMsg = describeError(error) || 'Unknown sync error';
       syncError.value = errorMsg;
-      return { success: false, error: errorMsg };
+      return { success: false, error: errorMsg, retryable: isRetryable(error) };
     } finally {
       isSyncing.value = false;
     }
@@ -152,7 +96,7 @@ export function useIBSync() {
   /**
    * Sauvegarde un tableau de trades déjà chargés (CSV import) directement en DB.
    * N'insère que les trades CLOTURÉS (open_close = 'C' ou vide).
-   * INSERT OR IGNORE : ne jamais écraser un trade existant (IBKR = source immuable).
+   * Upsert Rust : corrections IBKR appliquées, strategy / is_deleted de l'utilisateur préservés.
    */
   async function syncFromTrades(db, rawTrades, strategyOverrides = {}) {
     if (isSyncing.value) {
@@ -166,81 +110,16 @@ export function useIBSync() {
     syncError.value = null;
 
     try {
-      // Charger les stratégies déjà en base pour ne pas les écraser
-      let existingStrategies = {};
-      try {
-        const rows = await db.select('SELECT trade_id, strategy FROM flex_trades WHERE strategy IS NOT NULL');
-        for (const r of rows) existingStrategies[r.trade_id] = r.strategy;
-      } catch(e) { /* table inexistante au premier lancement */ }
-
-      let savedCount = 0;
-      let skippedCount = 0;
-      let skippedOpen = 0;
-      let skippedDuplicateId = 0;
-      let skippedInsertIgnore = 0;
-      let errorCount = 0;
-
-      // ── DIAGNOSTIC 1 : compter open_close dans l'input ──
-      const ocGroups = {};
-      for (const t of rawTrades) {
-        const oc = (t.open_close || '(vide)').toUpperCase();
-        ocGroups[oc] = (ocGroups[oc] || 0) + 1;
-      }
-      console.log(`[CSVSync] REÇU: ${rawTrades.length} trades. open_close répartition:`, ocGroups);
-
-      // ── DIAGNOSTIC 2 : collisions de trade_id dans l'input lui-même ──
-      const idSeen = new Map();
-      for (const t of rawTrades) {
-        if (idSeen.has(t.trade_id)) {
-          const prev = idSeen.get(t.trade_id);
-          console.warn(`[CSVSync] COLLISION trade_id="${t.trade_id}" entre:`, prev, '↔', { symbol: t.symbol, date: t.date, side: t.side, qty: t.quantity, price: t.price, strike: t.strike, pc: t.put_call, expiry: t.expiry, oc: t.open_close });
-          skippedDuplicateId++;
-        } else {
-          idSeen.set(t.trade_id, { symbol: t.symbol, date: t.date, side: t.side, qty: t.quantity, price: t.price, strike: t.strike, pc: t.put_call, expiry: t.expiry, oc: t.open_close });
-        }
-      }
-      console.log(`[CSVSync] Collisions trade_id dans input: ${skippedDuplicateId}`);
-
-      for (const t of rawTrades) {
-        // Ignorer les trades ouverts — ils sont suivis dans open_positions
-        const oc = (t.open_close || '').toUpperCase();
-        if (oc === 'O') { skippedOpen++; skippedCount++; continue; }
-
-        const strategy = strategyOverrides[t.trade_id] ?? existingStrategies[t.trade_id] ?? detectStrategy(t);
-        try {
-          const res = await db.execute(
-            `INSERT OR IGNORE INTO flex_trades
-             (trade_id, account_id, symbol, asset_class, side, quantity, multiplier,
-              price, commission, realized_pnl, date, time, expiry, strike, put_call,
-              open_close, exchange, proceeds, cost_basis, notes, strategy, synced_at)
-             VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,CURRENT_TIMESTAMP)`,
-            [
-              t.trade_id, t.account_id ?? null, t.symbol, t.asset_class, t.side,
-              t.quantity, t.multiplier ?? 1, t.price, t.commission, t.realized_pnl,
-              t.date, t.time ?? null, t.expiry ?? null, t.strike ?? null, t.put_call ?? null,
-              t.open_close ?? null, t.exchange ?? null, t.proceeds ?? null, t.cost_basis ?? null,
-              t.notes ?? null, strategy
-            ]
-          );
-          // rowsAffected = 0 si INSERT OR IGNORE a ignoré un doublon
-          if (res.rowsAffected > 0) savedCount++;
-          else { skippedInsertIgnore++; skippedCount++; }
-        } catch (e) {
-          errorCount++;
-          console.warn(`[CSVSync] ERREUR INSERT trade_id="${t.trade_id}" symbol=${t.symbol}:`, e.message);
-        }
-      }
-
-      console.log(`[CSVSync] RÉSULTAT: input=${rawTrades.length} | skippedOpen=${skippedOpen} | skippedInsertIgnore=${skippedInsertIgnore} | errors=${errorCount} | inserted=${savedCount}`);
-      console.log(`[CSVSync] Équation: ${skippedOpen} + ${skippedInsertIgnore} + ${errorCount} + ${savedCount} = ${skippedOpen + skippedInsertIgnore + errorCount + savedCount} (attendu ${rawTrades.length})`);
+      const report = await invoke('import_flex_trades', { trades: rawTrades, strategyOverrides, actor: 'import' });
+      const savedCount = report.inserted;
+      const skippedCount = report.unchanged + report.skipped_open + report.conflicts.length;
 
       tradesCount.value = savedCount;
       lastSyncTime.value = new Date().toISOString();
-      await updateSyncMetadata(db, savedCount);
 
-      return { success: true, count: savedCount, skipped: skippedCount };
+      return { success: true, count: savedCount, skipped: skippedCount, report };
     } catch (error) {
-      const errorMsg = error.message || error.toString() || 'Unknown sync error';
+      const errorMsg = describeError(error) || 'Unknown sync error';
       syncError.value = errorMsg;
       return { success: false, error: errorMsg };
     } finally {
@@ -249,18 +128,56 @@ export function useIBSync() {
   }
 
   /**
-   * Mise à jour du dernier sync timestamp
+   * Réconciliation journal / Flex / TWS (écarts de quantité, jambes manquantes,
+   * positions clôturées chez le courtier, écarts de prix) avec correction suggérée
+   * @param {Object} options - { profile, queryId, includeTws = false, priceTolerancePct }
+   * @returns {Promise<{sources: string[], mismatches: Array, warnings: string[]}>}
    */
-  async function updateSyncMetadata(db, count) {
+  async function reconcilePositions({ profile, queryId, includeTws = false, priceTolerancePct } = {}) {
+    return invoke('reconcile_positions', {
+      request: {
+        profile: profile || null,
+        query_id: queryId ? Number(queryId) : null,
+        include_tws: includeTws,
+        ...(priceTolerancePct !== undefined && { price_tolerance_pct: priceTolerancePct }),
+      },
+    });
+  }
+
+  /**
+   * Reprise de l'historique Flex année par année (365 jours max. par requête IBKR)
+   * Progression émise sur l'event 'ib-backfill-progress'
+   * @param {number} [years] - Années à remonter au plus
+   */
+  async function backfillHistory(years) {
+    isSyncing.value = true;
     try {
-      const now = new Date().toISOString();
-      await db.execute(
-        `INSERT OR REPLACE INTO sync_metadata (account_id, last_sync_date, trades_synced) 
-         VALUES ('FLEX', ?, ?)`,
-        [now, count]
-      );
-    } catch (error) {
-      console.warn('[FlexQuery] Could not update sync metadata:', error.message);
+      const outcome = await invoke('backfill_flex_history', { years: years ?? null });
+      lastSyncTime.value = new Date().toISOString();
+      return outcome;
+    } finally {
+      isSyncing.value = false;
+    }
+  }
+
+  /**
+   * Sync de toutes les Flex Queries (trades, cash, confirmations) de toutes les académies
+   * Les jobs en échec sont résumés dans syncError
+   * @param {Array<{profile: string, query_id: number}>} [jobs] - défaut : réglages de la sync (tokens par profil)
+   * @returns {Promise<{jobs: Array, accounts: Array, report: Object}>}
+   */
+  async function syncAllFlex(jobs) {
+    isSyncing.value = true;
+    try {
+      const summary = await invoke('sync_all_flex', { jobs: jobs ?? null });
+      const failed = summary.jobs.filter(j => !j.success);
+      syncError.value = failed.length
+        ? failed.map(j => `${j.profile} #${j.query_id}: ${describeError(j.error)}`).join(' | ')
+        : null;
+      lastSyncTime.value = new Date().toISOString();
+      return summary;
+    } finally {
+      isSyncing.value = false;
     }
   }
 
@@ -286,5 +203,8 @@ export function useIBSync() {
     startPositionWatch,
     stopPositionWatch,
     getLastSyncInfo,
+    reconcilePositions,
+    backfillHistory,
+    syncAllFlex,
   };
 }
@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import { initDB } from "../utils/db.js";
 import { 
     db, 
@@ -69,86 +70,6 @@ export async function init() {
     
     db.value = await initDB();
     
-    // 1. KASPER ACCOUNTS TABLE
-    await db.value.execute(`
-        CREATE TABLE IF NOT EXISTS kasper_accounts (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            account_number TEXT,
-            initial_capital REAL DEFAULT 0,
-            currency TEXT DEFAULT 'USD',
-            is_default BOOLEAN DEFAULT 0,
-            created_at TEXT DEFAULT CURRENT_TIMESTAMP
-        )
-    `);
-
-    try { await db.value.execute("ALTER TABLE kasper_accounts ADD COLUMN account_number TEXT"); } catch (e) {}
-
-    // Seed default account if none
-    const existingAccounts = await db.value.select("SELECT * FROM kasper_accounts");
-    let defaultAccountId = 1;
-    if (existingAccounts.length === 0) {
-            const res = await db.value.execute("INSERT INTO kasper_accounts (name, initial_capital, is_default) VALUES ('Compte Principal', 5000, 1)");
-            defaultAccountId = res.lastInsertId;
-    } else {
-            const def = existingAccounts.find(a => a.is_default);
-            defaultAccountId = def ? def.id : existingAccounts[0].id;
-    }
-
-    // 2. DAILY JOURNAL MIGRATION (Multi-account support)
-    const tableDef = await db.value.select("SELECT sql FROM sqlite_master WHERE type='table' AND name='kasper_daily_journal'");
-    
-    if (tableDef.length === 0) {
-            await db.value.execute(`
-            CREATE TABLE IF NOT EXISTS kasper_daily_journal (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                account_id INTEGER DEFAULT 1,
-                date TEXT NOT NULL,
-                profit_loss REAL DEFAULT 0,
-                risk_used REAL DEFAULT 0,
-                notes TEXT,
-                details TEXT,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(date, account_id),
-                FOREIGN KEY(account_id) REFERENCES kasper_accounts(id)
-            )
-        `);
-    } else if (!tableDef[0].sql.includes('account_id')) {
-        await db.value.execute("ALTER TABLE kasper_daily_journal RENAME TO kasper_daily_journal_old");
-        await db.value.execute(`
-            CREATE TABLE kasper_daily_journal (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                account_id INTEGER DEFAULT 1,
-                date TEXT NOT NULL,
-                profit_loss REAL DEFAULT 0,
-                risk_used REAL DEFAULT 0,
-                notes TEXT,
-                details TEXT,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(date, account_id),
-                FOREIGN KEY(account_id) REFERENCES kasper_accounts(id)
-            )
-        `);
-        await db.value.execute(`
-            INSERT INTO kasper_daily_journal (date, profit_loss, risk_used, notes, details, created_at, account_id)
-            SELECT date, profit_loss, risk_used, notes, details, created_at, ? 
-            FROM kasper_daily_journal_old
-        `, [defaultAccountId]);
-        await db.value.execute("DROP TABLE kasper_daily_journal_old");
-    }
-
-    // Ensure table exists for Pair Config
-    await db.value.execute(`
-        CREATE TABLE IF NOT EXISTS kasper_pairs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            symbol TEXT NOT NULL,
-            pip_value REAL DEFAULT 1,
-            sl_pips REAL DEFAULT 10,
-            risk_pct REAL DEFAULT 1,
-            created_at TEXT DEFAULT CURRENT_TIMESTAMP
-        )
-    `);
-
     // Init State
     await loadAccountsList();
     
@@ -249,6 +170,9 @@ export async function deleteAccount(id) {
         return;
     }
 
+    // Backup first (throws, and aborts the deletion, if it fails)
+    await invoke('backup_before', { operation: 'delete_kasper_account' });
+
     // Delete associated entries
     await db.value.execute("DELETE FROM kasper_daily_journal WHERE account_id = ?", [id]);
     
@@ -0,0 +1,59 @@
+import { ref } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+
+/** Profil des identifiants IB et de la query Flex principale */
+export const DEFAULT_PROFILE = 'default';
+
+/**
+ * Secrets (token Flex, identifiants IB) stockés côté Rust : trousseau système,
+ * ou coffre chiffré par mot de passe maître. Les valeurs ne reviennent jamais ici,
+ * seul l'index (profil, type, emplacement) est lisible.
+ */
+export function useSecrets() {
+  const secrets = ref([]);
+  const vault = ref(null);
+
+  async function refresh() {
+    secrets.value = await invoke('list_secrets');
+    vault.value = await invoke('get_secret_vault_status');
+    return secrets.value;
+  }
+
+  /**
+   * @param {string} profile
+   * @param {'flex_token'|'ib_username'|'ib_password'|'s3_secret_key'} kind
+   * @param {string} value
+   */
+  async function saveSecret(profile, kind, value) {
+    const info = await invoke('save_secret', { profile, kind, value });
+    await refresh();
+    return info;
+  }
+
+  async function deleteSecret(profile, kind) {
+    await invoke('delete_secret', { profile, kind });
+    await refresh();
+  }
+
+  /** @returns {Promise<Array<{kind: string, ok: boolean, message: string, error: ?Object}>>} */
+  async function testSecret(profile = DEFAULT_PROFILE) {
+    return invoke('test_secret', { profile });
+  }
+
+  function hasSecret(profile, kind) {
+    return secrets.value.some(s => s.profile === profile && s.kind === kind);
+  }
+
+  /** Coffre de repli (aucun trousseau système) : créé au premier déverrouillage */
+  async function unlockVault(masterPassword) {
+    vault.value = await invoke('unlock_secret_vault', { masterPassword });
+    return vault.value;
+  }
+
+  async function lockVault() {
+    await invoke('lock_secret_vault');
+    vault.value = await invoke('get_secret_vault_status');
+  }
+
+  return { secrets, vault, refresh, saveSecret, deleteSecret, testSecret, hasSecret, unlockVault, lockVault };
+}
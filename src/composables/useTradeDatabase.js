@@ -1,5 +1,6 @@
 import { ref, computed } from 'vue'
 import { Database } from '@tauri-apps/plugin-sql'
+import { invoke } from '@tauri-apps/api/core'
 import { useFlexQueries } from './useFlexQueries.js'
 
 let db = null
@@ -186,6 +187,8 @@ export function useTradeDatabase() {
     if (!db) return false
 
     try {
+      // Sauvegarde préalable : si elle échoue, la base n'est pas vidée
+      await invoke('backup_before', { operation: 'clear_flex_trades' })
       await db.execute('DELETE FROM flex_trades')
       dbTrades.value = []
       console.log('✅ Database cleared')
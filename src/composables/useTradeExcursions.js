@@ -0,0 +1,68 @@
+import { ref, computed } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+import { describeError } from '../utils/appError.js';
+
+const average = (values) => {
+  const defined = values.filter(v => v !== null && v !== undefined);
+  return defined.length ? defined.reduce((a, b) => a + b, 0) / defined.length : null;
+};
+
+/**
+ * MAE / MFE des trades clôturés (voir src-tauri/src/modules/excursions) : pire et meilleur
+ * mouvement pendant la détention, part du mouvement capturée (capture_ratio = captured / mfe)
+ * et mouvement laissé après la sortie (left_on_table, sur `post_exit_days` jours).
+ */
+export function useTradeExcursions() {
+  const excursions = ref([]);
+  const report = ref(null);
+  const loading = ref(false);
+  const computing = ref(false);
+  const error = ref(null);
+
+  async function loadExcursions() {
+    loading.value = true;
+    error.value = null;
+    try {
+      excursions.value = await invoke('get_trade_excursions');
+    } catch (e) {
+      error.value = describeError(e);
+    } finally {
+      loading.value = false;
+    }
+    return excursions.value;
+  }
+
+  /**
+   * Charge les barres manquantes (cache SQLite sinon fournisseurs) et calcule les trades non traités.
+   * @param {{interval?: 'day'|'hour', postExitDays?: number, recompute?: boolean}} [options]
+   */
+  async function computeExcursions(options = {}) {
+    computing.value = true;
+    error.value = null;
+    try {
+      report.value = await invoke('compute_trade_excursions', { options });
+      await loadExcursions();
+    } catch (e) {
+      error.value = describeError(e);
+    } finally {
+      computing.value = false;
+    }
+    return report.value;
+  }
+
+  /** Moyennes sur les trades d'une stratégie (toutes si null) */
+  function summaryOf(strategy = null) {
+    const rows = excursions.value.filter(x => !strategy || x.strategy === strategy);
+    return {
+      count: rows.length,
+      maePct: average(rows.map(x => x.mae_pct)),
+      mfePct: average(rows.map(x => x.mfe_pct)),
+      captureRatio: average(rows.map(x => x.capture_ratio)),
+      leftOnTablePct: average(rows.map(x => x.left_on_table_pct)),
+    };
+  }
+
+  const summary = computed(() => summaryOf());
+
+  return { excursions, report, loading, computing, error, summary, summaryOf, loadExcursions, computeExcursions };
+}
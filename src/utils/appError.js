@@ -0,0 +1,57 @@
+// Erreurs des commandes Tauri : objet {code, message, details, retryable} (AppError côté Rust)
+// Le message anglais reste dans la console ; l'utilisateur voit la traduction du code
+
+const MESSAGES = {
+  network: 'Serveur injoignable. Vérifiez la connexion puis réessayez.',
+  auth: 'Identifiants ou token refusés (ou coffre verrouillé). Vérifiez les réglages IBKR.',
+  flex_service: 'Le service Flex IBKR a refusé la requête.',
+  parse: 'Fichier ou réponse illisible.',
+  database: 'Erreur de la base de données locale.',
+  backup: 'La sauvegarde a échoué.',
+  tws_connection: 'TWS / IB Gateway injoignable. Vérifiez qu’il est lancé et connecté.',
+  validation: 'Paramètre invalide.',
+  internal: 'Erreur inattendue.',
+};
+
+/** Codes Flex Web Service les plus fréquents */
+const FLEX_MESSAGES = {
+  1003: 'Relevé indisponible pour le moment.',
+  1009: 'Serveur IBKR surchargé, réessayez dans quelques minutes.',
+  1012: 'Token Flex expiré : générez-en un nouveau dans le portail IBKR.',
+  1013: 'Adresse IP non autorisée pour ce token Flex.',
+  1014: 'Query Flex invalide : vérifiez son identifiant.',
+  1015: 'Token Flex invalide.',
+  1018: 'Trop de requêtes Flex, patientez une minute.',
+  1019: 'Relevé encore en cours de génération, réessayez dans 30 secondes.',
+};
+
+/** Normalise une erreur d'invoke (AppError, ancienne chaîne, Error JS) */
+export function toAppError(error) {
+  if (error && typeof error === 'object' && typeof error.code === 'string' && 'retryable' in error) {
+    return error;
+  }
+  const message = error?.message ?? String(error);
+  return { code: 'internal', message, details: null, retryable: false };
+}
+
+/** Message français à afficher */
+export function describeError(error) {
+  const appError = toAppError(error);
+  if (appError.code === 'flex_service') {
+    const flexCode = appError.details?.flex_code;
+    return FLEX_MESSAGES[flexCode] ?? `${MESSAGES.flex_service} (code ${flexCode})`;
+  }
+  if (appError.code === 'parse' && appError.details?.line) {
+    const { line, column, source_format: format } = appError.details;
+    return `${MESSAGES.parse} ${format.toUpperCase()} ligne ${line}${column ? `, colonne ${column}` : ''}.`;
+  }
+  if (appError.code === 'internal') {
+    return appError.message || MESSAGES.internal;
+  }
+  return MESSAGES[appError.code] ?? appError.message;
+}
+
+/** Vrai si relancer la même commande peut réussir (proposer "Réessayer") */
+export function isRetryable(error) {
+  return toAppError(error).retryable;
+}
@@ -7,6 +7,8 @@ import { detectStrategies } from './ibkr/strategyDetector.js';
 
 /**
  * Réconcilie et prépare les trades pour stockage
+ * @deprecated Dédoublonnage fait par import_flex_trades ; la réconciliation des positions
+ * est faite côté Rust par reconcile_positions (useIBSync().reconcilePositions)
  * @param {Array} trades - Trades bruts d'IB
  * @returns {Array} Trades prêts à être sauvegardés
  */
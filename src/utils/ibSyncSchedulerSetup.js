@@ -1,25 +1,35 @@
 /**
- * Initializes IB sync scheduler on app startup
- * Can be called from App.vue onMounted
+ * Sync IB planifiée : tourne côté Rust (modules/sync), même webview fermée.
+ * Ici on se contente d'écouter les résultats et de transmettre les identifiants Flex
+ * (le token va dans le magasin de secrets Rust, jamais dans settings.json).
+ * Called from App.vue onMounted
  */
 
-import { useIBSyncScheduler } from '../composables/useIBSyncScheduler'
-import { initDB } from '../utils/db'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useRocketStore } from '../composables/rocketStore.js'
+import { DEFAULT_PROFILE } from '../composables/useSecrets.js'
 
-let schedulerInstance = null
+let unlistenSync = null
 
 export async function initIBSyncScheduler() {
   try {
-    const db = await initDB()
-    const scheduler = useIBSyncScheduler()
+    const { lastSyncTime, lastSyncError } = useRocketStore()
+    // Échec affiché par la barre de titre jusqu'à la prochaine sync réussie
+    unlistenSync = await listen('ib-sync', ({ payload }) => {
+      if (payload.error) {
+        lastSyncError.value = `${payload.trigger}: ${payload.error}`
+        return
+      }
+      lastSyncError.value = null
+      lastSyncTime.value = payload.finished_at
+    })
 
-    // Start background sync every 30 minutes
-    await scheduler.initScheduler(db)
-
-    schedulerInstance = scheduler
-    console.log('[App] IB Sync Scheduler initialized - will check sync need every 30 minutes')
-
-    return scheduler
+    const status = await invoke('get_sync_status')
+    if (status.last_sync) lastSyncTime.value = status.last_sync
+    // Identifiants saisis avant la planification côté Rust
+    if (!status.configured) await pushFlexCredentials()
+    return status
   } catch (error) {
     console.error('[App] Failed to initialize IB Sync Scheduler:', error)
     return null
@@ -27,12 +37,38 @@ export async function initIBSyncScheduler() {
 }
 
 export function stopIBSyncScheduler() {
-  if (schedulerInstance) {
-    schedulerInstance.stopScheduler()
-    console.log('[App] IB Sync Scheduler stopped')
+  if (unlistenSync) {
+    unlistenSync()
+    unlistenSync = null
   }
 }
 
-export function getIBSyncScheduler() {
-  return schedulerInstance
+/**
+ * Migre vers le planificateur Rust les identifiants Flex d'anciennes versions : le token part
+ * dans le magasin de secrets, le query id dans la section "sync" de settings.json (incluse dans
+ * les sauvegardes) s'il n'y en a pas déjà un ; les deux sont ensuite effacés du localStorage
+ */
+export async function pushFlexCredentials() {
+  const legacyToken = localStorage.getItem('flex_token')
+  if (legacyToken) {
+    await invoke('save_secret', { profile: DEFAULT_PROFILE, kind: 'flex_token', value: legacyToken })
+    localStorage.removeItem('flex_token')
+  }
+  const legacyQueryId = parseInt(localStorage.getItem('flex_query_id') || '0')
+  if (!legacyQueryId) return
+  const settings = await invoke('get_sync_settings')
+  if (!settings.query_id) await saveFlexQueryId(legacyQueryId, settings)
+  localStorage.removeItem('flex_query_id')
+}
+
+/** Query id Flex du profil principal (0 si non configuré) */
+export async function loadFlexQueryId() {
+  const settings = await invoke('get_sync_settings')
+  return settings.query_id || 0
+}
+
+export async function saveFlexQueryId(queryId, current = null) {
+  const settings = current || await invoke('get_sync_settings')
+  if (settings.query_id === queryId) return
+  await invoke('set_sync_settings', { settings: { ...settings, query_id: queryId } })
 }
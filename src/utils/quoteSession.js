@@ -0,0 +1,39 @@
+// Séance d'une cotation (MarketQuote côté Rust) : price_field dit d'où vient le prix,
+// market_state dans quelle séance se trouvait le marché au moment de la cotation
+
+const FIELD_LABELS = {
+  pre_market: 'Pré-marché',
+  post_market: 'Post-marché',
+  last: 'Dernier échange',
+  mid: 'Milieu bid/ask',
+};
+
+/** Prix regularMarketPrice : cours en séance, sinon clôture (de la veille en pré-marché) */
+const REGULAR_LABELS = {
+  REGULAR: 'Séance régulière',
+  PRE: 'Clôture veille',
+  POST: 'Clôture du jour',
+  CLOSED: 'Clôture',
+};
+
+/**
+ * Libellé court de la séance utilisée par le prix d'une cotation
+ * @param {{price_field?: string, market_state?: string, stale?: boolean}} quote
+ * @returns {string|null}
+ */
+export function quoteSession(quote) {
+  if (!quote) return null;
+  const label = quote.price_field === 'regular'
+    ? (REGULAR_LABELS[quote.market_state] || 'Séance régulière')
+    : (FIELD_LABELS[quote.price_field] || 'Dernier cours');
+  return quote.stale ? `${label} (périmé)` : label;
+}
+
+/**
+ * Résumé des séances de plusieurs cotations ("Séance régulière + Post-marché")
+ * @param {Iterable<string|null>} labels
+ */
+export function joinSessions(labels) {
+  const unique = [...new Set([...labels].filter(Boolean))];
+  return unique.length ? unique.join(' + ') : null;
+}